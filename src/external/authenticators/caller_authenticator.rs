@@ -0,0 +1,197 @@
+use std::collections::HashMap;
+
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use jsonwebtoken::jwk::{JwkSet, KeyAlgorithm};
+
+use crate::internal::{
+    interfaces::caller_authenticator::CallerAuthenticatorInterface,
+    shared_reconciler_rust_libraries::{
+        common::utils::{app_error, app_error_with_msg},
+        models::entities::app_errors::{AppError, AppErrorKind},
+    },
+};
+
+const AUTHORIZATION_HEADER: &'static str = "authorization";
+const BEARER_TOKEN_PREFIX: &'static str = "Bearer ";
+
+#[derive(Clone, Debug, Default)]
+pub struct AuthSettings {
+    //secret used to check tokens signed with HS256
+    pub jwt_secret: Option<String>,
+
+    //local file holding the JSON Web Key Set used to check tokens signed with any other algorithm
+    pub jwks_file_path: Option<String>,
+
+    pub jwt_issuer: Option<String>,
+
+    pub jwt_audience: Option<String>,
+
+    //the claim in the token that holds the caller's user_id
+    pub user_id_claim: String,
+
+    //header holding the user_id of callers that have already been authenticated by the Dapr sidecar
+    pub trusted_user_id_header: Option<String>,
+}
+
+//works out who the caller is from a JWT bearer token or, failing that, from a header set by a trusted sidecar
+#[derive(Clone)]
+pub struct CallerAuthenticator {
+    settings: AuthSettings,
+    jwks: Option<JwkSet>,
+}
+
+impl CallerAuthenticatorInterface for CallerAuthenticator {
+    fn authenticate_caller(&self, request_headers: &HashMap<String, String>) -> Result<String, AppError> {
+        if let Some(bearer_token) = CallerAuthenticator::get_bearer_token(request_headers) {
+            return self.get_user_id_from_token(bearer_token);
+        }
+
+        let trusted_user_id = self
+            .settings
+            .trusted_user_id_header
+            .as_ref()
+            .and_then(|header_name| request_headers.get(&header_name.to_lowercase()))
+            .filter(|user_id| !user_id.trim().is_empty());
+
+        return match trusted_user_id {
+            Some(user_id) => Ok(user_id.trim().to_string()),
+            None => app_error_with_msg(AppErrorKind::BadClientRequest, "please supply a bearer token to identify the caller"),
+        };
+    }
+}
+
+impl CallerAuthenticator {
+    pub fn new(settings: AuthSettings) -> Result<CallerAuthenticator, AppError> {
+        let jwks = match settings.jwks_file_path.clone() {
+            None => None,
+            Some(jwks_file_path) => {
+                let contents = match std::fs::read_to_string(jwks_file_path) {
+                    Ok(contents) => contents,
+                    Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+                };
+
+                match serde_json::from_str::<JwkSet>(&contents) {
+                    Ok(jwks) => Some(jwks),
+                    Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+                }
+            }
+        };
+
+        return Ok(CallerAuthenticator { settings, jwks });
+    }
+
+    fn get_bearer_token(request_headers: &HashMap<String, String>) -> Option<&str> {
+        return request_headers
+            .get(AUTHORIZATION_HEADER)
+            .and_then(|authorization| authorization.strip_prefix(BEARER_TOKEN_PREFIX))
+            .map(|bearer_token| bearer_token.trim());
+    }
+
+    fn get_user_id_from_token(&self, bearer_token: &str) -> Result<String, AppError> {
+        let token_header = match decode_header(bearer_token) {
+            Ok(token_header) => token_header,
+            Err(e) => { return app_error(AppErrorKind::BadClientRequest, Box::new(e)); }
+        };
+
+        let decoding_key = self.get_decoding_key(token_header.alg, token_header.kid)?;
+
+        let mut validation = Validation::new(token_header.alg);
+        if let Some(jwt_issuer) = &self.settings.jwt_issuer {
+            validation.set_issuer(&[jwt_issuer]);
+        }
+        match &self.settings.jwt_audience {
+            Some(jwt_audience) => validation.set_audience(&[jwt_audience]),
+            None => validation.validate_aud = false,
+        }
+
+        let claims = match decode::<HashMap<String, serde_json::Value>>(bearer_token, &decoding_key, &validation) {
+            Ok(token) => token.claims,
+            Err(e) => { return app_error(AppErrorKind::BadClientRequest, Box::new(e)); }
+        };
+
+        return match claims.get(&self.settings.user_id_claim).and_then(|user_id| user_id.as_str()) {
+            Some(user_id) if !user_id.is_empty() => Ok(user_id.to_string()),
+            _ => app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!("the bearer token does not have a {} claim", self.settings.user_id_claim),
+            ),
+        };
+    }
+
+    //HS256 tokens are checked with the configured secret and every other token with the key
+    //in the JWKS whose id matches the token's. the key decides which algorithms it accepts,
+    //so a token cannot pick a weaker algorithm than the one its key was issued for
+    fn get_decoding_key(&self, algorithm: Algorithm, key_id: Option<String>) -> Result<DecodingKey, AppError> {
+        if algorithm == Algorithm::HS256 {
+            if let Some(jwt_secret) = &self.settings.jwt_secret {
+                return Ok(DecodingKey::from_secret(jwt_secret.as_bytes()));
+            }
+        }
+
+        let jwks = match &self.jwks {
+            Some(jwks) => jwks,
+            None => {
+                return app_error_with_msg(
+                    AppErrorKind::BadClientRequest,
+                    &format!("bearer tokens signed with {:?} are not accepted", algorithm),
+                );
+            }
+        };
+
+        let jwk = match key_id {
+            Some(key_id) => jwks.find(&key_id),
+            None if jwks.keys.len() == 1 => jwks.keys.first(),
+            None => None,
+        };
+
+        let jwk = match jwk {
+            Some(jwk) => jwk,
+            None => {
+                return app_error_with_msg(AppErrorKind::BadClientRequest, "the key that signed the bearer token is not known");
+            }
+        };
+
+        if let Some(key_algorithm) = jwk.common.key_algorithm {
+            match CallerAuthenticator::get_signing_algorithm(key_algorithm) {
+                Some(signing_algorithm) if signing_algorithm == algorithm => {}
+                Some(signing_algorithm) => {
+                    return app_error_with_msg(
+                        AppErrorKind::BadClientRequest,
+                        &format!("the bearer token was signed with {:?} but its key is for {:?}", algorithm, signing_algorithm),
+                    );
+                }
+                None => {
+                    return app_error_with_msg(
+                        AppErrorKind::BadClientRequest,
+                        &format!("the key that signed the bearer token is for {:?}, which is not a signing algorithm", key_algorithm),
+                    );
+                }
+            }
+        }
+
+        return match DecodingKey::from_jwk(jwk) {
+            Ok(decoding_key) => Ok(decoding_key),
+            Err(e) => app_error(AppErrorKind::InternalError, Box::new(e)),
+        };
+    }
+
+    //the algorithm a token signed with a key must have been signed with. keys for
+    //encrypting tokens have no signing algorithm, so no token is accepted for them
+    fn get_signing_algorithm(key_algorithm: KeyAlgorithm) -> Option<Algorithm> {
+        return match key_algorithm {
+            KeyAlgorithm::HS256 => Some(Algorithm::HS256),
+            KeyAlgorithm::HS384 => Some(Algorithm::HS384),
+            KeyAlgorithm::HS512 => Some(Algorithm::HS512),
+            KeyAlgorithm::ES256 => Some(Algorithm::ES256),
+            KeyAlgorithm::ES384 => Some(Algorithm::ES384),
+            KeyAlgorithm::RS256 => Some(Algorithm::RS256),
+            KeyAlgorithm::RS384 => Some(Algorithm::RS384),
+            KeyAlgorithm::RS512 => Some(Algorithm::RS512),
+            KeyAlgorithm::PS256 => Some(Algorithm::PS256),
+            KeyAlgorithm::PS384 => Some(Algorithm::PS384),
+            KeyAlgorithm::PS512 => Some(Algorithm::PS512),
+            KeyAlgorithm::EdDSA => Some(Algorithm::EdDSA),
+            KeyAlgorithm::RSA1_5 | KeyAlgorithm::RSA_OAEP | KeyAlgorithm::RSA_OAEP_256 => None,
+        };
+    }
+}
@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use jsonwebtoken::{encode, EncodingKey, Header};
+use serde_json::json;
+
+use crate::external::authenticators::caller_authenticator::{AuthSettings, CallerAuthenticator};
+use crate::internal::interfaces::caller_authenticator::CallerAuthenticatorInterface;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
+
+const JWT_SECRET: &'static str = "a-secret-that-is-only-used-in-tests";
+
+#[test]
+fn test_bearer_token_signed_with_the_secret_is_accepted() {
+    let sut = CallerAuthenticator::new(get_auth_settings()).unwrap();
+
+    let token = get_token(&Header::default(), JWT_SECRET, json!({"sub": "user@example.com", "exp": get_expiry(3600)}));
+
+    let user_id = sut.authenticate_caller(&get_bearer_token_headers(&token));
+
+    assert_eq!(user_id.unwrap(), "user@example.com");
+}
+
+#[test]
+fn test_bearer_token_with_a_bad_signature_is_rejected() {
+    let sut = CallerAuthenticator::new(get_auth_settings()).unwrap();
+
+    let token = get_token(&Header::default(), "some-other-secret", json!({"sub": "user@example.com", "exp": get_expiry(3600)}));
+
+    let user_id = sut.authenticate_caller(&get_bearer_token_headers(&token));
+
+    assert_eq!(user_id.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_expired_bearer_token_is_rejected() {
+    let sut = CallerAuthenticator::new(get_auth_settings()).unwrap();
+
+    let token = get_token(&Header::default(), JWT_SECRET, json!({"sub": "user@example.com", "exp": get_expiry(-3600)}));
+
+    let user_id = sut.authenticate_caller(&get_bearer_token_headers(&token));
+
+    assert_eq!(user_id.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_bearer_token_for_another_audience_is_rejected() {
+    let sut = CallerAuthenticator::new(AuthSettings {
+        jwt_audience: Some("svc-file-reader-processor".to_string()),
+        ..get_auth_settings()
+    }).unwrap();
+
+    let token = get_token(&Header::default(), JWT_SECRET, json!({"sub": "user@example.com", "aud": "another-service", "exp": get_expiry(3600)}));
+
+    let user_id = sut.authenticate_caller(&get_bearer_token_headers(&token));
+
+    assert_eq!(user_id.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_user_id_is_read_from_the_configured_claim() {
+    let sut = CallerAuthenticator::new(AuthSettings {
+        user_id_claim: "email".to_string(),
+        ..get_auth_settings()
+    }).unwrap();
+
+    let token = get_token(&Header::default(), JWT_SECRET, json!({"sub": "1234", "email": "user@example.com", "exp": get_expiry(3600)}));
+
+    let user_id = sut.authenticate_caller(&get_bearer_token_headers(&token));
+
+    assert_eq!(user_id.unwrap(), "user@example.com");
+}
+
+#[test]
+fn test_bearer_token_signed_with_a_key_in_the_jwks_is_accepted() {
+    let jwks_file_path = std::env::temp_dir().join("svc-file-reader-processor-test-jwks.json");
+    std::fs::write(&jwks_file_path, json!({
+        "keys": [{
+            "kty": "oct",
+            "kid": "KEY-1",
+            "alg": "HS384",
+            "k": "c2lnbmluZy1rZXktZnJvbS10aGUtandrcw",
+        }]
+    }).to_string()).unwrap();
+
+    let sut = CallerAuthenticator::new(AuthSettings {
+        jwt_secret: None,
+        jwks_file_path: Some(jwks_file_path.to_string_lossy().to_string()),
+        ..get_auth_settings()
+    }).unwrap();
+    std::fs::remove_file(&jwks_file_path).unwrap();
+
+    let mut header = Header::new(jsonwebtoken::Algorithm::HS384);
+    header.kid = Some("KEY-1".to_string());
+    let claims = json!({"sub": "user@example.com", "exp": get_expiry(3600)});
+
+    let known_key_token = get_token(&header, "signing-key-from-the-jwks", claims.clone());
+    header.kid = Some("KEY-2".to_string());
+    let unknown_key_token = get_token(&header, "signing-key-from-the-jwks", claims);
+
+    assert_eq!(sut.authenticate_caller(&get_bearer_token_headers(&known_key_token)).unwrap(), "user@example.com");
+    assert_eq!(sut.authenticate_caller(&get_bearer_token_headers(&unknown_key_token)).unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_bearer_token_is_rejected_if_its_key_is_for_another_algorithm() {
+    let jwks_file_path = std::env::temp_dir().join("svc-file-reader-processor-test-jwks-key-algorithms.json");
+    std::fs::write(&jwks_file_path, json!({
+        "keys": [
+            {"kty": "oct", "kid": "HS512-KEY", "alg": "HS512", "k": "c2lnbmluZy1rZXktZnJvbS10aGUtandrcw"},
+            {"kty": "oct", "kid": "ENCRYPTION-KEY", "alg": "RSA-OAEP", "k": "c2lnbmluZy1rZXktZnJvbS10aGUtandrcw"},
+        ]
+    }).to_string()).unwrap();
+
+    let sut = CallerAuthenticator::new(AuthSettings {
+        jwt_secret: None,
+        jwks_file_path: Some(jwks_file_path.to_string_lossy().to_string()),
+        ..get_auth_settings()
+    }).unwrap();
+    std::fs::remove_file(&jwks_file_path).unwrap();
+
+    let mut header = Header::new(jsonwebtoken::Algorithm::HS384);
+    let claims = json!({"sub": "user@example.com", "exp": get_expiry(3600)});
+
+    header.kid = Some("HS512-KEY".to_string());
+    let other_algorithm_token = get_token(&header, "signing-key-from-the-jwks", claims.clone());
+    header.kid = Some("ENCRYPTION-KEY".to_string());
+    let encryption_key_token = get_token(&header, "signing-key-from-the-jwks", claims);
+
+    assert_eq!(sut.authenticate_caller(&get_bearer_token_headers(&other_algorithm_token)).unwrap_err().kind, AppErrorKind::BadClientRequest);
+    assert_eq!(sut.authenticate_caller(&get_bearer_token_headers(&encryption_key_token)).unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_user_id_is_read_from_the_trusted_header_when_there_is_no_bearer_token() {
+    let sut = CallerAuthenticator::new(AuthSettings {
+        trusted_user_id_header: Some("X-User-Id".to_string()),
+        ..get_auth_settings()
+    }).unwrap();
+
+    let request_headers = HashMap::from([("x-user-id".to_string(), "user@example.com".to_string())]);
+
+    let user_id = sut.authenticate_caller(&request_headers);
+
+    assert_eq!(user_id.unwrap(), "user@example.com");
+}
+
+#[test]
+fn test_callers_without_a_bearer_token_or_trusted_header_are_rejected() {
+    let sut = CallerAuthenticator::new(get_auth_settings()).unwrap();
+
+    let request_headers = HashMap::from([("x-user-id".to_string(), "user@example.com".to_string())]);
+
+    let user_id = sut.authenticate_caller(&request_headers);
+
+    assert_eq!(user_id.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+fn get_auth_settings() -> AuthSettings {
+    AuthSettings {
+        jwt_secret: Some(JWT_SECRET.to_string()),
+        jwks_file_path: None,
+        jwt_issuer: None,
+        jwt_audience: None,
+        user_id_claim: "sub".to_string(),
+        trusted_user_id_header: None,
+    }
+}
+
+fn get_token(header: &Header, secret: &str, claims: serde_json::Value) -> String {
+    encode(header, &claims, &EncodingKey::from_secret(secret.as_bytes())).unwrap()
+}
+
+fn get_expiry(seconds_from_now: i64) -> i64 {
+    chrono::Utc::now().timestamp() + seconds_from_now
+}
+
+fn get_bearer_token_headers(token: &str) -> HashMap<String, String> {
+    HashMap::from([("authorization".to_string(), format!("Bearer {}", token))])
+}
@@ -0,0 +1,5 @@
+pub mod caller_authenticator;
+
+#[cfg(test)]
+#[path = "./caller_authenticator_test.rs"]
+mod caller_authenticator_test;
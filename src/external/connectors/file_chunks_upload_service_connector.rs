@@ -1,14 +1,35 @@
 use async_trait::async_trait;
+use sha2::{Digest, Sha256};
 
+use crate::external::connectors::retry_policy::RetryPolicy;
 use crate::internal::{
     interfaces::file_chunks_upload_service_connector::FileChunksUploadHandlerServiceConnectorInterface,
-    shared_reconciler_rust_libraries::models::entities::app_errors::AppError,
+    models::entities::chunk_payload::ChunkPayloadFormat,
+    shared_reconciler_rust_libraries::{
+        common::utils::{app_error, app_error_with_msg},
+        models::entities::app_errors::{AppError, AppErrorKind},
+    },
 };
-use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::interfaces::file_upload_handler_microservice::FileChunksUploadHandlerMicroserviceClientInterface;
 use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::view_models::requests::UploadFileChunkRequest;
 
+//the header the receiving service reads the idempotency key of a chunk from
+pub const IDEMPOTENCY_KEY_HEADER: &'static str = "Idempotency-Key";
+
+//the header the receiving service reads the payload version of a chunk's rows from,
+//so that rows of raw text are marked just like structured ones
+pub const CHUNK_PAYLOAD_VERSION_HEADER: &'static str = "Chunk-Payload-Version";
+
+const UPLOAD_FILE_CHUNK_METHOD: &'static str = "upload-file-chunk";
+
+//sends chunks to the file chunks upload service through the Dapr sidecar's service invocation api.
+//the shared microservice client has no way of setting a header, so the chunks are posted from here
 pub struct FileChunksUploadHandlerServiceConnector {
-    file_upload_chunk_microservice_client: Box<dyn FileChunksUploadHandlerMicroserviceClientInterface>,
+    upload_file_chunk_url: String,
+    retry_policy: RetryPolicy,
+    chunk_payload_format: ChunkPayloadFormat,
+
+    //built once so that every chunk and every retry of it reuses the same connections
+    http_client: reqwest::Client,
 }
 
 #[async_trait]
@@ -17,15 +38,83 @@ impl FileChunksUploadHandlerServiceConnectorInterface for FileChunksUploadHandle
         &self,
         file_upload_chunk: &UploadFileChunkRequest,
     ) -> Result<(), AppError> {
-        let _ = self.file_upload_chunk_microservice_client.upload_file_chunk(file_upload_chunk).await?;
-        return Ok(());
+        let idempotency_key = get_idempotency_key(&file_upload_chunk.upload_request_id, file_upload_chunk.chunk_sequence_number);
+        let idempotency_key = &idempotency_key;
+
+        return self.retry_policy.run(|attempt| async move {
+            let upload_result = self.send_file_chunk(file_upload_chunk, idempotency_key).await;
+
+            if let Err(e) = &upload_result {
+                log::warn!(
+                    "attempt {} to upload file chunk with idempotency key {} failed: {}",
+                    attempt, idempotency_key, e
+                );
+            }
+
+            upload_result
+        }).await;
     }
 }
 
 impl FileChunksUploadHandlerServiceConnector {
-    pub(crate) fn new(microservice_client: Box<dyn FileChunksUploadHandlerMicroserviceClientInterface>) -> FileChunksUploadHandlerServiceConnector {
+    pub(crate) fn new(
+        dapr_connection_url: &str,
+        file_chunks_service_app_id: &str,
+        retry_policy: RetryPolicy,
+        chunk_payload_format: ChunkPayloadFormat,
+    ) -> FileChunksUploadHandlerServiceConnector {
         return FileChunksUploadHandlerServiceConnector {
-            file_upload_chunk_microservice_client: microservice_client
+            upload_file_chunk_url: format!(
+                "{}/v1.0/invoke/{}/method/{}",
+                dapr_connection_url.trim_end_matches('/'),
+                file_chunks_service_app_id,
+                UPLOAD_FILE_CHUNK_METHOD
+            ),
+            retry_policy,
+            chunk_payload_format,
+            http_client: reqwest::Client::new(),
+        };
+    }
+
+    //rejected chunks are not retried, chunks that could not be delivered are
+    async fn send_file_chunk(&self, file_upload_chunk: &UploadFileChunkRequest, idempotency_key: &str) -> Result<(), AppError> {
+        let response = match self.http_client
+            .post(&self.upload_file_chunk_url)
+            .header(IDEMPOTENCY_KEY_HEADER, idempotency_key)
+            .header(CHUNK_PAYLOAD_VERSION_HEADER, self.chunk_payload_format.get_version())
+            .json(file_upload_chunk)
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
         };
+
+        if response.status().is_client_error() {
+            return app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!("the file chunks upload service rejected the chunk with status {}", response.status()),
+            );
+        }
+
+        if !response.status().is_success() {
+            return app_error_with_msg(
+                AppErrorKind::InternalError,
+                &format!("failed to upload the file chunk, the file chunks upload service returned status {}", response.status()),
+            );
+        }
+
+        return Ok(());
     }
 }
+
+/**
+derives the key that identifies a chunk no matter how many times it is sent,
+so that the receiving service can tell a retried chunk apart from a new one
+
+the key is the same for every attempt at uploading a chunk and is sent in the Idempotency-Key header
+ */
+pub fn get_idempotency_key(upload_request_id: &str, chunk_sequence_number: i64) -> String {
+    let chunk_identity = format!("{}:{}", upload_request_id, chunk_sequence_number);
+    return hex::encode(Sha256::digest(chunk_identity.as_bytes()));
+}
@@ -0,0 +1,115 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+use crate::external::connectors::file_chunks_upload_service_connector::{get_idempotency_key, FileChunksUploadHandlerServiceConnector};
+use crate::external::connectors::retry_policy::RetryPolicy;
+use crate::internal::interfaces::file_chunks_upload_service_connector::FileChunksUploadHandlerServiceConnectorInterface;
+use crate::internal::models::entities::chunk_payload::ChunkPayloadFormat;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file_upload_chunk::FileUploadChunkSource;
+use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::view_models::requests::UploadFileChunkRequest;
+
+fn get_dummy_file_chunk() -> UploadFileChunkRequest {
+    UploadFileChunkRequest {
+        upload_request_id: "RECON-TASK-1234".to_string(),
+        chunk_sequence_number: 7,
+        chunk_source: FileUploadChunkSource::PrimaryFileChunk,
+        chunk_rows: vec![],
+        is_last_chunk: false,
+    }
+}
+
+fn get_dummy_retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(4),
+        retryable_error_kinds: vec![AppErrorKind::InternalError],
+    }
+}
+
+//answers each request it receives with the next of the given status codes,
+//keeping the request line and headers of every request
+async fn start_file_chunks_upload_service(status_codes: Vec<u16>) -> (String, Arc<Mutex<Vec<String>>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let service_url = format!("http://{}", listener.local_addr().unwrap());
+    let received_requests = Arc::new(Mutex::new(vec![]));
+
+    let requests = received_requests.clone();
+    tokio::spawn(async move {
+        for status_code in status_codes {
+            let (mut connection, _) = listener.accept().await.unwrap();
+
+            let mut request = vec![];
+            let mut buffer = [0u8; 1024];
+            while !String::from_utf8_lossy(&request).contains("\r\n\r\n") {
+                let bytes_read = connection.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..bytes_read]);
+            }
+
+            let request = String::from_utf8_lossy(&request).to_string();
+            let request_head = request.split("\r\n\r\n").next().unwrap().to_string();
+            requests.lock().unwrap().push(request_head);
+
+            let response = format!("HTTP/1.1 {} STATUS\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", status_code);
+            connection.write_all(response.as_bytes()).await.unwrap();
+        }
+    });
+
+    return (service_url, received_requests);
+}
+
+#[test]
+fn test_the_idempotency_key_of_a_chunk_is_sent_with_every_attempt_at_uploading_it() {
+    let (result, received_requests) = tokio_test::block_on(async {
+        let (service_url, received_requests) = start_file_chunks_upload_service(vec![503, 200]).await;
+        let connector = FileChunksUploadHandlerServiceConnector::new(&service_url, "svc-file-chunks-upload-manager", get_dummy_retry_policy(), ChunkPayloadFormat::RawDataV1);
+
+        let result = connector.upload_file_chunk(&get_dummy_file_chunk()).await;
+        (result, received_requests)
+    });
+
+    assert!(result.is_ok());
+
+    let received_requests = received_requests.lock().unwrap();
+    let expected_header = format!("idempotency-key: {}", get_idempotency_key("RECON-TASK-1234", 7));
+    assert_eq!(received_requests.len(), 2);
+    for request in received_requests.iter() {
+        assert!(request.starts_with("POST /v1.0/invoke/svc-file-chunks-upload-manager/method/upload-file-chunk "));
+        assert!(request.to_lowercase().contains(&expected_header));
+    }
+}
+
+#[test]
+fn test_chunks_rejected_by_the_upload_service_are_not_retried() {
+    let (result, received_requests) = tokio_test::block_on(async {
+        let (service_url, received_requests) = start_file_chunks_upload_service(vec![400, 200]).await;
+        let connector = FileChunksUploadHandlerServiceConnector::new(&service_url, "svc-file-chunks-upload-manager", get_dummy_retry_policy(), ChunkPayloadFormat::RawDataV1);
+
+        let result = connector.upload_file_chunk(&get_dummy_file_chunk()).await;
+        (result, received_requests)
+    });
+
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::BadClientRequest);
+    assert_eq!(received_requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_the_payload_version_is_sent_with_every_chunk_including_raw_text_ones() {
+    let received_requests = tokio_test::block_on(async {
+        let (service_url, received_requests) = start_file_chunks_upload_service(vec![200, 200]).await;
+
+        for chunk_payload_format in [ChunkPayloadFormat::RawDataV1, ChunkPayloadFormat::StructuredCellsV2] {
+            let connector = FileChunksUploadHandlerServiceConnector::new(&service_url, "svc-file-chunks-upload-manager", get_dummy_retry_policy(), chunk_payload_format);
+            connector.upload_file_chunk(&get_dummy_file_chunk()).await.unwrap();
+        }
+        received_requests
+    });
+
+    let received_requests = received_requests.lock().unwrap();
+    assert!(received_requests[0].to_lowercase().contains("chunk-payload-version: 1"));
+    assert!(received_requests[1].to_lowercase().contains("chunk-payload-version: 2"));
+}
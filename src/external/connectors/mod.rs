@@ -1,2 +1,11 @@
 pub mod file_chunks_upload_service_connector;
 pub mod recon_tasks_service_connector;
+pub mod retry_policy;
+
+#[cfg(test)]
+#[path = "./retry_policy_test.rs"]
+mod retry_policy_test;
+
+#[cfg(test)]
+#[path = "./file_chunks_upload_service_connector_test.rs"]
+mod file_chunks_upload_service_connector_test;
@@ -1,33 +1,54 @@
 use async_trait::async_trait;
+use serde::Deserialize;
 
 use crate::internal::{
     interfaces::recon_tasks_service_connector::ReconTasksServiceConnectorInterface,
-    shared_reconciler_rust_libraries::models::entities::{
-        app_errors::AppError,
-        file::FileThatHasBeenRead,
+    models::entities::file::FileThatHasBeenRead,
+    shared_reconciler_rust_libraries::{
+        common::utils::{app_error, app_error_with_msg},
+        models::entities::app_errors::{AppError, AppErrorKind},
     },
 };
 use crate::internal::shared_reconciler_rust_libraries::models::entities::file::FileMetadata;
 use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::{ComparisonPair, ReconciliationConfigs, ReconFileType};
 use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::interfaces::recon_tasks_microservice::ReconTasksMicroserviceClientInterface;
 use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::view_models::requests::{AttachComparisonFileRequest, AttachPrimaryFileRequest, CreateReconTaskRequest};
+use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::view_models::responses::ReconTaskResponseDetails;
+
+const RECON_TASKS_METHOD: &'static str = "recon-tasks";
+
+//the part of a recon task returned by the recon tasks service that is needed to check the second file against it
+#[derive(Deserialize, Debug)]
+struct ReconTaskComparisonPairsResponse {
+    task_details: ReconTaskComparisonPairs,
+}
+
+#[derive(Deserialize, Debug)]
+struct ReconTaskComparisonPairs {
+    comparison_pairs: Vec<ComparisonPair>,
+}
 
 pub struct ReconTasksServiceConnector {
     recon_tasks_microservice_client: Box<dyn ReconTasksMicroserviceClientInterface>,
+
+    //the shared microservice client cannot search or get recon tasks, so they are searched
+    //and fetched through the Dapr sidecar's service invocation api at this url
+    recon_tasks_url: String,
+    http_client: reqwest::Client,
 }
 
 #[async_trait]
 impl ReconTasksServiceConnectorInterface for ReconTasksServiceConnector {
-    async fn create_recon_task(&self, _file: &FileThatHasBeenRead) -> Result<String, AppError> {
+    async fn create_recon_task(
+        &self,
+        file: &FileThatHasBeenRead,
+        user_id: &str,
+        recon_configurations: &ReconciliationConfigs,
+    ) -> Result<String, AppError> {
         let request = CreateReconTaskRequest {
-            user_id: Self::get_user_id(),
-            recon_configurations: ReconciliationConfigs {
-                should_check_for_duplicate_records_in_comparison_file: false,
-                should_reconciliation_be_case_sensitive: false,
-                should_ignore_white_space: false,
-                should_do_reverse_reconciliation: false,
-            },
-            comparison_pairs: Self::get_comparison_pairs(_file.clone().file_metadata),
+            user_id: user_id.to_string(),
+            recon_configurations: recon_configurations.clone(),
+            comparison_pairs: Self::get_comparison_pairs(file.clone().file_metadata),
         };
 
         let result = self.recon_tasks_microservice_client.create_recon_task(&request).await?;
@@ -42,7 +63,7 @@ impl ReconTasksServiceConnectorInterface for ReconTasksServiceConnector {
             task_id: Self::get_recon_task_id(&file.clone()),
             primary_file_name: Self::get_file_name(&file.clone()),
             primary_file_hash: Self::get_file_hash(&file.clone()),
-            primary_file_row_count: file.file_rows.len() as u64,
+            primary_file_row_count: file.row_count,
             primary_file_headers: file.column_headers.clone(),
             primary_file_delimiters: Self::get_column_delimiters(file.file_metadata.clone()),
         };
@@ -59,7 +80,7 @@ impl ReconTasksServiceConnectorInterface for ReconTasksServiceConnector {
             task_id: Self::get_recon_task_id(&file.clone()),
             comparison_file_name: Self::get_file_name(&file.clone()),
             comparison_file_hash: Self::get_file_hash(&file.clone()),
-            comparison_file_row_count: file.file_rows.len() as u64,
+            comparison_file_row_count: file.row_count,
             comparison_file_headers: file.column_headers.clone(),
             comparison_file_delimiters: Self::get_column_delimiters(file.file_metadata.clone()),
         };
@@ -67,12 +88,77 @@ impl ReconTasksServiceConnectorInterface for ReconTasksServiceConnector {
         let result = self.recon_tasks_microservice_client.attach_comparison_file_to_task(&request).await?;
         return Ok(result.task_id);
     }
+
+    async fn find_active_recon_task_with_file(&self, file_hash: &str) -> Result<Option<String>, AppError> {
+        let response = match self.http_client
+            .get(&self.recon_tasks_url)
+            .query(&[("file_hash", file_hash), ("is_done", "false")])
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+        };
+
+        if !response.status().is_success() {
+            return app_error_with_msg(
+                AppErrorKind::InternalError,
+                &format!("failed to search recon tasks, the recon tasks service returned status {}", response.status()),
+            );
+        }
+
+        return match response.json::<Vec<ReconTaskResponseDetails>>().await {
+            Ok(recon_tasks) => Ok(recon_tasks.into_iter().next().map(|recon_task| recon_task.task_id)),
+            Err(e) => app_error(AppErrorKind::InternalError, Box::new(e)),
+        };
+    }
+
+    async fn get_recon_task_comparison_pairs(&self, recon_task_id: &str) -> Result<Vec<ComparisonPair>, AppError> {
+        let response = match self.http_client
+            .get(format!("{}/{}", self.recon_tasks_url, recon_task_id))
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+        };
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return app_error_with_msg(
+                AppErrorKind::NotFound,
+                &format!("no recon task was found for upload_request_id {}", recon_task_id),
+            );
+        }
+
+        if !response.status().is_success() {
+            return app_error_with_msg(
+                AppErrorKind::InternalError,
+                &format!("failed to get recon task {}, the recon tasks service returned status {}", recon_task_id, response.status()),
+            );
+        }
+
+        return match response.json::<ReconTaskComparisonPairsResponse>().await {
+            Ok(recon_task) => Ok(recon_task.task_details.comparison_pairs),
+            Err(e) => app_error(AppErrorKind::InternalError, Box::new(e)),
+        };
+    }
 }
 
 impl ReconTasksServiceConnector {
-    pub(crate) fn new(recon_tasks_microservice_client: Box<dyn ReconTasksMicroserviceClientInterface>) -> ReconTasksServiceConnector {
+    pub(crate) fn new(
+        recon_tasks_microservice_client: Box<dyn ReconTasksMicroserviceClientInterface>,
+        dapr_connection_url: &str,
+        recon_tasks_service_app_id: &str,
+    ) -> ReconTasksServiceConnector {
         return ReconTasksServiceConnector {
-            recon_tasks_microservice_client
+            recon_tasks_microservice_client,
+            recon_tasks_url: format!(
+                "{}/v1.0/invoke/{}/method/{}",
+                dapr_connection_url.trim_end_matches('/'),
+                recon_tasks_service_app_id,
+                RECON_TASKS_METHOD
+            ),
+            http_client: reqwest::Client::new(),
         };
     }
 
@@ -95,14 +181,7 @@ impl ReconTasksServiceConnector {
     }
 
     fn get_file_hash(file: &FileThatHasBeenRead) -> String {
-        return match file.clone().file_type {
-            ReconFileType::PrimaryFile => {
-                format!("PRIMARY-FILE-{}", file.clone().upload_request_id.unwrap_or("".to_string()))
-            }
-            ReconFileType::ComparisonFile => {
-                format!("COMPARISON-FILE-{}", file.clone().upload_request_id.unwrap_or("".to_string()))
-            }
-        };
+        return file.file_hash.clone();
     }
 
     fn get_column_delimiters(file_metadata: Option<FileMetadata>) -> Vec<char> {
@@ -142,8 +221,4 @@ impl ReconTasksServiceConnector {
             }
         };
     }
-
-    fn get_user_id() -> String {
-        "nkasozi@gmail.com".to_string()
-    }
 }
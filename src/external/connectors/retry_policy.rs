@@ -0,0 +1,75 @@
+use std::future::Future;
+use std::time::Duration;
+
+use rand::Rng;
+
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{AppError, AppErrorKind};
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_INITIAL_BACKOFF_MILLIS: u64 = 200;
+const DEFAULT_MAX_BACKOFF_MILLIS: u64 = 5_000;
+
+//how a call to another service is retried when it fails with an error that may go away on its own
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    //includes the first attempt, so 1 means the call is never retried
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub retryable_error_kinds: Vec<AppErrorKind>,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        return RetryPolicy {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_backoff: Duration::from_millis(DEFAULT_INITIAL_BACKOFF_MILLIS),
+            max_backoff: Duration::from_millis(DEFAULT_MAX_BACKOFF_MILLIS),
+            retryable_error_kinds: vec![AppErrorKind::InternalError],
+        };
+    }
+}
+
+impl RetryPolicy {
+    /**
+    runs the operation until it succeeds, fails with an error that is not retryable
+    or has been attempted max_attempts times
+
+    the operation is given the number of the attempt being made, starting from 1
+     */
+    pub async fn run<T, F, Fut>(&self, mut operation: F) -> Result<T, AppError>
+        where F: FnMut(u32) -> Fut,
+              Fut: Future<Output=Result<T, AppError>>
+    {
+        let mut attempt = 1;
+
+        loop {
+            let error = match operation(attempt).await {
+                Ok(result) => return Ok(result),
+                Err(e) => e,
+            };
+
+            if attempt >= self.max_attempts || !self.retryable_error_kinds.contains(&error.kind) {
+                return Err(error);
+            }
+
+            tokio::time::sleep(self.get_backoff(attempt)).await;
+            attempt = attempt + 1;
+        }
+    }
+
+    //exponential backoff with equal jitter: the wait after an attempt is at least half of
+    //initial_backoff x 2^(attempt - 1), capped at max_backoff, plus a random part of up to the other half.
+    //the jitter keeps concurrent uploads that failed together from being retried in lock step
+    pub(crate) fn get_backoff(&self, attempt: u32) -> Duration {
+        let exponential_backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff);
+
+        let half_of_backoff = exponential_backoff / 2;
+        let jitter = rand::thread_rng().gen_range(0..=half_of_backoff.as_millis() as u64);
+
+        return half_of_backoff + Duration::from_millis(jitter);
+    }
+}
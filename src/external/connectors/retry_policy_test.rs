@@ -0,0 +1,99 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::external::connectors::file_chunks_upload_service_connector::get_idempotency_key;
+use crate::external::connectors::retry_policy::RetryPolicy;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{AppError, AppErrorKind};
+
+fn get_dummy_retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        max_attempts: 3,
+        initial_backoff: Duration::from_millis(1),
+        max_backoff: Duration::from_millis(4),
+        retryable_error_kinds: vec![AppErrorKind::InternalError],
+    }
+}
+
+//fails with the given errors one after the other then succeeds
+fn run_policy_against_errors(retry_policy: &RetryPolicy, errors: Vec<AppErrorKind>) -> (Result<u32, AppError>, Vec<u32>) {
+    let attempts_made = Arc::new(Mutex::new(vec![]));
+
+    let result = tokio_test::block_on(retry_policy.run(|attempt| {
+        let attempts_made = attempts_made.clone();
+        let error = errors.get(attempt as usize - 1).cloned();
+
+        async move {
+            attempts_made.lock().unwrap().push(attempt);
+            match error {
+                Some(kind) => Err(AppError::new(kind, "error occurred".to_string())),
+                None => Ok(attempt),
+            }
+        }
+    }));
+
+    let attempts_made = attempts_made.lock().unwrap().clone();
+    return (result, attempts_made);
+}
+
+#[test]
+fn test_retryable_errors_are_retried_until_the_operation_succeeds() {
+    let (result, attempts_made) = run_policy_against_errors(
+        &get_dummy_retry_policy(),
+        vec![AppErrorKind::InternalError, AppErrorKind::InternalError],
+    );
+
+    assert_eq!(result, Ok(3));
+    assert_eq!(attempts_made, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_the_last_error_is_returned_once_max_attempts_is_reached() {
+    let (result, attempts_made) = run_policy_against_errors(
+        &get_dummy_retry_policy(),
+        vec![AppErrorKind::InternalError; 5],
+    );
+
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::InternalError);
+    assert_eq!(attempts_made, vec![1, 2, 3]);
+}
+
+#[test]
+fn test_errors_that_are_not_retryable_are_returned_straight_away() {
+    let (result, attempts_made) = run_policy_against_errors(
+        &get_dummy_retry_policy(),
+        vec![AppErrorKind::BadClientRequest],
+    );
+
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::BadClientRequest);
+    assert_eq!(attempts_made, vec![1]);
+}
+
+#[test]
+fn test_backoff_grows_exponentially_with_jitter_and_is_capped() {
+    let retry_policy = RetryPolicy {
+        initial_backoff: Duration::from_millis(100),
+        max_backoff: Duration::from_millis(1_000),
+        ..get_dummy_retry_policy()
+    };
+
+    for _ in 0..50 {
+        let first_backoff = retry_policy.get_backoff(1);
+        assert!(first_backoff >= Duration::from_millis(50) && first_backoff <= Duration::from_millis(100));
+
+        let third_backoff = retry_policy.get_backoff(3);
+        assert!(third_backoff >= Duration::from_millis(200) && third_backoff <= Duration::from_millis(400));
+
+        let capped_backoff = retry_policy.get_backoff(30);
+        assert!(capped_backoff >= Duration::from_millis(500) && capped_backoff <= Duration::from_millis(1_000));
+    }
+}
+
+#[test]
+fn test_idempotency_key_is_the_same_for_every_attempt_at_a_chunk() {
+    let key = get_idempotency_key("RECON-TASK-1234", 7);
+
+    assert_eq!(key, get_idempotency_key("RECON-TASK-1234", 7));
+    assert_ne!(key, get_idempotency_key("RECON-TASK-1234", 8));
+    assert_ne!(key, get_idempotency_key("RECON-TASK-5678", 7));
+    assert_eq!(key.len(), 64);
+}
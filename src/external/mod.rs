@@ -1,2 +1,5 @@
+pub mod authenticators;
 pub mod connectors;
 pub mod readers;
+pub mod retrievers;
+pub mod stores;
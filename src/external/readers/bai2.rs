@@ -0,0 +1,491 @@
+use crate::external::readers::common::{convert_amount_to_cell_value, convert_yymmdd_date, get_column_delimiter, get_column_headers, read_text_file};
+use crate::external::readers::parsed_files::ParsedFile;
+use crate::internal::models::entities::{
+    file_read_options::FileReadOptions,
+    file_row::{CellValue, FileRow},
+    statement_summary::{Balance, StatementBalances, StatementSummary},
+};
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error_with_msg;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+//the columns of every row read from a bai2 file, in order
+const BAI2_COLUMN_HEADERS: [&str; 13] = [
+    "as_of_date",
+    "account_number",
+    "currency",
+    "type_code",
+    "debit_credit_mark",
+    "amount",
+    "funds_type",
+    "value_date",
+    "bank_reference",
+    "customer_reference",
+    "text",
+    "originator_id",
+    "ultimate_receiver_id",
+];
+
+//bai2 amounts have no decimal point, they are in the smallest unit of the currency.
+//these currencies have no minor unit or three decimals, every other currency is taken to have two decimals
+const ZERO_DECIMAL_CURRENCIES: [&str; 6] = ["JPY", "KRW", "CLP", "ISK", "VND", "XOF"];
+const THREE_DECIMAL_CURRENCIES: [&str; 7] = ["BHD", "IQD", "JOD", "KWD", "LYD", "OMR", "TND"];
+
+//the currency of a group or account that does not name one
+const DEFAULT_CURRENCY: &str = "USD";
+
+//a record with its 88 continuation records, each physical record without its record code and trailing /
+#[derive(Clone, PartialEq, Debug)]
+struct Bai2Record {
+    record_code: String,
+    parts: Vec<String>,
+    line_number: usize,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct Bai2Transaction {
+    pub as_of_date: String,
+    pub account_number: String,
+    pub currency: String,
+    pub type_code: String,
+    pub amount: i64,
+    pub funds_type: String,
+    pub value_date: Option<String>,
+    pub bank_reference: String,
+    pub customer_reference: String,
+    pub text: String,
+    pub originator_id: String,
+    pub ultimate_receiver_id: String,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct Bai2File {
+    pub transactions: Vec<Bai2Transaction>,
+    pub accounts: Vec<StatementBalances>,
+}
+
+//what has been added up so far for a file, group or account, to check against its trailer
+#[derive(Clone, PartialEq, Debug, Default)]
+struct Bai2Totals {
+    control_total: i128,
+    record_count: u64,
+    item_count: u64,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+struct Bai2Group {
+    ultimate_receiver_id: String,
+    originator_id: String,
+    as_of_date: String,
+    currency: String,
+    totals: Bai2Totals,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+struct Bai2Account {
+    account_number: String,
+    currency: String,
+    totals: Bai2Totals,
+}
+
+pub struct Bai2FileReader {}
+
+impl Bai2FileReader {
+    //the control totals are only known at the end of the file, so the whole file is
+    //parsed and checked before any of its rows are read
+    pub fn read_file(file: &File, _read_options: &FileReadOptions) -> Result<ParsedFile, AppError> {
+        let text = read_text_file(file, "bai2", None)?;
+        let bai2_file = Bai2FileReader::parse_file(&text)?;
+        let column_delimiter = get_column_delimiter(file);
+
+        let file_rows = bai2_file
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(row_index, transaction)| Bai2FileReader::convert_transaction_into_file_row(row_index as u64 + 1, transaction, column_delimiter))
+            .collect::<Result<Vec<FileRow>, AppError>>()?;
+
+        return Ok(ParsedFile {
+            column_headers: get_column_headers(&BAI2_COLUMN_HEADERS),
+            file_rows,
+            statement_summary: Some(StatementSummary { statements: bai2_file.accounts }),
+        });
+    }
+
+    /**
+    parses a bai2 file, checking the control total, record count and group or account count in
+    every account (49), group (98) and file (99) trailer against the records before it
+
+    # Errors
+
+    This function will return an error if a record is malformed or out of place, or if a trailer does not match
+     */
+    pub(crate) fn parse_file(text: &str) -> Result<Bai2File, AppError> {
+        let mut bai2_file = Bai2File::default();
+        let mut file_totals: Option<Bai2Totals> = None;
+        let mut group: Option<Bai2Group> = None;
+        let mut account: Option<Bai2Account> = None;
+        let mut file_id = None;
+        let mut has_file_trailer = false;
+
+        for record in Bai2FileReader::join_continuation_records(text)? {
+            let physical_record_count = record.parts.len() as u64;
+            let fields = record.get_fields();
+
+            if has_file_trailer {
+                return record.error("comes after the 99 file trailer");
+            }
+            if record.record_code != "01" && file_totals.is_none() {
+                return record.error("comes before the 01 file header");
+            }
+
+            for totals in [file_totals.as_mut(), group.as_mut().map(|group| &mut group.totals), account.as_mut().map(|account| &mut account.totals)]
+                .into_iter()
+                .flatten()
+            {
+                totals.record_count = totals.record_count + physical_record_count;
+            }
+
+            match record.record_code.as_str() {
+                "01" => {
+                    if file_totals.is_some() {
+                        return record.error("is a second 01 file header");
+                    }
+                    file_totals = Some(Bai2Totals { record_count: physical_record_count, ..Bai2Totals::default() });
+                    file_id = fields.get(4).cloned().filter(|file_id| !file_id.is_empty());
+                }
+                "02" => {
+                    if group.is_some() {
+                        return record.error("starts a group before the last group's 98 trailer");
+                    }
+                    group = Some(Bai2Group {
+                        ultimate_receiver_id: get_field(&fields, 0),
+                        originator_id: get_field(&fields, 1),
+                        as_of_date: record.parse_date(&get_field(&fields, 3), "an as of date")?,
+                        currency: Some(get_field(&fields, 5)).filter(|currency| !currency.is_empty()).unwrap_or(DEFAULT_CURRENCY.to_string()),
+                        totals: Bai2Totals { record_count: physical_record_count, ..Bai2Totals::default() },
+                    });
+                }
+                "03" => {
+                    let current_group = match group.as_mut() {
+                        Some(current_group) if account.is_none() => current_group,
+                        _ => return record.error("is an account identifier outside of a group or inside another account"),
+                    };
+
+                    let currency = Some(get_field(&fields, 1)).filter(|currency| !currency.is_empty()).unwrap_or(current_group.currency.clone());
+                    let mut statement_balances = StatementBalances {
+                        account_identification: Some(get_field(&fields, 0)),
+                        statement_reference: file_id.clone(),
+                        ..StatementBalances::default()
+                    };
+                    let mut totals = Bai2Totals { record_count: physical_record_count, ..Bai2Totals::default() };
+
+                    //the rest of the record is sets of type code, amount, item count and funds type
+                    let mut field_index = 2;
+                    while field_index < fields.len() && fields[field_index..].iter().any(|field| !field.is_empty()) {
+                        let type_code = get_field(&fields, field_index);
+                        let amount = record.parse_amount(&get_field(&fields, field_index + 1))?;
+                        totals.control_total = totals.control_total + amount as i128;
+
+                        let balance = Balance {
+                            debit_credit_mark: if amount < 0 { String::from("D") } else { String::from("C") },
+                            date: current_group.as_of_date.clone(),
+                            currency: currency.clone(),
+                            amount: convert_amount(amount.unsigned_abs(), &currency),
+                        };
+                        match type_code.as_str() {
+                            "010" => statement_balances.opening_balance = Some(balance),
+                            "015" => statement_balances.closing_balance = Some(balance),
+                            "045" => statement_balances.closing_available_balance = Some(balance),
+                            _ => {}
+                        }
+
+                        let (_, funds_type_field_count) = record.parse_funds_type(&fields, field_index + 3)?;
+                        field_index = field_index + 3 + funds_type_field_count;
+                    }
+
+                    bai2_file.accounts.push(statement_balances);
+                    account = Some(Bai2Account {
+                        account_number: get_field(&fields, 0),
+                        currency,
+                        totals,
+                    });
+                }
+                "16" => {
+                    let (current_group, current_account) = match (group.as_ref(), account.as_mut()) {
+                        (Some(current_group), Some(current_account)) => (current_group, current_account),
+                        _ => return record.error("is a transaction detail outside of an account"),
+                    };
+
+                    let transaction = record.parse_transaction_detail(current_group, current_account)?;
+                    current_account.totals.control_total = current_account.totals.control_total + transaction.amount as i128;
+                    bai2_file.transactions.push(transaction);
+                }
+                "49" => {
+                    let current_account = match account.take() {
+                        Some(current_account) => current_account,
+                        None => return record.error("is an account trailer without an account"),
+                    };
+                    record.check_trailer_field(&fields, 0, "control total", current_account.totals.control_total)?;
+                    record.check_trailer_field(&fields, 1, "number of records", current_account.totals.record_count as i128)?;
+
+                    if let Some(current_group) = group.as_mut() {
+                        current_group.totals.control_total = current_group.totals.control_total + current_account.totals.control_total;
+                        current_group.totals.item_count = current_group.totals.item_count + 1;
+                    }
+                }
+                "98" => {
+                    if account.is_some() {
+                        return record.error("ends a group before the last account's 49 trailer");
+                    }
+                    let current_group = match group.take() {
+                        Some(current_group) => current_group,
+                        None => return record.error("is a group trailer without a group"),
+                    };
+                    record.check_trailer_field(&fields, 0, "control total", current_group.totals.control_total)?;
+                    record.check_trailer_field(&fields, 1, "number of accounts", current_group.totals.item_count as i128)?;
+                    record.check_trailer_field(&fields, 2, "number of records", current_group.totals.record_count as i128)?;
+
+                    if let Some(totals) = file_totals.as_mut() {
+                        totals.control_total = totals.control_total + current_group.totals.control_total;
+                        totals.item_count = totals.item_count + 1;
+                    }
+                }
+                "99" => {
+                    if group.is_some() {
+                        return record.error("ends the file before the last group's 98 trailer");
+                    }
+                    let totals = file_totals.clone().unwrap_or_default();
+                    record.check_trailer_field(&fields, 0, "control total", totals.control_total)?;
+                    record.check_trailer_field(&fields, 1, "number of groups", totals.item_count as i128)?;
+                    record.check_trailer_field(&fields, 2, "number of records", totals.record_count as i128)?;
+                    has_file_trailer = true;
+                }
+                _ => return record.error("has a record code that is not used in BAI2 files"),
+            }
+        }
+
+        if !has_file_trailer {
+            return app_error_with_msg(AppErrorKind::BadClientRequest, "the BAI2 file has no 99 file trailer");
+        }
+
+        return Ok(bai2_file);
+    }
+
+    //an 88 record carries on the record before it
+    fn join_continuation_records(text: &str) -> Result<Vec<Bai2Record>, AppError> {
+        let mut records: Vec<Bai2Record> = vec![];
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (record_code, content) = line.split_once(',').unwrap_or((line, ""));
+            let content = content.strip_suffix('/').unwrap_or(content).to_string();
+
+            if record_code == "88" {
+                match records.last_mut() {
+                    Some(record) => record.parts.push(content),
+                    None => {
+                        return app_error_with_msg(
+                            AppErrorKind::BadClientRequest,
+                            &format!("the 88 continuation record on line {} has no record to continue", line_index + 1),
+                        );
+                    }
+                }
+                continue;
+            }
+
+            records.push(Bai2Record {
+                record_code: record_code.to_string(),
+                parts: vec![content],
+                line_number: line_index + 1,
+            });
+        }
+
+        return Ok(records);
+    }
+
+    fn convert_transaction_into_file_row(row_number: u64, transaction: &Bai2Transaction, column_delimiter: char) -> Result<FileRow, AppError> {
+        let amount = convert_amount(transaction.amount.unsigned_abs(), &transaction.currency);
+        let debit_credit_mark = get_debit_credit_mark(&transaction.type_code);
+
+        let cells = vec![
+            transaction.as_of_date.clone(),
+            transaction.account_number.clone(),
+            transaction.currency.clone(),
+            transaction.type_code.clone(),
+            debit_credit_mark.clone(),
+            amount.clone(),
+            transaction.funds_type.clone(),
+            transaction.value_date.clone().unwrap_or_default(),
+            transaction.bank_reference.clone(),
+            transaction.customer_reference.clone(),
+            transaction.text.clone(),
+            transaction.originator_id.clone(),
+            transaction.ultimate_receiver_id.clone(),
+        ];
+
+        let values = cells
+            .iter()
+            .enumerate()
+            .map(|(column_index, cell)| match BAI2_COLUMN_HEADERS[column_index] {
+                _ if cell.is_empty() => Ok(CellValue::Empty),
+                "as_of_date" | "value_date" => Ok(CellValue::Date(cell.clone())),
+                "amount" => convert_amount_to_cell_value(cell),
+                _ => Ok(CellValue::Text(cell.clone())),
+            })
+            .collect::<Result<Vec<CellValue>, AppError>>()?;
+
+        return Ok(FileRow::from_cells(row_number, cells, column_delimiter).with_values(values));
+    }
+}
+
+impl Bai2Record {
+    fn get_fields(&self) -> Vec<String> {
+        return self.parts.join(",").split(',').map(|field| field.trim().to_string()).collect();
+    }
+
+    fn error<T>(&self, problem: &str) -> Result<T, AppError> {
+        return app_error_with_msg(
+            AppErrorKind::BadClientRequest,
+            &format!("the {} record on line {} {}", self.record_code, self.line_number, problem),
+        );
+    }
+
+    //yymmdd to yyyy-mm-dd
+    fn parse_date(&self, date: &str, field_name: &str) -> Result<String, AppError> {
+        return match convert_yymmdd_date(date) {
+            Some(date) => Ok(date),
+            None => self.error(&format!("has {} {} that is not a yymmdd date", field_name, date)),
+        };
+    }
+
+    //amounts can be signed in account identifier records, an empty amount is zero
+    fn parse_amount(&self, amount: &str) -> Result<i64, AppError> {
+        if amount.is_empty() {
+            return Ok(0);
+        }
+        return match amount.trim_start_matches('+').parse::<i64>() {
+            Ok(amount) => Ok(amount),
+            Err(_) => self.error(&format!("has an amount {} that is not a whole number", amount)),
+        };
+    }
+
+    /**
+    funds types V, S and D are followed by fields of their own: a value date and time,
+    three availability amounts, or a count of (days, amount) pairs
+
+    returns the funds type and how many fields it takes up, itself included
+     */
+    fn parse_funds_type(&self, fields: &[String], field_index: usize) -> Result<(String, usize), AppError> {
+        let funds_type = get_field(fields, field_index);
+
+        let field_count = match funds_type.as_str() {
+            "V" => 3,
+            "S" => 4,
+            "D" => {
+                let distribution_count = get_field(fields, field_index + 1);
+                match distribution_count.parse::<usize>() {
+                    Ok(distribution_count) => 2 + distribution_count * 2,
+                    Err(_) => return self.error(&format!("has a distribution count {} that is not a number", distribution_count)),
+                }
+            }
+            _ => 1,
+        };
+
+        return Ok((funds_type, field_count));
+    }
+
+    //16,type code,amount,funds type,[funds type fields],bank reference,customer reference,text
+    fn parse_transaction_detail(&self, group: &Bai2Group, account: &Bai2Account) -> Result<Bai2Transaction, AppError> {
+        let fields = self.get_fields();
+        let (funds_type, funds_type_field_count) = self.parse_funds_type(&fields, 2)?;
+        let references_index = 2 + funds_type_field_count;
+
+        let value_date = match (funds_type.as_str(), get_field(&fields, 3)) {
+            ("V", value_date) if !value_date.is_empty() => Some(self.parse_date(&value_date, "a value date")?),
+            _ => None,
+        };
+
+        return Ok(Bai2Transaction {
+            as_of_date: group.as_of_date.clone(),
+            account_number: account.account_number.clone(),
+            currency: account.currency.clone(),
+            type_code: get_field(&fields, 0),
+            amount: self.parse_amount(&get_field(&fields, 1))?,
+            funds_type,
+            value_date,
+            bank_reference: get_field(&fields, references_index),
+            customer_reference: get_field(&fields, references_index + 1),
+            text: self.get_text_from_field(references_index + 2),
+            originator_id: group.originator_id.clone(),
+            ultimate_receiver_id: group.ultimate_receiver_id.clone(),
+        });
+    }
+
+    //the text is the rest of the record, commas and all. where it carries on
+    //into a continuation record the two parts are joined with a space
+    fn get_text_from_field(&self, field_index: usize) -> String {
+        let mut fields_left_to_skip = field_index;
+        let mut text_parts = vec![];
+
+        for part in &self.parts {
+            if fields_left_to_skip == 0 {
+                text_parts.push(part.trim().to_string());
+                continue;
+            }
+
+            let part_fields: Vec<&str> = part.split(',').collect();
+            if part_fields.len() > fields_left_to_skip {
+                text_parts.push(part_fields[fields_left_to_skip..].join(",").trim().to_string());
+                fields_left_to_skip = 0;
+            } else {
+                fields_left_to_skip = fields_left_to_skip - part_fields.len();
+            }
+        }
+
+        return text_parts.into_iter().filter(|text_part| !text_part.is_empty()).collect::<Vec<String>>().join(" ");
+    }
+
+    fn check_trailer_field(&self, fields: &[String], field_index: usize, field_name: &str, expected_value: i128) -> Result<(), AppError> {
+        let trailer_value = get_field(fields, field_index);
+        return match trailer_value.trim_start_matches('+').parse::<i128>() {
+            Ok(trailer_value) if trailer_value == expected_value => Ok(()),
+            _ => self.error(&format!("has a {} of {} but the records before it add up to {}", field_name, trailer_value, expected_value)),
+        };
+    }
+}
+
+fn get_field(fields: &[String], field_index: usize) -> String {
+    return fields.get(field_index).cloned().unwrap_or_default();
+}
+
+//type codes 100 to 399 are credits and 400 to 699 are debits
+fn get_debit_credit_mark(type_code: &str) -> String {
+    return match type_code.parse::<u32>() {
+        Ok(100..=399) => String::from("C"),
+        Ok(400..=699) => String::from("D"),
+        _ => String::new(),
+    };
+}
+
+fn convert_amount(amount: u64, currency: &str) -> String {
+    let decimals = if ZERO_DECIMAL_CURRENCIES.contains(&currency) {
+        0
+    } else if THREE_DECIMAL_CURRENCIES.contains(&currency) {
+        3
+    } else {
+        2
+    };
+
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let minor_units_per_unit = 10_u64.pow(decimals);
+    return format!("{}.{:0width$}", amount / minor_units_per_unit, amount % minor_units_per_unit, width = decimals as usize);
+}
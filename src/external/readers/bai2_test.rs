@@ -0,0 +1,100 @@
+use crate::external::readers::bai2::Bai2FileReader;
+use crate::external::readers::test_files::get_dummy_file;
+use crate::internal::models::entities::file_format::FileFormat;
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::models::entities::file_row::CellValue;
+use crate::internal::models::entities::statement_summary::Balance;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
+
+const DUMMY_BAI2_FILE: &str = "01,SENDR1,RECVR1,220301,0800,FILE1,,,2/
+02,RECVR1,ORIGIN1,1,220301,0800,USD,2/
+03,0012345678,USD,010,100000,,,015,125050,,/
+16,165,30000,0,BANKREF1,CUSTREF1,Deposit from/
+88,customer 42
+16,475,4950,V,220302,,BANKREF2,CUSTREF2,Cheque, 1001/
+49,260000,5/
+98,260000,1,7/
+99,260000,1,9/
+";
+
+#[test]
+fn test_transaction_details_become_rows_with_their_account_and_group() {
+    let file = get_dummy_file("svc-file-reader-processor-test-bai2.txt", DUMMY_BAI2_FILE);
+    let read_options = FileReadOptions { format: Some(FileFormat::Bai2), ..FileReadOptions::default() };
+
+    let parsed_file = Bai2FileReader::read_file(&file, &read_options).unwrap();
+    let rows = parsed_file.file_rows;
+
+    assert_eq!(parsed_file.column_headers.len(), 13);
+    assert_eq!(parsed_file.statement_summary.unwrap().statements.len(), 1);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].cells, vec![
+        "2022-03-01", "0012345678", "USD", "165", "C", "300.00", "0", "", "BANKREF1", "CUSTREF1",
+        "Deposit from customer 42", "ORIGIN1", "RECVR1",
+    ]);
+    assert_eq!(rows[1].cells[3..11], ["475", "D", "49.50", "V", "2022-03-02", "BANKREF2", "CUSTREF2", "Cheque, 1001"]);
+    assert_eq!(rows[1].values.clone().unwrap()[5], CellValue::Decimal(49.5));
+}
+
+#[test]
+fn test_account_balances_are_read_from_the_account_identifier() {
+    let bai2_file = Bai2FileReader::parse_file(DUMMY_BAI2_FILE).unwrap();
+
+    assert_eq!(bai2_file.accounts.len(), 1);
+    assert_eq!(bai2_file.accounts[0].account_identification, Some(String::from("0012345678")));
+    assert_eq!(bai2_file.accounts[0].statement_reference, Some(String::from("FILE1")));
+    assert_eq!(bai2_file.accounts[0].opening_balance, Some(Balance {
+        debit_credit_mark: String::from("C"),
+        date: String::from("2022-03-01"),
+        currency: String::from("USD"),
+        amount: String::from("1000.00"),
+    }));
+    assert_eq!(bai2_file.accounts[0].closing_balance.clone().unwrap().amount, "1250.50");
+}
+
+#[test]
+fn test_trailers_that_do_not_match_their_records_are_rejected() {
+    let wrong_account_total = Bai2FileReader::parse_file(&DUMMY_BAI2_FILE.replace("49,260000,5/", "49,260001,5/"));
+    let wrong_group_record_count = Bai2FileReader::parse_file(&DUMMY_BAI2_FILE.replace("98,260000,1,7/", "98,260000,1,6/"));
+    let wrong_group_count = Bai2FileReader::parse_file(&DUMMY_BAI2_FILE.replace("99,260000,1,9/", "99,260000,2,9/"));
+    let missing_file_trailer = Bai2FileReader::parse_file(&DUMMY_BAI2_FILE.replace("99,260000,1,9/", ""));
+
+    let wrong_account_total_error = wrong_account_total.unwrap_err();
+    assert_eq!(wrong_account_total_error.kind, AppErrorKind::BadClientRequest);
+    assert_eq!(
+        wrong_account_total_error.message,
+        "the 49 record on line 7 has a control total of 260001 but the records before it add up to 260000"
+    );
+    assert!(wrong_group_record_count.unwrap_err().message.contains("number of records of 6"));
+    assert!(wrong_group_count.unwrap_err().message.contains("number of groups of 2"));
+    assert_eq!(missing_file_trailer.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_records_out_of_place_are_rejected() {
+    let detail_outside_account = Bai2FileReader::parse_file("01,S,R,220301,0800,F,,,2/\n02,R,O,1,220301,,USD,2/\n16,165,100,0,,,text/\n");
+    let orphan_continuation = Bai2FileReader::parse_file("88,more text\n");
+    let second_file_header = Bai2FileReader::parse_file("01,S,R,220301,0800,F,,,2/\n01,S,R,220302,0800,G,,,2/\n");
+
+    assert!(detail_outside_account.unwrap_err().message.contains("16 record on line 3 is a transaction detail outside of an account"));
+    assert_eq!(orphan_continuation.unwrap_err().kind, AppErrorKind::BadClientRequest);
+    assert!(second_file_header.unwrap_err().message.contains("01 record on line 2 is a second 01 file header"));
+}
+
+#[test]
+fn test_dates_that_are_not_six_digits_are_rejected_with_their_line() {
+    let bad_as_of_date = Bai2FileReader::parse_file(&DUMMY_BAI2_FILE.replace("02,RECVR1,ORIGIN1,1,220301", "02,RECVR1,ORIGIN1,1,22\u{0663}301"));
+    let bad_value_date = Bai2FileReader::parse_file(&DUMMY_BAI2_FILE.replace("V,220302", "V,2203"));
+
+    assert_eq!(bad_as_of_date.unwrap_err().message, "the 02 record on line 2 has an as of date 22\u{0663}301 that is not a yymmdd date");
+    assert!(bad_value_date.unwrap_err().message.contains("16 record on line 6 has a value date 2203"));
+}
+
+#[test]
+fn test_amounts_are_written_with_the_decimals_of_their_currency() {
+    let three_decimals = Bai2FileReader::parse_file(&DUMMY_BAI2_FILE.replace("USD", "KWD")).unwrap();
+    let zero_decimals = Bai2FileReader::parse_file(&DUMMY_BAI2_FILE.replace("USD", "JPY")).unwrap();
+
+    assert_eq!(three_decimals.accounts[0].closing_balance.clone().unwrap().amount, "125.050");
+    assert_eq!(zero_decimals.accounts[0].closing_balance.clone().unwrap().amount, "125050");
+}
@@ -0,0 +1,425 @@
+use std::collections::VecDeque;
+use std::io::BufRead;
+
+use quick_xml::events::Event;
+use quick_xml::Reader;
+
+use crate::external::readers::common::{convert_amount_to_cell_value, get_column_delimiter, get_column_headers, get_file_path};
+use crate::external::readers::text_encoding::open_text_file;
+use crate::internal::interfaces::file_reader::FileRowStream;
+use crate::internal::models::entities::{
+    file_read_options::FileReadOptions,
+    file_row::{CellValue, FileRow},
+    statement_summary::{Balance, StatementBalances, StatementSummary},
+};
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error_with_msg;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+//the columns of every row read from a camt.053 or camt.054 document, in order
+const CAMT_COLUMN_HEADERS: [&str; 11] = [
+    "booking_date",
+    "value_date",
+    "amount",
+    "currency",
+    "credit_debit_indicator",
+    "end_to_end_id",
+    "remittance_information",
+    "entry_reference",
+    "account_servicer_reference",
+    "statement_id",
+    "account",
+];
+
+//where the amount is in CAMT_COLUMN_HEADERS
+const AMOUNT_COLUMN_INDEX: usize = 2;
+
+//the elements that wrap the entries of one account: a statement in camt.053,
+//a notification in camt.054 and a report in camt.052
+const CAMT_STATEMENT_ELEMENTS: [&str; 3] = ["Stmt", "Ntfctn", "Rpt"];
+
+pub struct CamtFileReader {}
+
+impl CamtFileReader {
+    pub fn read_column_headers(_file: &File, _read_options: &FileReadOptions) -> Result<Vec<String>, AppError> {
+        return Ok(get_column_headers(&CAMT_COLUMN_HEADERS));
+    }
+
+    pub fn count_file_rows(file: &File, read_options: &FileReadOptions) -> Result<u64, AppError> {
+        let mut row_count = 0;
+        for file_row in CamtFileReader::stream_file_rows(file, read_options)? {
+            let _ = file_row?;
+            row_count = row_count + 1;
+        }
+        return Ok(row_count);
+    }
+
+    //the document is parsed as it is read, an entry at a time
+    pub fn stream_file_rows(file: &File, _read_options: &FileReadOptions) -> Result<FileRowStream, AppError> {
+        let text_file_reader = open_text_file(&get_file_path(file, "camt")?, None)?;
+        let column_delimiter = get_column_delimiter(file);
+        return Ok(Box::new(CamtEntries::new(text_file_reader, column_delimiter)));
+    }
+
+    pub fn read_statement_summary(file: &File, _read_options: &FileReadOptions) -> Result<StatementSummary, AppError> {
+        let text_file_reader = open_text_file(&get_file_path(file, "camt")?, None)?;
+        return CamtFileReader::read_statement_summary_from_document(text_file_reader);
+    }
+
+    //the balances are spread through the document, so all of it is read but none of its entries are kept
+    pub(crate) fn read_statement_summary_from_document<R: BufRead>(reader: R) -> Result<StatementSummary, AppError> {
+        let mut entries = CamtEntries::new(reader, ',');
+        for file_row in entries.by_ref() {
+            let _ = file_row?;
+        }
+        return Ok(StatementSummary { statements: entries.statements });
+    }
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+struct CamtEntry {
+    booking_date: Option<CellValue>,
+    value_date: Option<CellValue>,
+    amount: Option<String>,
+    currency: Option<String>,
+    credit_debit_indicator: Option<String>,
+    entry_reference: Option<String>,
+    account_servicer_reference: Option<String>,
+    additional_information: Option<String>,
+    transactions: Vec<CamtTransaction>,
+}
+
+//the details of one of the transactions batched into an entry,
+//anything a transaction leaves out is taken from its entry
+#[derive(Clone, PartialEq, Debug, Default)]
+struct CamtTransaction {
+    amount: Option<String>,
+    currency: Option<String>,
+    credit_debit_indicator: Option<String>,
+    end_to_end_id: Option<String>,
+    account_servicer_reference: Option<String>,
+    unstructured_remittance_information: Vec<String>,
+    creditor_reference: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+struct CamtBalance {
+    type_code: Option<String>,
+    amount: Option<String>,
+    currency: Option<String>,
+    credit_debit_indicator: Option<String>,
+    date: Option<String>,
+}
+
+/**
+the rows of a camt document, read an entry at a time. an entry becomes a row per transaction
+in its details, or a single row if it has no transaction details. the balances of every statement
+are collected into statements as the document is read
+ */
+struct CamtEntries<R: BufRead> {
+    reader: Reader<R>,
+    buffer: Vec<u8>,
+    column_delimiter: char,
+
+    //the local names of the elements enclosing the current position in the document
+    path: Vec<String>,
+    currency_of_current_amount: Option<String>,
+
+    statement_id: Option<String>,
+    account: Option<String>,
+    entry: Option<CamtEntry>,
+    transaction: Option<CamtTransaction>,
+    balance: Option<CamtBalance>,
+
+    statements: Vec<StatementBalances>,
+    rows_ready: VecDeque<FileRow>,
+    next_row_number: u64,
+    is_finished: bool,
+}
+
+impl<R: BufRead> CamtEntries<R> {
+    fn new(reader: R, column_delimiter: char) -> CamtEntries<R> {
+        let mut reader = Reader::from_reader(reader);
+        reader.trim_text(true);
+
+        return CamtEntries {
+            reader,
+            buffer: vec![],
+            column_delimiter,
+            path: vec![],
+            currency_of_current_amount: None,
+            statement_id: None,
+            account: None,
+            entry: None,
+            transaction: None,
+            balance: None,
+            statements: vec![],
+            rows_ready: VecDeque::new(),
+            next_row_number: 1,
+            is_finished: false,
+        };
+    }
+
+    //reads the document up to the end of the next entry, or to the end of the document
+    fn read_next_entry(&mut self) -> Result<(), AppError> {
+        while self.rows_ready.is_empty() && !self.is_finished {
+            self.buffer.clear();
+            let event = match self.reader.read_event_into(&mut self.buffer) {
+                Ok(event) => event.into_owned(),
+                Err(e) => {
+                    return app_error_with_msg(
+                        AppErrorKind::BadClientRequest,
+                        &format!("the file is not a valid camt document at byte {}: {}", self.reader.buffer_position(), e),
+                    );
+                }
+            };
+
+            match event {
+                Event::Start(element) => {
+                    let name = String::from_utf8_lossy(element.local_name().as_ref()).to_string();
+                    self.currency_of_current_amount = element
+                        .attributes()
+                        .filter_map(|attribute| attribute.ok())
+                        .find(|attribute| attribute.key.local_name().as_ref() == b"Ccy")
+                        .and_then(|attribute| attribute.decode_and_unescape_value(&self.reader).ok().map(|value| value.to_string()));
+                    self.start_element(&name);
+                    self.path.push(name);
+                }
+                Event::End(_) => {
+                    if let Some(name) = self.path.pop() {
+                        self.end_element(&name)?;
+                    }
+                }
+                Event::Text(text) => {
+                    let text = match text.unescape() {
+                        Ok(text) => text.to_string(),
+                        Err(e) => {
+                            return app_error_with_msg(
+                                AppErrorKind::BadClientRequest,
+                                &format!("the file is not a valid camt document at byte {}: {}", self.reader.buffer_position(), e),
+                            );
+                        }
+                    };
+                    self.read_text(text);
+                }
+                Event::CData(text) => self.read_text(String::from_utf8_lossy(&text).to_string()),
+                Event::Eof => self.is_finished = true,
+                _ => {}
+            }
+        }
+
+        return Ok(());
+    }
+
+    fn start_element(&mut self, name: &str) {
+        match name {
+            _ if CAMT_STATEMENT_ELEMENTS.contains(&name) => {
+                self.statement_id = None;
+                self.account = None;
+                self.statements.push(StatementBalances::default());
+            }
+            "Ntry" => self.entry = Some(CamtEntry::default()),
+            "TxDtls" if self.entry.is_some() => self.transaction = Some(CamtTransaction::default()),
+            "Bal" if self.entry.is_none() => self.balance = Some(CamtBalance::default()),
+            _ => {}
+        }
+    }
+
+    fn end_element(&mut self, name: &str) -> Result<(), AppError> {
+        match name {
+            "TxDtls" => {
+                if let (Some(entry), Some(transaction)) = (self.entry.as_mut(), self.transaction.take()) {
+                    entry.transactions.push(transaction);
+                }
+            }
+            "Ntry" => {
+                if let Some(entry) = self.entry.take() {
+                    self.convert_entry_into_file_rows(entry)?;
+                }
+            }
+            "Bal" => {
+                if let Some(balance) = self.balance.take() {
+                    self.add_balance(balance);
+                }
+            }
+            _ => {}
+        }
+
+        return Ok(());
+    }
+
+    fn read_text(&mut self, text: String) {
+        let path = &self.path;
+        let is_at = |path_suffix: &[&str]| path_ends_with(path, path_suffix);
+        let currency = self.currency_of_current_amount.clone();
+
+        if let Some(transaction) = self.transaction.as_mut() {
+            if is_at(&["TxDtls", "Amt"]) || is_at(&["TxDtls", "AmtDtls", "TxAmt", "Amt"]) {
+                transaction.amount = Some(text);
+                transaction.currency = currency;
+            } else if is_at(&["TxDtls", "CdtDbtInd"]) {
+                transaction.credit_debit_indicator = Some(text);
+            } else if is_at(&["TxDtls", "Refs", "EndToEndId"]) {
+                transaction.end_to_end_id = Some(text);
+            } else if is_at(&["TxDtls", "Refs", "AcctSvcrRef"]) {
+                transaction.account_servicer_reference = Some(text);
+            } else if is_at(&["RmtInf", "Ustrd"]) {
+                transaction.unstructured_remittance_information.push(text);
+            } else if is_at(&["RmtInf", "Strd", "CdtrRefInf", "Ref"]) {
+                transaction.creditor_reference = Some(text);
+            }
+            return;
+        }
+
+        if let Some(entry) = self.entry.as_mut() {
+            let date = match path.last().map(|name| name.as_str()) {
+                Some("DtTm") => CellValue::DateTime(text.clone()),
+                _ => CellValue::Date(text.clone()),
+            };
+
+            if is_at(&["Ntry", "BookgDt", "Dt"]) || is_at(&["Ntry", "BookgDt", "DtTm"]) {
+                entry.booking_date = Some(date);
+            } else if is_at(&["Ntry", "ValDt", "Dt"]) || is_at(&["Ntry", "ValDt", "DtTm"]) {
+                entry.value_date = Some(date);
+            } else if is_at(&["Ntry", "Amt"]) {
+                entry.amount = Some(text);
+                entry.currency = currency;
+            } else if is_at(&["Ntry", "CdtDbtInd"]) {
+                entry.credit_debit_indicator = Some(text);
+            } else if is_at(&["Ntry", "NtryRef"]) {
+                entry.entry_reference = Some(text);
+            } else if is_at(&["Ntry", "AcctSvcrRef"]) {
+                entry.account_servicer_reference = Some(text);
+            } else if is_at(&["Ntry", "AddtlNtryInf"]) {
+                entry.additional_information = Some(text);
+            }
+            return;
+        }
+
+        if let Some(balance) = self.balance.as_mut() {
+            if is_at(&["Bal", "Tp", "CdOrPrtry", "Cd"]) {
+                balance.type_code = Some(text);
+            } else if is_at(&["Bal", "Amt"]) {
+                balance.amount = Some(text);
+                balance.currency = currency;
+            } else if is_at(&["Bal", "CdtDbtInd"]) {
+                balance.credit_debit_indicator = Some(text);
+            } else if is_at(&["Bal", "Dt", "Dt"]) || is_at(&["Bal", "Dt", "DtTm"]) {
+                balance.date = Some(text.chars().take(10).collect());
+            }
+            return;
+        }
+
+        let statement = self.statements.last_mut();
+        if CAMT_STATEMENT_ELEMENTS.iter().any(|statement_element| is_at(&[statement_element, "Id"])) {
+            self.statement_id = Some(text.clone());
+            if let Some(statement) = statement {
+                statement.statement_reference = Some(text);
+            }
+        } else if is_at(&["Acct", "Id", "IBAN"]) || is_at(&["Acct", "Id", "Othr", "Id"]) {
+            self.account = Some(text.clone());
+            if let Some(statement) = statement {
+                statement.account_identification = Some(text);
+            }
+        }
+    }
+
+    //opening balances are OPBD, or PRCD (the previous day's closing balance) if there is no OPBD
+    fn add_balance(&mut self, balance: CamtBalance) {
+        let statement = match self.statements.last_mut() {
+            Some(statement) => statement,
+            None => return,
+        };
+
+        let converted_balance = Balance {
+            debit_credit_mark: match balance.credit_debit_indicator.as_deref() {
+                Some("DBIT") => String::from("D"),
+                _ => String::from("C"),
+            },
+            date: balance.date.unwrap_or_default(),
+            currency: balance.currency.unwrap_or_default(),
+            amount: balance.amount.unwrap_or_default(),
+        };
+
+        match balance.type_code.as_deref() {
+            Some("OPBD") => statement.opening_balance = Some(converted_balance),
+            Some("PRCD") if statement.opening_balance.is_none() => statement.opening_balance = Some(converted_balance),
+            Some("CLBD") => statement.closing_balance = Some(converted_balance),
+            Some("CLAV") => statement.closing_available_balance = Some(converted_balance),
+            _ => {}
+        }
+    }
+
+    //the amount of a batched entry is the total of all its transactions, so a transaction in a batch
+    //that leaves out its own amount gets an empty amount rather than the whole batch's
+    fn convert_entry_into_file_rows(&mut self, entry: CamtEntry) -> Result<(), AppError> {
+        let is_batch = entry.transactions.len() > 1;
+        let transactions = if entry.transactions.is_empty() { vec![CamtTransaction::default()] } else { entry.transactions.clone() };
+
+        for transaction in transactions {
+            let remittance_information = if transaction.unstructured_remittance_information.is_empty() {
+                transaction.creditor_reference.clone().or(entry.additional_information.clone())
+            } else {
+                Some(transaction.unstructured_remittance_information.join(" "))
+            };
+            let amount = if is_batch { transaction.amount.clone() } else { transaction.amount.clone().or(entry.amount.clone()) };
+            let text = |text: Option<String>| match text {
+                Some(text) => CellValue::Text(text),
+                None => CellValue::Empty,
+            };
+
+            let values = vec![
+                entry.booking_date.clone().unwrap_or(CellValue::Empty),
+                entry.value_date.clone().unwrap_or(CellValue::Empty),
+                match &amount {
+                    Some(amount) => convert_amount_to_cell_value(amount.trim())?,
+                    None => CellValue::Empty,
+                },
+                text(transaction.currency.clone().or(entry.currency.clone())),
+                text(transaction.credit_debit_indicator.clone().or(entry.credit_debit_indicator.clone())),
+                text(transaction.end_to_end_id.clone()),
+                text(remittance_information),
+                text(entry.entry_reference.clone()),
+                text(transaction.account_servicer_reference.clone().or(entry.account_servicer_reference.clone())),
+                text(self.statement_id.clone()),
+                text(self.account.clone()),
+            ];
+
+            let cells = values
+                .iter()
+                .enumerate()
+                .map(|(column_index, value)| match (column_index, value) {
+                    //the amount keeps the digits it was written with
+                    (AMOUNT_COLUMN_INDEX, _) => amount.clone().unwrap_or_default(),
+                    (_, CellValue::Text(text)) | (_, CellValue::Date(text)) | (_, CellValue::DateTime(text)) => text.clone(),
+                    _ => String::new(),
+                })
+                .collect();
+
+            self.rows_ready.push_back(FileRow::from_cells(self.next_row_number, cells, self.column_delimiter).with_values(values));
+            self.next_row_number = self.next_row_number + 1;
+        }
+
+        return Ok(());
+    }
+}
+
+//whether the elements enclosing the current position end with the ones in path_suffix
+fn path_ends_with(path: &[String], path_suffix: &[&str]) -> bool {
+    return path.len() >= path_suffix.len()
+        && path[path.len() - path_suffix.len()..].iter().zip(path_suffix).all(|(name, expected_name)| name == expected_name);
+}
+
+impl<R: BufRead> Iterator for CamtEntries<R> {
+    type Item = Result<FileRow, AppError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Err(e) = self.read_next_entry() {
+            self.is_finished = true;
+            return Some(Err(e));
+        }
+        return self.rows_ready.pop_front().map(Ok);
+    }
+}
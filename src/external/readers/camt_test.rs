@@ -0,0 +1,151 @@
+use std::io::Cursor;
+
+use crate::external::readers::camt::CamtFileReader;
+use crate::external::readers::test_files::get_dummy_file;
+use crate::internal::models::entities::file_format::FileFormat;
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::models::entities::file_row::CellValue;
+use crate::internal::models::entities::statement_summary::Balance;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
+
+const DUMMY_CAMT_053_DOCUMENT: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<Document xmlns="urn:iso:std:iso:20022:tech:xsd:camt.053.001.08">
+  <BkToCstmrStmt>
+    <Stmt>
+      <Id>STMT-1</Id>
+      <Acct><Id><IBAN>BE68539007547034</IBAN></Id></Acct>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>OPBD</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="EUR">1000.00</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <Dt><Dt>2022-03-01</Dt></Dt>
+      </Bal>
+      <Bal>
+        <Tp><CdOrPrtry><Cd>CLBD</Cd></CdOrPrtry></Tp>
+        <Amt Ccy="EUR">849.50</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <Dt><Dt>2022-03-01</Dt></Dt>
+      </Bal>
+      <Ntry>
+        <NtryRef>E1</NtryRef>
+        <Amt Ccy="EUR">250.50</Amt>
+        <CdtDbtInd>DBIT</CdtDbtInd>
+        <BookgDt><Dt>2022-03-01</Dt></BookgDt>
+        <ValDt><Dt>2022-03-02</Dt></ValDt>
+        <AcctSvcrRef>BANKREF1</AcctSvcrRef>
+        <NtryDtls>
+          <TxDtls>
+            <Refs><EndToEndId>INV-42</EndToEndId></Refs>
+            <Amt Ccy="EUR">200.50</Amt>
+            <RmtInf><Ustrd>Invoice 42</Ustrd><Ustrd>office supplies</Ustrd></RmtInf>
+          </TxDtls>
+          <TxDtls>
+            <Refs><EndToEndId>INV-43</EndToEndId></Refs>
+            <AmtDtls><TxAmt><Amt Ccy="EUR">50.00</Amt></TxAmt></AmtDtls>
+            <RmtInf><Strd><CdtrRefInf><Ref>RF18539007547034</Ref></CdtrRefInf></Strd></RmtInf>
+          </TxDtls>
+        </NtryDtls>
+      </Ntry>
+    </Stmt>
+    <Stmt>
+      <Id>STMT-2</Id>
+      <Acct><Id><Othr><Id>0012345678</Id></Othr></Id></Acct>
+      <Ntry>
+        <Amt Ccy="USD">100</Amt>
+        <CdtDbtInd>CRDT</CdtDbtInd>
+        <BookgDt><DtTm>2022-03-02T10:15:00</DtTm></BookgDt>
+        <AddtlNtryInf>Interest &amp; fees</AddtlNtryInf>
+      </Ntry>
+    </Stmt>
+  </BkToCstmrStmt>
+</Document>"#;
+
+#[test]
+fn test_transaction_details_and_entries_without_them_become_rows() {
+    let file = get_dummy_file("svc-file-reader-processor-test-camt053.xml", DUMMY_CAMT_053_DOCUMENT);
+    let read_options = FileReadOptions { format: Some(FileFormat::Camt), ..FileReadOptions::default() };
+
+    let headers = CamtFileReader::read_column_headers(&file, &read_options).unwrap();
+    let rows: Vec<_> = CamtFileReader::stream_file_rows(&file, &read_options).unwrap().map(|x| x.unwrap()).collect();
+
+    assert_eq!(&headers[..7], ["booking_date", "value_date", "amount", "currency", "credit_debit_indicator", "end_to_end_id", "remittance_information"]);
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].cells, vec![
+        "2022-03-01", "2022-03-02", "200.50", "EUR", "DBIT", "INV-42", "Invoice 42 office supplies",
+        "E1", "BANKREF1", "STMT-1", "BE68539007547034",
+    ]);
+    assert_eq!(rows[1].cells[2..7], ["50.00", "EUR", "DBIT", "INV-43", "RF18539007547034"]);
+    assert_eq!(rows[2].row_number, 3);
+    assert_eq!(rows[2].cells[2..7], ["100", "USD", "CRDT", "", "Interest & fees"]);
+    assert_eq!(rows[2].cells[9..], ["STMT-2", "0012345678"]);
+    assert_eq!(rows[2].values.clone().unwrap()[0], CellValue::DateTime(String::from("2022-03-02T10:15:00")));
+}
+
+#[test]
+fn test_balances_are_read_for_every_statement() {
+    let statement_summary = CamtFileReader::read_statement_summary_from_document(Cursor::new(DUMMY_CAMT_053_DOCUMENT)).unwrap();
+
+    assert_eq!(statement_summary.statements.len(), 2);
+    assert_eq!(statement_summary.statements[0].statement_reference, Some(String::from("STMT-1")));
+    assert_eq!(statement_summary.statements[0].closing_balance, Some(Balance {
+        debit_credit_mark: String::from("C"),
+        date: String::from("2022-03-01"),
+        currency: String::from("EUR"),
+        amount: String::from("849.50"),
+    }));
+    assert_eq!(statement_summary.statements[1].account_identification, Some(String::from("0012345678")));
+    assert_eq!(statement_summary.statements[1].opening_balance, None);
+}
+
+#[test]
+fn test_camt_054_notifications_are_read_like_statements() {
+    let document = r#"<Document><BkToCstmrDbtCdtNtfctn><Ntfctn><Id>NTF-1</Id>
+        <Ntry><Amt Ccy="EUR">10.00</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+        <NtryDtls><TxDtls><Refs><EndToEndId>E2E-1</EndToEndId></Refs></TxDtls></NtryDtls></Ntry>
+        </Ntfctn></BkToCstmrDbtCdtNtfctn></Document>"#;
+
+    let statement_summary = CamtFileReader::read_statement_summary_from_document(Cursor::new(document)).unwrap();
+
+    assert_eq!(statement_summary.statements[0].statement_reference, Some(String::from("NTF-1")));
+}
+
+#[test]
+fn test_transactions_in_a_batch_do_not_get_the_batch_total_as_their_amount() {
+    let document = r#"<Document><BkToCstmrStmt><Stmt><Id>STMT-1</Id>
+        <Ntry><Amt Ccy="EUR">300.00</Amt><CdtDbtInd>CRDT</CdtDbtInd><NtryDtls><Btch><NbOfTxs>2</NbOfTxs></Btch>
+        <TxDtls><Refs><EndToEndId>E2E-1</EndToEndId></Refs></TxDtls>
+        <TxDtls><Refs><EndToEndId>E2E-2</EndToEndId></Refs></TxDtls>
+        </NtryDtls></Ntry>
+        <Ntry><Amt Ccy="EUR">10.00</Amt><CdtDbtInd>CRDT</CdtDbtInd>
+        <NtryDtls><TxDtls><Refs><EndToEndId>E2E-3</EndToEndId></Refs></TxDtls></NtryDtls></Ntry>
+        </Stmt></BkToCstmrStmt></Document>"#;
+    let file = get_dummy_file("svc-file-reader-processor-test-camt053-batch.xml", document);
+    let read_options = FileReadOptions { format: Some(FileFormat::Camt), ..FileReadOptions::default() };
+
+    let rows: Vec<_> = CamtFileReader::stream_file_rows(&file, &read_options).unwrap().map(|x| x.unwrap()).collect();
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].cells[2..6], ["", "EUR", "CRDT", "E2E-1"]);
+    assert_eq!(rows[0].values.clone().unwrap()[2], CellValue::Empty);
+    assert_eq!(rows[1].cells[2], "");
+    assert_eq!(rows[2].cells[2..6], ["10.00", "EUR", "CRDT", "E2E-3"]);
+}
+
+#[test]
+fn test_malformed_document_is_rejected() {
+    let result = CamtFileReader::read_statement_summary_from_document(Cursor::new("<Document><Stmt><Id>1</Stmt></Document>"));
+
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_amount_that_is_not_a_number_is_rejected() {
+    let document = r#"<Document><BkToCstmrStmt><Stmt><Id>STMT-1</Id>
+        <Ntry><Amt Ccy="EUR">1,000.00</Amt><CdtDbtInd>CRDT</CdtDbtInd></Ntry>
+        </Stmt></BkToCstmrStmt></Document>"#;
+
+    let error = CamtFileReader::read_statement_summary_from_document(Cursor::new(document)).unwrap_err();
+
+    assert_eq!(error.kind, AppErrorKind::BadClientRequest);
+    assert_eq!(error.message, "the amount 1,000.00 cannot be read as a number");
+}
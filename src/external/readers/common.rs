@@ -0,0 +1,109 @@
+use std::io::Read;
+use std::sync::OnceLock;
+
+use regex::Regex;
+
+use crate::external::readers::text_encoding::open_text_file;
+use crate::internal::models::entities::file_row::CellValue;
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+//the path of the file on the local disk, file_kind names the file in the error, e.g. "csv"
+pub(crate) fn get_file_path(file: &File, file_kind: &str) -> Result<String, AppError> {
+    return match file.file_path.clone() {
+        None => app_error_with_msg(AppErrorKind::BadClientRequest, &format!("please supply a file_path for the {} file", file_kind)),
+        Some(path) => Ok(path),
+    };
+}
+
+//the whole of a text file, decoded from the encoding given or else the one detected from the start of the file
+pub(crate) fn read_text_file(file: &File, file_kind: &str, encoding_label: Option<&str>) -> Result<String, AppError> {
+    let mut text = String::new();
+    if let Err(e) = open_text_file(&get_file_path(file, file_kind)?, encoding_label)?.read_to_string(&mut text) {
+        return app_error(AppErrorKind::BadClientRequest, Box::new(e));
+    }
+    return Ok(text);
+}
+
+//the delimiter the cells of a row are joined with in its raw data. it is the first delimiter in the
+//file's metadata, which the recon task uses to split the row again, or a comma if there is none
+pub(crate) fn get_column_delimiter(file: &File) -> char {
+    return file
+        .file_metadata
+        .clone()
+        .and_then(|metadata| metadata.column_delimiters)
+        .and_then(|column_delimiters| column_delimiters.first().cloned())
+        .unwrap_or(',');
+}
+
+//bank statement and payment files have the same well-known columns in every row, whatever is in the file
+pub(crate) fn get_column_headers(column_headers: &[&str]) -> Vec<String> {
+    return column_headers.iter().map(|column_header| column_header.to_string()).collect();
+}
+
+//a regex kept in a static, compiled the first time it is used rather than every time a file is read
+pub(crate) struct LazyRegex {
+    pattern: &'static str,
+    regex: OnceLock<Result<Regex, regex::Error>>,
+}
+
+impl LazyRegex {
+    pub(crate) const fn new(pattern: &'static str) -> LazyRegex {
+        return LazyRegex {
+            pattern,
+            regex: OnceLock::new(),
+        };
+    }
+
+    /**
+    the compiled regex
+
+    # Errors
+
+    an internal error if the pattern is not a valid regex
+     */
+    pub(crate) fn get(&self) -> Result<&Regex, AppError> {
+        return match self.regex.get_or_init(|| Regex::new(self.pattern)) {
+            Ok(regex) => Ok(regex),
+            Err(e) => app_error(AppErrorKind::InternalError, Box::new(e.clone())),
+        };
+    }
+}
+
+/**
+an amount read from a file as a decimal typed value
+
+# Errors
+
+a bad client request if the amount cannot be read as a number, so that it is never sent on as zero
+ */
+pub(crate) fn convert_amount_to_cell_value(amount: &str) -> Result<CellValue, AppError> {
+    return match amount.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok(CellValue::Decimal(value)),
+        _ => app_error_with_msg(AppErrorKind::BadClientRequest, &format!("the amount {} cannot be read as a number", amount)),
+    };
+}
+
+//a yymmdd date, e.g. 220301, as yyyy-mm-dd. years from 80 on are in the 1900s.
+//none if the date is not six ascii digits
+pub(crate) fn convert_yymmdd_date(date: &str) -> Option<String> {
+    if date.len() != 6 || !date.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    let year: u32 = date.get(0..2)?.parse().ok()?;
+    let century = if year >= 80 { 1900 } else { 2000 };
+    return Some(format!("{}-{}-{}", century + year, date.get(2..4)?, date.get(4..6)?));
+}
+
+//a yyyymmdd date, e.g. 20220301, as yyyy-mm-dd. none if the date is not eight ascii digits
+pub(crate) fn convert_yyyymmdd_date(date: &str) -> Option<String> {
+    if date.len() != 8 || !date.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    return Some(format!("{}-{}-{}", date.get(0..4)?, date.get(4..6)?, date.get(6..8)?));
+}
@@ -1,10 +1,17 @@
-use std::io::{BufRead, BufReader};
-
-use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error;
+use std::io::{BufRead, Read};
+
+use crate::external::readers::common::get_file_path;
+use crate::external::readers::csv_sniffer::{CsvSniffer, SNIFF_SAMPLE_SIZE_IN_BYTES};
+use crate::external::readers::csv_tokenizer::{ColumnDelimiters, CsvTokenizer};
+use crate::external::readers::text_encoding::{open_text_file, TextFileReader};
+use crate::internal::interfaces::file_reader::FileRowStream;
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::models::entities::file_row::FileRow;
+use crate::internal::models::entities::inferred_file_settings::InferredFileSettings;
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
 use crate::internal::shared_reconciler_rust_libraries::models::entities::{
     app_errors::AppError,
-    file::{File, FileThatHasBeenRead},
-    file_row::FileRow,
+    file::File,
 };
 use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
 use crate::internal::shared_reconciler_rust_libraries::models::entities::file::FileMetadata;
@@ -12,117 +19,142 @@ use crate::internal::shared_reconciler_rust_libraries::models::entities::file::F
 pub struct CsvFileReader {}
 
 impl CsvFileReader {
-    pub fn read_file(file: &File) -> Result<FileThatHasBeenRead, AppError> {
+    pub fn read_column_headers(file: &File, read_options: &FileReadOptions) -> Result<Vec<String>, AppError> {
         let updated_file = CsvFileReader::set_default_column_delimiter_if_none_found(file);
-        let column_headers_found = CsvFileReader::read_column_headers(&updated_file)?;
-        let file_rows_found = CsvFileReader::read_file_rows(&updated_file, if column_headers_found.is_empty() { false } else { true })?;
-        let file_that_has_been_read = FileThatHasBeenRead {
-            id: updated_file.id.clone(),
-            upload_request_id: updated_file.upload_request_id.clone(),
-            file_type: updated_file.file_type.clone(),
-            column_headers: column_headers_found,
-            file_rows: file_rows_found,
-            file_metadata: file.file_metadata.clone(),
-        };
-        return Ok(file_that_has_been_read);
-    }
-
-
-    fn read_column_headers(_file: &File) -> Result<Vec<String>, AppError> {
-        let mut headers = vec![];
+        let mut tokenizer = CsvFileReader::open_file(&updated_file, read_options)?;
 
-        let column_delimiters = match _file.file_metadata.clone() {
-            None => { return Ok(headers); }
-            Some(metadata) => {
-                match metadata.column_delimiters {
-                    None => { return Ok(headers); }
-                    Some(delimiters) => delimiters
-                }
-            }
+        let first_record = match tokenizer.next() {
+            None => return Ok(vec![]),
+            Some(record) => record?,
         };
 
-        let file_path = match _file.file_path.clone() {
-            None => { return Ok(headers); }
-            Some(path) => path
-        };
+        //the first record in the file holds the column headers,
+        //unless the file has none and the columns are named after their position
+        if !CsvFileReader::has_header_row(read_options) {
+            return Ok((1..=first_record.cells.len()).map(|column_number| format!("column_{}", column_number)).collect());
+        }
 
-        let open_result = std::fs::File::open(file_path);
+        return Ok(first_record.cells);
+    }
+
+    //reads the start of the file to work out the settings the request left out
+    pub fn infer_file_settings(file: &File, read_options: &FileReadOptions) -> Result<InferredFileSettings, AppError> {
+        let file_path = get_file_path(file, "csv")?;
 
-        let opened_file = match open_result {
+        let opened_file = match std::fs::File::open(file_path) {
             Ok(opened_file) => opened_file,
-            Err(_) => { return Ok(headers); }
+            Err(e) => { return app_error(AppErrorKind::BadClientRequest, Box::new(e)); }
         };
 
-        let reader = BufReader::new(opened_file);
-
-        for line in reader.lines() {
-            return match line {
-                Ok(line_details) => {
-                    headers = CsvFileReader::split_file_row(line_details, column_delimiters);
-                    Ok(headers)
-                }
-                Err(_) => {
-                    Ok(headers)
-                }
-            };
+        let mut sample = vec![];
+        if let Err(e) = opened_file.take(SNIFF_SAMPLE_SIZE_IN_BYTES as u64).read_to_end(&mut sample) {
+            return app_error(AppErrorKind::InternalError, Box::new(e));
         }
 
-        return Ok(headers);
+        let sniffed_settings = CsvSniffer::sniff(&sample);
+        let csv_read_options = read_options.csv.clone().unwrap_or_default();
+
+        let is_column_delimiter_supplied = csv_read_options.column_delimiters.is_some()
+            || csv_read_options.column_delimiter_pattern.is_some()
+            || file.file_metadata.as_ref().map_or(false, |metadata| metadata.column_delimiters.is_some());
+
+        return Ok(InferredFileSettings {
+            column_delimiter: sniffed_settings.column_delimiter.filter(|_| !is_column_delimiter_supplied),
+            quote_character: sniffed_settings.quote_character.filter(|_| csv_read_options.quote_character.is_none()),
+            has_header_row: sniffed_settings.has_header_row.filter(|_| csv_read_options.has_header_row.is_none()),
+            encoding: sniffed_settings.encoding.filter(|_| csv_read_options.encoding.is_none()),
+        });
     }
 
-    fn split_file_row(line_details: String, column_delimiters: Vec<char>) -> Vec<String> {
-        let mut result = vec![];
-        for column_delimiter in column_delimiters {
-            let split_file_row_values: Vec<String> = line_details.split(column_delimiter).into_iter().map(|x| x.to_string()).collect();
-            for split_file_row_value in split_file_row_values {
-                result.push(split_file_row_value);
-            }
+    pub fn count_file_rows(file: &File, read_options: &FileReadOptions) -> Result<u64, AppError> {
+        let updated_file = CsvFileReader::set_default_column_delimiter_if_none_found(file);
+        let tokenizer = CsvFileReader::open_file(&updated_file, read_options)?;
+
+        //every record after the column headers is counted but not kept
+        let mut row_count = 0;
+        for file_row in CsvFileReader::convert_records_into_file_rows(tokenizer, CsvFileReader::has_header_row(read_options)) {
+            let _ = file_row?;
+            row_count = row_count + 1;
         }
-        return result;
+        return Ok(row_count);
     }
 
-    fn read_file_rows(_file: &File, has_header_row: bool) -> Result<Vec<FileRow>, AppError> {
-        let mut file_rows = vec![];
-        let start_row_index = if has_header_row { 1 } else { 0 };
+    pub fn stream_file_rows(file: &File, read_options: &FileReadOptions) -> Result<FileRowStream, AppError> {
+        let updated_file = CsvFileReader::set_default_column_delimiter_if_none_found(file);
+        let tokenizer = CsvFileReader::open_file(&updated_file, read_options)?;
+        return Ok(Box::new(CsvFileReader::convert_records_into_file_rows(tokenizer, CsvFileReader::has_header_row(read_options))));
+    }
 
-        let file_path = match _file.file_path.clone() {
-            None => { return Ok(file_rows); }
-            Some(path) => path
-        };
+    fn open_file(file: &File, read_options: &FileReadOptions) -> Result<CsvTokenizer<TextFileReader>, AppError> {
+        let column_delimiters = CsvFileReader::get_column_delimiters(file, read_options)?;
 
-        let open_result = std::fs::File::open(file_path);
+        let file_path = get_file_path(file, "csv")?;
 
-        let opened_file = match open_result {
-            Ok(opened_file) => opened_file,
-            Err(_) => { return Ok(file_rows); }
-        };
+        //the file is read as utf-8 whatever encoding it was saved in
+        let encoding = read_options.csv.as_ref().and_then(|csv_read_options| csv_read_options.encoding.clone());
+        let text_file_reader = open_text_file(&file_path, encoding.as_deref())?;
 
-        let reader = BufReader::new(opened_file);
+        let quote_character = read_options.csv.as_ref().and_then(|csv_read_options| csv_read_options.quote_character);
 
-        let mut row_index = 0;
-        for line in reader.lines() {
-            if row_index < start_row_index {
-                row_index = row_index + 1;
-                continue;
-            }
+        let tokenizer = CsvTokenizer::new(text_file_reader, column_delimiters);
+        return Ok(match quote_character {
+            Some(quote_character) => tokenizer.with_quote_character(quote_character),
+            None => tokenizer,
+        });
+    }
 
-            match line {
-                Ok(line_details) => {
-                    let split_file_row = FileRow {
-                        raw_data: line_details.clone(),
-                        row_number: row_index.clone(),
-                    };
-                    file_rows.push(split_file_row);
-                }
-                Err(e) => {
-                    return app_error(AppErrorKind::InternalError, Box::new(e));
-                }
-            };
+    //the first record in the file holds the column headers and is skipped,
+    //every record after it becomes a file row numbered from 1.
+    //in a file without a header row the first record is row 1
+    pub(crate) fn convert_records_into_file_rows<R: BufRead>(tokenizer: CsvTokenizer<R>, has_header_row: bool) -> impl Iterator<Item = Result<FileRow, AppError>> {
+        let first_row_number = if has_header_row { 0 } else { 1 };
+
+        return tokenizer
+            .enumerate()
+            .map(move |(row_index, record)| (row_index as u64 + first_row_number, record))
+            .filter_map(|(row_number, record)| match (row_number, record) {
+                (0, Ok(_)) => None,
+                (_, Err(e)) => Some(Err(e)),
+                (row_number, Ok(record)) => Some(Ok(FileRow {
+                    raw_data: record.raw_data,
+                    row_number,
+                    cells: record.cells,
+                    values: None,
+                })),
+            });
+    }
 
-            row_index = row_index + 1;
-        }
+    //delimiters in the csv read options win over the single character ones in the file metadata
+    pub(crate) fn get_column_delimiters(file: &File, read_options: &FileReadOptions) -> Result<ColumnDelimiters, AppError> {
+        let csv_read_options = read_options.csv.clone().unwrap_or_default();
+
+        return match (csv_read_options.column_delimiters, csv_read_options.column_delimiter_pattern) {
+            (Some(_), Some(_)) => app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                "please supply either column_delimiters or a column_delimiter_pattern, not both",
+            ),
+            (None, Some(column_delimiter_pattern)) => ColumnDelimiters::pattern(&column_delimiter_pattern),
+            (Some(column_delimiters), None) if column_delimiters.iter().all(|delimiter| delimiter.is_empty()) => app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                "please supply at least one column delimiter that is not empty",
+            ),
+            (Some(column_delimiters), None) => Ok(ColumnDelimiters::any_of(column_delimiters)),
+            (None, None) => {
+                let column_delimiters = match file.file_metadata.clone() {
+                    None => vec![','],
+                    Some(metadata) => metadata.column_delimiters.unwrap_or(vec![','])
+                };
+                Ok(ColumnDelimiters::from_chars(&column_delimiters))
+            }
+        };
+    }
 
-        return Ok(file_rows);
+    fn has_header_row(read_options: &FileReadOptions) -> bool {
+        return read_options
+            .csv
+            .as_ref()
+            .and_then(|csv_read_options| csv_read_options.has_header_row)
+            .unwrap_or(true);
     }
 
     fn set_default_column_delimiter_if_none_found(file: &File) -> File {
@@ -0,0 +1,185 @@
+use std::io::Cursor;
+
+use crate::external::readers::csv_tokenizer::{ColumnDelimiters, CsvTokenizer};
+use crate::external::readers::text_encoding::TextEncoding;
+use crate::internal::models::entities::inferred_file_settings::InferredFileSettings;
+
+//the delimiters a file is checked for, in the order they are preferred when they score the same
+const CANDIDATE_COLUMN_DELIMITERS: [char; 4] = [',', ';', '\t', '|'];
+const CANDIDATE_QUOTE_CHARACTERS: [char; 2] = ['"', '\''];
+
+//how many lines at the start of a file are looked at
+pub const SNIFF_SAMPLE_LINE_COUNT: usize = 20;
+
+//how many bytes at the start of a file are read to find the sample lines
+pub const SNIFF_SAMPLE_SIZE_IN_BYTES: usize = 64 * 1024;
+
+/**
+works out how a delimited text file is laid out from a sample taken from the start of it
+
+- the delimiter is the candidate that appears outside quotes the same number of times on the most lines
+- the quote character is the candidate that most often wraps a whole cell
+- the first line is a header row if its cells do not look like the cells below them
+ */
+pub struct CsvSniffer {}
+
+impl CsvSniffer {
+    pub fn sniff(sample: &[u8]) -> InferredFileSettings {
+        let (encoding, byte_order_mark_length) = TextEncoding::detect(sample);
+        let text = encoding.decode_lossy(&sample[byte_order_mark_length..]);
+
+        let lines: Vec<&str> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .take(SNIFF_SAMPLE_LINE_COUNT)
+            .collect();
+
+        let (column_delimiter, quote_character) = CsvSniffer::sniff_column_delimiter_and_quote_character(&lines);
+        let has_header_row = CsvSniffer::sniff_header_row(&lines, column_delimiter, quote_character);
+
+        return InferredFileSettings {
+            column_delimiter: Some(column_delimiter.to_string()),
+            quote_character: Some(quote_character),
+            has_header_row: Some(has_header_row),
+            encoding: Some(encoding.get_label().to_string()),
+        };
+    }
+
+    //delimiters inside quoted cells do not count, so the delimiter is sniffed once for every candidate quote
+    //character and the quote character that wraps the most cells is kept, along with the delimiter sniffed for it
+    fn sniff_column_delimiter_and_quote_character(lines: &[&str]) -> (char, char) {
+        let mut best_quote_character = CANDIDATE_QUOTE_CHARACTERS[0];
+        let mut best_column_delimiter = CsvSniffer::sniff_column_delimiter(lines, best_quote_character);
+        let mut best_count = 0;
+
+        for quote_character in CANDIDATE_QUOTE_CHARACTERS {
+            let column_delimiter = CsvSniffer::sniff_column_delimiter(lines, quote_character);
+            let quoted_cell_count = lines
+                .iter()
+                .flat_map(|line| CsvSniffer::split_unquoted(line, column_delimiter, quote_character))
+                .map(|cell| cell.trim())
+                .filter(|cell| cell.len() >= 2 && cell.starts_with(quote_character) && cell.ends_with(quote_character))
+                .count();
+
+            if quoted_cell_count > best_count {
+                best_count = quoted_cell_count;
+                best_quote_character = quote_character;
+                best_column_delimiter = column_delimiter;
+            }
+        }
+
+        return (best_column_delimiter, best_quote_character);
+    }
+
+    fn sniff_column_delimiter(lines: &[&str], quote_character: char) -> char {
+        let mut best_column_delimiter = CANDIDATE_COLUMN_DELIMITERS[0];
+        let mut best_score = (0, 0);
+
+        for column_delimiter in CANDIDATE_COLUMN_DELIMITERS {
+            let counts: Vec<usize> = lines
+                .iter()
+                .map(|line| CsvSniffer::split_unquoted(line, column_delimiter, quote_character).len() - 1)
+                .collect();
+
+            //the count seen on the most lines, and how many lines it was seen on
+            let mut score = (0, 0);
+            for count in counts.iter().filter(|count| **count > 0) {
+                let lines_with_count = counts.iter().filter(|other_count| *other_count == count).count();
+                if (lines_with_count, *count) > score {
+                    score = (lines_with_count, *count);
+                }
+            }
+
+            if score > best_score {
+                best_score = score;
+                best_column_delimiter = column_delimiter;
+            }
+        }
+
+        return best_column_delimiter;
+    }
+
+    //each column votes on whether the first line is a header. a column votes for a header if its
+    //first cell is text while the cells below are numbers, or if the cells below are all the same
+    //length and the first cell is not. a file whose columns do not vote either way has a header row
+    fn sniff_header_row(lines: &[&str], column_delimiter: char, quote_character: char) -> bool {
+        let rows: Vec<Vec<String>> = lines
+            .iter()
+            .map(|line| CsvSniffer::split_line(line, column_delimiter, quote_character))
+            .collect();
+
+        let (first_row, other_rows) = match rows.split_first() {
+            Some((first_row, other_rows)) if !other_rows.is_empty() => (first_row, other_rows),
+            _ => return true,
+        };
+
+        //a header line that ends in a delimiter leaves empty cells at its end, which say nothing either way
+        let first_row_length = first_row.iter().rposition(|cell| !cell.trim().is_empty()).map_or(0, |index| index + 1);
+        let first_row = &first_row[..first_row_length];
+
+        if first_row.is_empty() || first_row.iter().any(|cell| cell.trim().is_empty()) {
+            return false;
+        }
+
+        let mut votes = 0;
+        for (column_index, first_cell) in first_row.iter().enumerate() {
+            let cells_below: Vec<&str> = other_rows
+                .iter()
+                .filter_map(|row| row.get(column_index))
+                .map(|cell| cell.trim())
+                .filter(|cell| !cell.is_empty())
+                .collect();
+
+            if cells_below.is_empty() {
+                continue;
+            }
+
+            if cells_below.iter().all(|cell| CsvSniffer::is_number(cell)) {
+                votes = votes + if CsvSniffer::is_number(first_cell.trim()) { -1 } else { 1 };
+                continue;
+            }
+
+            let cell_length = cells_below[0].chars().count();
+            if cells_below.iter().all(|cell| cell.chars().count() == cell_length) {
+                votes = votes + if first_cell.trim().chars().count() == cell_length { -1 } else { 1 };
+            }
+        }
+
+        return votes >= 0;
+    }
+
+    //the cells of the line split on the delimiter, leaving delimiters between quotes alone
+    fn split_unquoted(line: &str, column_delimiter: char, quote_character: char) -> Vec<&str> {
+        let mut cells = vec![];
+        let mut cell_start = 0;
+        let mut is_in_quotes = false;
+
+        for (index, character) in line.char_indices() {
+            if character == quote_character {
+                is_in_quotes = !is_in_quotes;
+            } else if character == column_delimiter && !is_in_quotes {
+                cells.push(&line[cell_start..index]);
+                cell_start = index + character.len_utf8();
+            }
+        }
+        cells.push(&line[cell_start..]);
+
+        return cells;
+    }
+
+    fn split_line(line: &str, column_delimiter: char, quote_character: char) -> Vec<String> {
+        let mut tokenizer = CsvTokenizer::new(Cursor::new(line), ColumnDelimiters::from_chars(&[column_delimiter]))
+            .with_quote_character(quote_character);
+
+        return match tokenizer.next() {
+            Some(Ok(record)) => record.cells,
+            //a quoted cell that carries on past the sample line is split as plain text
+            _ => line.split(column_delimiter).map(|cell| cell.to_string()).collect(),
+        };
+    }
+
+    fn is_number(cell: &str) -> bool {
+        let cell = cell.replace(|character: char| character == ',' || character == ' ', "");
+        return !cell.is_empty() && cell.parse::<f64>().is_ok();
+    }
+}
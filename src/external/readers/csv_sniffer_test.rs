@@ -0,0 +1,72 @@
+use crate::external::readers::csv_sniffer::CsvSniffer;
+use crate::external::readers::text_encoding::TextEncoding;
+
+#[test]
+fn test_delimiter_is_sniffed_from_the_most_consistent_candidate() {
+    let semicolons = CsvSniffer::sniff(b"id;narration;amount\n001;Payment, ref 123;2000,50\n002;Refund;15,00\n");
+    let tabs = CsvSniffer::sniff(b"id\tamount\n001\t2000\n002\t15\n");
+    let pipes = CsvSniffer::sniff(b"id|narration|amount\n001|\"a|b\"|2000\n002|c|15\n");
+
+    assert_eq!(semicolons.column_delimiter, Some(String::from(";")));
+    assert_eq!(tabs.column_delimiter, Some(String::from("\t")));
+    assert_eq!(pipes.column_delimiter, Some(String::from("|")));
+}
+
+#[test]
+fn test_quote_character_is_sniffed() {
+    let single_quotes = CsvSniffer::sniff(b"id,narration\n001,'Payment, ref 123'\n002,'Refund'\n");
+    let double_quotes = CsvSniffer::sniff(b"id,narration\n001,\"Payment\"\n002,Refund\n");
+
+    assert_eq!(single_quotes.quote_character, Some('\''));
+    assert_eq!(double_quotes.quote_character, Some('"'));
+}
+
+#[test]
+fn test_delimiters_inside_cells_quoted_with_the_sniffed_quote_character_are_ignored() {
+    let inferred_settings = CsvSniffer::sniff(b"'id;no'|narration\n'1;2'|Payment\n'3;4'|Refund\n");
+
+    assert_eq!(inferred_settings.column_delimiter, Some(String::from("|")));
+    assert_eq!(inferred_settings.quote_character, Some('\''));
+}
+
+#[test]
+fn test_header_row_is_sniffed() {
+    let with_header = CsvSniffer::sniff(b"id,narration,amount\n001,Payment,2000\n002,Refund,15.5\n");
+    let without_header = CsvSniffer::sniff(b"001,Payment,2000\n002,Refund,15.5\n003,Fee,1\n");
+
+    assert_eq!(with_header.has_header_row, Some(true));
+    assert_eq!(without_header.has_header_row, Some(false));
+}
+
+#[test]
+fn test_a_header_row_ending_in_a_delimiter_is_still_a_header_row() {
+    let inferred_settings = CsvSniffer::sniff(b"id,narration,amount,\n001,Payment,2000,\n002,Refund,15.5,\n");
+
+    assert_eq!(inferred_settings.has_header_row, Some(true));
+}
+
+#[test]
+fn test_encoding_is_sniffed() {
+    let utf8_with_bom = CsvSniffer::sniff(b"\xEF\xBB\xBFid;amount\n001;2000\n");
+    let windows_1252 = CsvSniffer::sniff(b"id;narration\n001;Caf\xE9 \x80 5\n");
+    let utf16_le: Vec<u8> = "id;amount\n001;2000\n".encode_utf16().flat_map(|code_unit| code_unit.to_le_bytes()).collect();
+    let utf16_le = CsvSniffer::sniff(&utf16_le);
+
+    assert_eq!(utf8_with_bom.encoding, Some(String::from("utf-8")));
+    assert_eq!(utf8_with_bom.column_delimiter, Some(String::from(";")));
+    assert_eq!(windows_1252.encoding, Some(String::from("windows-1252")));
+    assert_eq!(utf16_le.encoding, Some(String::from("utf-16le")));
+    assert_eq!(utf16_le.column_delimiter, Some(String::from(";")));
+}
+
+#[test]
+fn test_text_encodings_are_detected_and_decoded() {
+    assert_eq!(TextEncoding::detect(b"\xFF\xFEi\x00d\x00"), (TextEncoding::Utf16Le, 2));
+    assert_eq!(TextEncoding::detect(b"\xFE\xFF\x00i\x00d"), (TextEncoding::Utf16Be, 2));
+    assert_eq!(TextEncoding::detect("caf\u{e9}".as_bytes()), (TextEncoding::Utf8, 0));
+    //a sample cut off part way through a character is still utf-8
+    assert_eq!(TextEncoding::detect(&"caf\u{e9}".as_bytes()[..4]), (TextEncoding::Utf8, 0));
+
+    assert_eq!(TextEncoding::Windows1252.decode_lossy(b"\x80 caf\xE9 \x93ok\x94"), "\u{20AC} caf\u{e9} \u{201C}ok\u{201D}");
+    assert_eq!(TextEncoding::Latin1.decode_lossy(b"\x80caf\xE9"), "\u{80}caf\u{e9}");
+}
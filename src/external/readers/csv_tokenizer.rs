@@ -0,0 +1,221 @@
+use std::io::BufRead;
+
+use regex::Regex;
+
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{
+    AppError, AppErrorKind,
+};
+
+const DEFAULT_QUOTE_CHARACTER: char = '"';
+
+//a single logical record in a csv file.
+//a record can span several physical lines
+//if one of its fields is quoted and contains a line break
+#[derive(Clone, PartialEq, Debug)]
+pub struct CsvRecord {
+    pub raw_data: String,
+    pub cells: Vec<String>,
+    pub first_line_number: u64,
+}
+
+//what separates the cells of a record. a delimiter is only looked for outside quoted fields
+#[derive(Clone, Debug)]
+pub enum ColumnDelimiters {
+    //any one of the delimiters ends a cell. delimiters can be more than one character
+    //long and the longest one that matches is used, so "||" wins over "|"
+    AnyOf(Vec<String>),
+
+    //a regex matched at the start of each unquoted position, e.g. "\s*;\s*"
+    Pattern(Regex),
+}
+
+impl ColumnDelimiters {
+    pub fn from_chars(column_delimiters: &[char]) -> ColumnDelimiters {
+        return ColumnDelimiters::any_of(column_delimiters.iter().map(|delimiter| delimiter.to_string()).collect());
+    }
+
+    pub fn any_of(mut column_delimiters: Vec<String>) -> ColumnDelimiters {
+        column_delimiters.retain(|delimiter| !delimiter.is_empty());
+        column_delimiters.sort_by(|a, b| b.len().cmp(&a.len()));
+        return ColumnDelimiters::AnyOf(column_delimiters);
+    }
+
+    pub fn pattern(column_delimiter_pattern: &str) -> Result<ColumnDelimiters, AppError> {
+        //anchoring the pattern stops the regex from scanning ahead for a match further along the line
+        return match Regex::new(&format!("^(?:{})", column_delimiter_pattern)) {
+            Ok(pattern) => Ok(ColumnDelimiters::Pattern(pattern)),
+            Err(e) => app_error(AppErrorKind::BadClientRequest, Box::new(e)),
+        };
+    }
+
+    //the length in bytes of the delimiter that starts at the beginning of the text, if there is one
+    fn get_delimiter_length(&self, text: &str) -> Option<usize> {
+        return match self {
+            ColumnDelimiters::AnyOf(column_delimiters) => column_delimiters
+                .iter()
+                .find(|delimiter| text.starts_with(delimiter.as_str()))
+                .map(|delimiter| delimiter.len()),
+            ColumnDelimiters::Pattern(pattern) => pattern
+                .find(text)
+                .map(|delimiter| delimiter.end())
+                .filter(|delimiter_length| *delimiter_length > 0),
+        };
+    }
+}
+
+/**
+splits the contents of a csv file into records as described in RFC 4180
+
+- fields may be wrapped in double quotes, or in another quote character
+- a quoted field may contain delimiters, line breaks and escaped quotes ("")
+- records may be terminated by either CRLF or LF
+- any of the supplied delimiters, or a match of the delimiter pattern, ends a field
+ */
+pub struct CsvTokenizer<R: BufRead> {
+    reader: R,
+    column_delimiters: ColumnDelimiters,
+    quote_character: char,
+    next_line_number: u64,
+}
+
+impl<R: BufRead> CsvTokenizer<R> {
+    pub fn new(reader: R, column_delimiters: ColumnDelimiters) -> CsvTokenizer<R> {
+        return CsvTokenizer {
+            reader,
+            column_delimiters,
+            quote_character: DEFAULT_QUOTE_CHARACTER,
+            next_line_number: 1,
+        };
+    }
+
+    //fields are wrapped in double quotes unless another quote character is given
+    pub fn with_quote_character(mut self, quote_character: char) -> CsvTokenizer<R> {
+        self.quote_character = quote_character;
+        return self;
+    }
+
+    fn read_line(&mut self) -> Result<Option<String>, AppError> {
+        let mut line = String::new();
+        match self.reader.read_line(&mut line) {
+            Ok(0) => Ok(None),
+            Ok(_) => {
+                self.next_line_number = self.next_line_number + 1;
+                Ok(Some(line))
+            }
+            //text that cannot be decoded is a problem with the file, not with the service
+            Err(e) if e.kind() == std::io::ErrorKind::InvalidData => app_error(AppErrorKind::BadClientRequest, Box::new(e)),
+            Err(e) => app_error(AppErrorKind::InternalError, Box::new(e)),
+        }
+    }
+
+    fn read_record(&mut self) -> Result<Option<CsvRecord>, AppError> {
+        let first_line_number = self.next_line_number;
+
+        let mut line = match self.read_line()? {
+            None => return Ok(None),
+            Some(line) => line,
+        };
+
+        let mut raw_data = String::new();
+        let mut cells = vec![];
+        let mut current_cell = String::new();
+        let mut is_in_quotes = false;
+        let mut is_at_start_of_cell = true;
+        let quote_character = self.quote_character;
+
+        loop {
+            let mut position = 0;
+
+            //delimiters are never allowed to swallow the line ending
+            let end_of_line_content = line.trim_end_matches(|character| character == '\r' || character == '\n').len();
+
+            while let Some(character) = line[position..].chars().next() {
+                let next_position = position + character.len_utf8();
+
+                if is_in_quotes {
+                    if character == quote_character {
+                        //two quotes in a row inside a quoted field is an escaped quote
+                        if line[next_position..].starts_with(quote_character) {
+                            current_cell.push(quote_character);
+                            position = next_position + quote_character.len_utf8();
+                            continue;
+                        }
+                        is_in_quotes = false;
+                    } else {
+                        current_cell.push(character);
+                    }
+                    position = next_position;
+                    continue;
+                }
+
+                if character == quote_character && is_at_start_of_cell {
+                    is_in_quotes = true;
+                    is_at_start_of_cell = false;
+                    position = next_position;
+                    continue;
+                }
+
+                if character == '\n' || (character == '\r' && line[next_position..].starts_with('\n')) {
+                    break;
+                }
+
+                let rest_of_line_content = line.get(position..end_of_line_content).unwrap_or("");
+                if let Some(delimiter_length) = self.column_delimiters.get_delimiter_length(rest_of_line_content) {
+                    cells.push(current_cell);
+                    current_cell = String::new();
+                    is_at_start_of_cell = true;
+                    position = position + delimiter_length;
+                    continue;
+                }
+
+                current_cell.push(character);
+                is_at_start_of_cell = false;
+                position = next_position;
+            }
+
+            raw_data.push_str(&line);
+
+            if !is_in_quotes {
+                break;
+            }
+
+            //the quoted field carries on to the next line
+            line = match self.read_line()? {
+                Some(next_line) => next_line,
+                None => {
+                    return app_error_with_msg(
+                        AppErrorKind::BadClientRequest,
+                        &format!("unterminated quoted field in record starting on line {}", first_line_number),
+                    );
+                }
+            };
+        }
+
+        cells.push(current_cell);
+
+        return Ok(Some(CsvRecord {
+            raw_data: Self::trim_line_ending(raw_data),
+            cells,
+            first_line_number,
+        }));
+    }
+
+    fn trim_line_ending(mut raw_data: String) -> String {
+        if raw_data.ends_with('\n') {
+            raw_data.pop();
+            if raw_data.ends_with('\r') {
+                raw_data.pop();
+            }
+        }
+        return raw_data;
+    }
+}
+
+impl<R: BufRead> Iterator for CsvTokenizer<R> {
+    type Item = Result<CsvRecord, AppError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        return self.read_record().transpose();
+    }
+}
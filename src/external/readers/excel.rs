@@ -1,29 +1,226 @@
+use calamine::{open_workbook_auto, Data, Range, Reader};
+
+use crate::external::readers::common::{get_column_delimiter, get_file_path};
+use crate::external::readers::parsed_files::ParsedFile;
+use crate::internal::models::entities::{
+    file_read_options::{ExcelReadOptions, FileReadOptions},
+    file_row::{CellValue, FileRow},
+};
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
 use crate::internal::shared_reconciler_rust_libraries::models::entities::{
-    app_errors::AppError,
-    file::{File, FileThatHasBeenRead},
-    file_row::FileRow,
+    app_errors::{AppError, AppErrorKind},
+    file::File,
 };
 
+//how many rows at the top of a sheet are inspected when looking for the header row
+const HEADER_ROW_DETECTION_SAMPLE_SIZE: usize = 20;
+
 pub struct ExcelFileReader {}
 
 impl ExcelFileReader {
-    pub fn read_file(file: &File) -> Result<FileThatHasBeenRead, AppError> {
-        let file_that_has_been_read = FileThatHasBeenRead {
-            id: file.id.clone(),
-            upload_request_id: file.upload_request_id.clone(),
-            file_type: file.file_type.clone(),
-            column_headers: ExcelFileReader::read_column_headers(file),
-            file_rows: ExcelFileReader::read_file_rows(file),
-            file_metadata: file.file_metadata.clone(),
+    //calamine loads the whole sheet into memory, so the sheet is read in full before any of its rows are
+    pub fn read_file(file: &File, read_options: &FileReadOptions) -> Result<ParsedFile, AppError> {
+        let excel_read_options = read_options.excel.clone().unwrap_or_default();
+        let sheet = ExcelFileReader::read_sheet(file, &excel_read_options)?;
+        let column_delimiter = get_column_delimiter(file);
+
+        let (column_headers, file_rows) = ExcelFileReader::read_column_headers_and_file_rows(
+            &sheet,
+            excel_read_options.header_row_index,
+            column_delimiter,
+        )?;
+
+        return Ok(ParsedFile {
+            column_headers,
+            file_rows,
+            statement_summary: None,
+        });
+    }
+
+    fn read_sheet(file: &File, excel_read_options: &ExcelReadOptions) -> Result<Range<Data>, AppError> {
+        let file_path = get_file_path(file, "excel")?;
+
+        let mut workbook = match open_workbook_auto(file_path) {
+            Ok(workbook) => workbook,
+            Err(e) => { return app_error(AppErrorKind::BadClientRequest, Box::new(e)); }
+        };
+
+        let sheet_names = workbook.sheet_names();
+
+        let sheet_name = match (excel_read_options.sheet_name.clone(), excel_read_options.sheet_index) {
+            (Some(sheet_name), _) => sheet_name,
+            (None, Some(sheet_index)) => match sheet_names.get(sheet_index) {
+                Some(sheet_name) => sheet_name.clone(),
+                None => {
+                    return app_error_with_msg(
+                        AppErrorKind::BadClientRequest,
+                        &format!("sheet index {} is out of range, the workbook has {} sheet(s)", sheet_index, sheet_names.len()),
+                    );
+                }
+            },
+            (None, None) => match sheet_names.first() {
+                Some(sheet_name) => sheet_name.clone(),
+                None => {
+                    return app_error_with_msg(AppErrorKind::BadClientRequest, "the workbook does not contain any sheets");
+                }
+            },
+        };
+
+        if !sheet_names.contains(&sheet_name) {
+            return app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!("sheet '{}' was not found, available sheets are: {}", sheet_name, sheet_names.join(", ")),
+            );
+        }
+
+        return match workbook.worksheet_range(&sheet_name) {
+            Ok(range) => Ok(range),
+            Err(e) => app_error(AppErrorKind::BadClientRequest, Box::new(e)),
+        };
+    }
+
+    //rows are numbered by their distance from the header row so that,
+    //just like in the csv reader, the header is row 0 and the first record below it is row 1.
+    //completely empty rows are skipped
+    pub(crate) fn read_column_headers_and_file_rows(
+        sheet: &Range<Data>,
+        header_row_index: Option<usize>,
+        column_delimiter: char,
+    ) -> Result<(Vec<String>, Vec<FileRow>), AppError> {
+        let header_row_index = match header_row_index {
+            Some(header_row_index) => header_row_index,
+            None => match ExcelFileReader::detect_header_row_index(sheet) {
+                Some(header_row_index) => header_row_index,
+                None => return Ok((vec![], vec![])),
+            },
+        };
+
+        let column_headers = match sheet.rows().nth(header_row_index) {
+            Some(header_row) => ExcelFileReader::trim_trailing_empty_cells(
+                header_row.iter().map(ExcelFileReader::convert_cell_to_text).collect(),
+            ),
+            None => {
+                return app_error_with_msg(
+                    AppErrorKind::BadClientRequest,
+                    &format!("header row index {} is beyond the last row of the sheet", header_row_index),
+                );
+            }
+        };
+
+        //the text and the typed value of each cell are worked out in the same pass over the row
+        let mut file_rows = vec![];
+        for (row_index, row) in sheet.rows().enumerate().skip(header_row_index + 1) {
+            let (cells, values): (Vec<String>, Vec<CellValue>) = row
+                .iter()
+                .map(|cell| {
+                    let cell_text = ExcelFileReader::convert_cell_to_text(cell);
+                    let cell_value = ExcelFileReader::convert_cell_to_value(cell, &cell_text);
+                    (cell_text, cell_value)
+                })
+                .unzip();
+
+            if cells.iter().all(|cell| cell.is_empty()) {
+                continue;
+            }
+
+            let row_number = (row_index - header_row_index) as u64;
+            file_rows.push(FileRow::from_cells(row_number, cells, column_delimiter).with_values(values));
+        }
+
+        return Ok((column_headers, file_rows));
+    }
+
+    //the header row is taken to be the first row in which every filled-in cell is text
+    //and which fills at least half as many cells as the widest row near the top of the sheet.
+    //this skips over title rows such as "Statement for March" that sit above the real headers
+    fn detect_header_row_index(sheet: &Range<Data>) -> Option<usize> {
+        let sample: Vec<&[Data]> = sheet.rows().take(HEADER_ROW_DETECTION_SAMPLE_SIZE).collect();
+
+        let widest_row_cell_count = sample
+            .iter()
+            .map(|row| row.iter().filter(|cell| **cell != Data::Empty).count())
+            .max()
+            .unwrap_or(0);
+
+        if widest_row_cell_count == 0 {
+            return None;
+        }
+
+        for (row_index, row) in sample.iter().enumerate() {
+            let filled_cells: Vec<&Data> = row.iter().filter(|cell| **cell != Data::Empty).collect();
+            let are_all_filled_cells_text = filled_cells.iter().all(|cell| matches!(cell, Data::String(_)));
+
+            if !filled_cells.is_empty() && are_all_filled_cells_text && filled_cells.len() * 2 >= widest_row_cell_count {
+                return Some(row_index);
+            }
+        }
+
+        return None;
+    }
+
+    //converts a cell into text that stays the same no matter
+    //how the cell was formatted for display in the workbook
+    pub(crate) fn convert_cell_to_text(cell: &Data) -> String {
+        return match cell {
+            Data::Empty => String::new(),
+            Data::String(value) => value.trim().to_string(),
+            Data::Int(value) => value.to_string(),
+            Data::Float(value) => ExcelFileReader::convert_float_to_text(*value),
+            Data::Bool(value) => value.to_string(),
+            Data::DateTime(value) if value.is_duration() => match value.as_duration() {
+                Some(duration) => format!(
+                    "{:02}:{:02}:{:02}",
+                    duration.num_hours(),
+                    duration.num_minutes() % 60,
+                    duration.num_seconds() % 60
+                ),
+                None => ExcelFileReader::convert_float_to_text(value.as_f64()),
+            },
+            Data::DateTime(value) => match value.as_datetime() {
+                Some(date_time) if date_time.time() == chrono::NaiveTime::MIN => date_time.format("%Y-%m-%d").to_string(),
+                Some(date_time) => date_time.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                None => ExcelFileReader::convert_float_to_text(value.as_f64()),
+            },
+            Data::DateTimeIso(value) => value.clone(),
+            Data::DurationIso(value) => value.clone(),
+            Data::Error(value) => value.to_string(),
+        };
+    }
+
+    //keeps the type the workbook gave the cell. dates, times and durations carry
+    //the cell's text, as written by convert_cell_to_text, so both read the same
+    pub(crate) fn convert_cell_to_value(cell: &Data, cell_text: &str) -> CellValue {
+        return match cell {
+            Data::Empty => CellValue::Empty,
+            Data::String(value) => CellValue::Text(value.trim().to_string()),
+            Data::Int(value) => CellValue::Integer(*value),
+            Data::Float(value) => CellValue::Decimal(*value),
+            Data::Bool(value) => CellValue::Boolean(*value),
+            Data::DateTime(value) if value.is_duration() => CellValue::Duration(cell_text.to_string()),
+            Data::DateTime(value) => match value.as_datetime() {
+                Some(date_time) if date_time.time() == chrono::NaiveTime::MIN => CellValue::Date(cell_text.to_string()),
+                Some(_) => CellValue::DateTime(cell_text.to_string()),
+                None => CellValue::Decimal(value.as_f64()),
+            },
+            Data::DateTimeIso(value) => CellValue::DateTime(value.clone()),
+            Data::DurationIso(value) => CellValue::Duration(value.clone()),
+            Data::Error(value) => CellValue::Text(value.to_string()),
         };
-        return Ok(file_that_has_been_read);
     }
 
-    fn read_column_headers(_file: &File) -> Vec<String> {
-        vec![]
+    //whole numbers are written without a trailing ".0" so that
+    //an amount of 2000 reads the same as it would in a csv export
+    fn convert_float_to_text(value: f64) -> String {
+        if value.fract() == 0.0 && value.abs() < 1e15 {
+            return format!("{}", value as i64);
+        }
+        return value.to_string();
     }
 
-    fn read_file_rows(_file: &File) -> Vec<FileRow> {
-        vec![]
+    fn trim_trailing_empty_cells(mut row: Vec<String>) -> Vec<String> {
+        while row.last().map_or(false, |cell| cell.is_empty()) {
+            row.pop();
+        }
+        return row;
     }
 }
@@ -0,0 +1,101 @@
+use calamine::{Data, Range};
+
+use crate::external::readers::excel::ExcelFileReader;
+use crate::internal::models::entities::file_row::CellValue;
+
+fn build_sheet(rows: Vec<Vec<Data>>) -> Range<Data> {
+    let width = rows.iter().map(|row| row.len()).max().unwrap_or(1) as u32;
+    let mut sheet = Range::new((0, 0), (rows.len() as u32 - 1, width - 1));
+    for (row_index, row) in rows.into_iter().enumerate() {
+        for (column_index, cell) in row.into_iter().enumerate() {
+            sheet.set_value((row_index as u32, column_index as u32), cell);
+        }
+    }
+    return sheet;
+}
+
+fn text(value: &str) -> Data {
+    Data::String(value.to_string())
+}
+
+#[test]
+fn test_header_row_is_detected_below_title_rows() {
+    let sheet = build_sheet(vec![
+        vec![text("Statement for March")],
+        vec![],
+        vec![text("id"), text("narration"), text("amount")],
+        vec![text("001"), text("Payment, ref 123"), Data::Float(2000.0)],
+        vec![],
+        vec![text("002"), text("Refund"), Data::Float(-15.5)],
+    ]);
+
+    let (headers, rows) = ExcelFileReader::read_column_headers_and_file_rows(&sheet, None, ',').unwrap();
+
+    assert_eq!(headers, vec!["id", "narration", "amount"]);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].row_number, 1);
+    assert_eq!(rows[0].cells, vec!["001", "Payment, ref 123", "2000"]);
+    assert_eq!(rows[0].raw_data, "001,\"Payment, ref 123\",2000");
+    assert_eq!(rows[1].row_number, 3);
+    assert_eq!(rows[1].cells, vec!["002", "Refund", "-15.5"]);
+}
+
+#[test]
+fn test_supplied_header_row_index_is_used() {
+    let sheet = build_sheet(vec![
+        vec![text("ignored"), text("row")],
+        vec![text("id"), text("amount")],
+        vec![text("001"), Data::Int(10)],
+    ]);
+
+    let (headers, rows) = ExcelFileReader::read_column_headers_and_file_rows(&sheet, Some(1), ',').unwrap();
+
+    assert_eq!(headers, vec!["id", "amount"]);
+    assert_eq!(rows.len(), 1);
+    assert_eq!(rows[0].cells, vec!["001", "10"]);
+}
+
+#[test]
+fn test_header_row_index_beyond_the_sheet_is_rejected() {
+    let sheet = build_sheet(vec![vec![text("id")]]);
+
+    let result = ExcelFileReader::read_column_headers_and_file_rows(&sheet, Some(5), ',');
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_cells_are_converted_to_stable_text() {
+    assert_eq!(ExcelFileReader::convert_cell_to_text(&Data::Empty), "");
+    assert_eq!(ExcelFileReader::convert_cell_to_text(&text("  padded ")), "padded");
+    assert_eq!(ExcelFileReader::convert_cell_to_text(&Data::Float(12.0)), "12");
+    assert_eq!(ExcelFileReader::convert_cell_to_text(&Data::Float(0.1)), "0.1");
+    assert_eq!(ExcelFileReader::convert_cell_to_text(&Data::Int(-7)), "-7");
+    assert_eq!(ExcelFileReader::convert_cell_to_text(&Data::Bool(true)), "true");
+    assert_eq!(ExcelFileReader::convert_cell_to_text(&Data::DateTimeIso("2022-03-01".to_string())), "2022-03-01");
+}
+
+#[test]
+fn test_rows_keep_the_typed_value_of_each_cell() {
+    let sheet = build_sheet(vec![
+        vec![text("id"), text("amount"), text("is_reversal"), text("settled_on")],
+        vec![text("001"), Data::Float(15.5), Data::Bool(false), Data::DateTimeIso("2022-03-01T10:30:00".to_string())],
+        vec![text("002"), Data::Int(10)],
+    ]);
+
+    let (_, rows) = ExcelFileReader::read_column_headers_and_file_rows(&sheet, Some(0), ',').unwrap();
+
+    assert_eq!(rows[0].values, Some(vec![
+        CellValue::Text("001".to_string()),
+        CellValue::Decimal(15.5),
+        CellValue::Boolean(false),
+        CellValue::DateTime("2022-03-01T10:30:00".to_string()),
+    ]));
+    assert_eq!(rows[1].values, Some(vec![
+        CellValue::Text("002".to_string()),
+        CellValue::Integer(10),
+        CellValue::Empty,
+        CellValue::Empty,
+    ]));
+    assert_eq!(rows[1].cells.len(), rows[1].values.clone().unwrap().len());
+}
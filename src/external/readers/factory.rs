@@ -1,23 +1,110 @@
 use crate::internal::{
-    interfaces::file_reader::FileReader,
+    interfaces::file_reader::{FileReader, FileRowStream},
+    models::entities::{
+        file_format::FileFormat,
+        file_read_options::FileReadOptions,
+        inferred_file_settings::InferredFileSettings,
+        statement_summary::StatementSummary,
+    },
     shared_reconciler_rust_libraries::models::entities::{
         app_errors::AppError,
-        file::{File, FileThatHasBeenRead, SupportedFileExtension},
+        file::File,
     },
 };
 use async_trait::async_trait;
 
-use super::{csv::CsvFileReader, excel::ExcelFileReader, pdf::PdfFileReader};
+use super::{
+    bai2::Bai2FileReader,
+    camt::CamtFileReader,
+    csv::CsvFileReader,
+    excel::ExcelFileReader,
+    fixed_width::FixedWidthFileReader,
+    json::{JsonFileReader, NdjsonFileReader},
+    mt940::Mt940FileReader,
+    nacha::NachaFileReader,
+    ofx::OfxFileReader,
+    parsed_files::ParsedFileCache,
+    pdf::PdfFileReader,
+};
 
-pub struct FileReaderFactory {}
+pub struct FileReaderFactory {
+    //files that are parsed in full are only parsed once for all the reads of a split file job
+    pub parsed_files: ParsedFileCache,
+}
 
 #[async_trait]
 impl FileReader for FileReaderFactory {
-    async fn read_file(&self, file: &File) -> Result<FileThatHasBeenRead, AppError> {
-        match file.file_extension {
-            SupportedFileExtension::Csv => CsvFileReader::read_file(file),
-            SupportedFileExtension::Excel => ExcelFileReader::read_file(file),
-            SupportedFileExtension::Pdf => PdfFileReader::read_file(file),
+    async fn infer_file_settings(&self, file: &File, read_options: &FileReadOptions) -> Result<Option<InferredFileSettings>, AppError> {
+        match FileFormat::from_file(file, read_options) {
+            FileFormat::Csv => CsvFileReader::infer_file_settings(file, read_options).map(Some),
+            FileFormat::Excel => Ok(None),
+            FileFormat::Pdf => Ok(None),
+            FileFormat::FixedWidth => Ok(None),
+            FileFormat::Json => Ok(None),
+            FileFormat::Ndjson => Ok(None),
+            FileFormat::Mt940 => Ok(None),
+            FileFormat::Camt => Ok(None),
+            FileFormat::Bai2 => Ok(None),
+            FileFormat::Ofx => Ok(None),
+            FileFormat::Nacha => Ok(None),
+        }
+    }
+
+    async fn read_statement_summary(&self, file: &File, read_options: &FileReadOptions) -> Result<Option<StatementSummary>, AppError> {
+        match FileFormat::from_file(file, read_options) {
+            FileFormat::Mt940 => Ok(self.parsed_files.read(file, read_options, Mt940FileReader::read_file)?.statement_summary.clone()),
+            FileFormat::Camt => CamtFileReader::read_statement_summary(file, read_options).map(Some),
+            FileFormat::Bai2 => Ok(self.parsed_files.read(file, read_options, Bai2FileReader::read_file)?.statement_summary.clone()),
+            FileFormat::Ofx => Ok(self.parsed_files.read(file, read_options, OfxFileReader::read_file)?.statement_summary.clone()),
+            FileFormat::Csv | FileFormat::Excel | FileFormat::Pdf | FileFormat::FixedWidth | FileFormat::Json | FileFormat::Ndjson | FileFormat::Nacha => Ok(None),
+        }
+    }
+
+    async fn read_column_headers(&self, file: &File, read_options: &FileReadOptions) -> Result<Vec<String>, AppError> {
+        match FileFormat::from_file(file, read_options) {
+            FileFormat::Csv => CsvFileReader::read_column_headers(file, read_options),
+            FileFormat::Excel => Ok(self.parsed_files.read(file, read_options, ExcelFileReader::read_file)?.column_headers.clone()),
+            FileFormat::Pdf => Ok(self.parsed_files.read(file, read_options, PdfFileReader::read_file)?.column_headers.clone()),
+            FileFormat::FixedWidth => FixedWidthFileReader::read_column_headers(file, read_options),
+            FileFormat::Json => Ok(self.parsed_files.read(file, read_options, JsonFileReader::read_file)?.column_headers.clone()),
+            FileFormat::Ndjson => NdjsonFileReader::read_column_headers(file, read_options),
+            FileFormat::Mt940 => Ok(self.parsed_files.read(file, read_options, Mt940FileReader::read_file)?.column_headers.clone()),
+            FileFormat::Camt => CamtFileReader::read_column_headers(file, read_options),
+            FileFormat::Bai2 => Ok(self.parsed_files.read(file, read_options, Bai2FileReader::read_file)?.column_headers.clone()),
+            FileFormat::Ofx => Ok(self.parsed_files.read(file, read_options, OfxFileReader::read_file)?.column_headers.clone()),
+            FileFormat::Nacha => Ok(self.parsed_files.read(file, read_options, NachaFileReader::read_file)?.column_headers.clone()),
+        }
+    }
+
+    async fn count_file_rows(&self, file: &File, read_options: &FileReadOptions) -> Result<u64, AppError> {
+        match FileFormat::from_file(file, read_options) {
+            FileFormat::Csv => CsvFileReader::count_file_rows(file, read_options),
+            FileFormat::Excel => Ok(self.parsed_files.read(file, read_options, ExcelFileReader::read_file)?.file_rows.len() as u64),
+            FileFormat::Pdf => Ok(self.parsed_files.read(file, read_options, PdfFileReader::read_file)?.file_rows.len() as u64),
+            FileFormat::FixedWidth => FixedWidthFileReader::count_file_rows(file, read_options),
+            FileFormat::Json => Ok(self.parsed_files.read(file, read_options, JsonFileReader::read_file)?.file_rows.len() as u64),
+            FileFormat::Ndjson => NdjsonFileReader::count_file_rows(file, read_options),
+            FileFormat::Mt940 => Ok(self.parsed_files.read(file, read_options, Mt940FileReader::read_file)?.file_rows.len() as u64),
+            FileFormat::Camt => CamtFileReader::count_file_rows(file, read_options),
+            FileFormat::Bai2 => Ok(self.parsed_files.read(file, read_options, Bai2FileReader::read_file)?.file_rows.len() as u64),
+            FileFormat::Ofx => Ok(self.parsed_files.read(file, read_options, OfxFileReader::read_file)?.file_rows.len() as u64),
+            FileFormat::Nacha => Ok(self.parsed_files.read(file, read_options, NachaFileReader::read_file)?.file_rows.len() as u64),
+        }
+    }
+
+    async fn stream_file_rows(&self, file: &File, read_options: &FileReadOptions) -> Result<FileRowStream, AppError> {
+        match FileFormat::from_file(file, read_options) {
+            FileFormat::Csv => CsvFileReader::stream_file_rows(file, read_options),
+            FileFormat::Excel => Ok(Box::new(self.parsed_files.take(file, read_options, ExcelFileReader::read_file)?.file_rows.into_iter().map(Ok))),
+            FileFormat::Pdf => Ok(Box::new(self.parsed_files.take(file, read_options, PdfFileReader::read_file)?.file_rows.into_iter().map(Ok))),
+            FileFormat::FixedWidth => FixedWidthFileReader::stream_file_rows(file, read_options),
+            FileFormat::Json => Ok(Box::new(self.parsed_files.take(file, read_options, JsonFileReader::read_file)?.file_rows.into_iter().map(Ok))),
+            FileFormat::Ndjson => NdjsonFileReader::stream_file_rows(file, read_options),
+            FileFormat::Mt940 => Ok(Box::new(self.parsed_files.take(file, read_options, Mt940FileReader::read_file)?.file_rows.into_iter().map(Ok))),
+            FileFormat::Camt => CamtFileReader::stream_file_rows(file, read_options),
+            FileFormat::Bai2 => Ok(Box::new(self.parsed_files.take(file, read_options, Bai2FileReader::read_file)?.file_rows.into_iter().map(Ok))),
+            FileFormat::Ofx => Ok(Box::new(self.parsed_files.take(file, read_options, OfxFileReader::read_file)?.file_rows.into_iter().map(Ok))),
+            FileFormat::Nacha => Ok(Box::new(self.parsed_files.take(file, read_options, NachaFileReader::read_file)?.file_rows.into_iter().map(Ok))),
         }
     }
 }
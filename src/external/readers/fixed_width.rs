@@ -0,0 +1,195 @@
+use std::collections::VecDeque;
+use std::io::{BufRead, Lines};
+
+use crate::external::readers::common::{get_column_delimiter, get_file_path};
+use crate::external::readers::text_encoding::open_text_file;
+use crate::internal::interfaces::file_reader::FileRowStream;
+use crate::internal::models::entities::{
+    file_read_options::{FileReadOptions, FixedWidthColumn, FixedWidthReadOptions, FixedWidthTrim},
+    file_row::FileRow,
+};
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+//the pad character of zero padded numbers
+const ZERO_PAD_CHARACTER: char = '0';
+
+pub struct FixedWidthFileReader {}
+
+impl FixedWidthFileReader {
+    //the column headers come from the layout in the read options, not from the file
+    pub fn read_column_headers(_file: &File, read_options: &FileReadOptions) -> Result<Vec<String>, AppError> {
+        let fixed_width_read_options = FixedWidthFileReader::get_fixed_width_read_options(read_options)?;
+        return Ok(fixed_width_read_options.columns.iter().map(|column| column.name.clone()).collect());
+    }
+
+    pub fn count_file_rows(file: &File, read_options: &FileReadOptions) -> Result<u64, AppError> {
+        let mut row_count = 0;
+        for file_row in FixedWidthFileReader::stream_file_rows(file, read_options)? {
+            let _ = file_row?;
+            row_count = row_count + 1;
+        }
+        return Ok(row_count);
+    }
+
+    pub fn stream_file_rows(file: &File, read_options: &FileReadOptions) -> Result<FileRowStream, AppError> {
+        let fixed_width_read_options = FixedWidthFileReader::get_fixed_width_read_options(read_options)?;
+
+        let file_path = get_file_path(file, "fixed width")?;
+
+        let text_file_reader = open_text_file(&file_path, fixed_width_read_options.encoding.as_deref())?;
+        let column_delimiter = get_column_delimiter(file);
+
+        return Ok(Box::new(FixedWidthFileReader::convert_records_into_file_rows(
+            text_file_reader,
+            fixed_width_read_options,
+            column_delimiter,
+        )));
+    }
+
+    //header and trailer records are dropped, as are blank lines and records with a skipped prefix.
+    //every record left becomes a file row numbered from 1
+    pub(crate) fn convert_records_into_file_rows<R: BufRead>(
+        reader: R,
+        fixed_width_read_options: FixedWidthReadOptions,
+        column_delimiter: char,
+    ) -> impl Iterator<Item = Result<FileRow, AppError>> {
+        let records = FixedWidthRecords {
+            lines: reader.lines(),
+            header_records_left_to_skip: fixed_width_read_options.header_record_count.unwrap_or(0),
+            trailer_record_count: fixed_width_read_options.trailer_record_count.unwrap_or(0),
+            buffered_records: VecDeque::new(),
+        };
+        let skip_record_prefixes = fixed_width_read_options.skip_record_prefixes.clone().unwrap_or_default();
+        let columns = fixed_width_read_options.columns;
+
+        return records
+            .filter(move |record| match record {
+                Ok(record) => !skip_record_prefixes.iter().any(|prefix| !prefix.is_empty() && record.starts_with(prefix.as_str())),
+                Err(_) => true,
+            })
+            .enumerate()
+            .map(move |(row_index, record)| {
+                let record = record?;
+                let cells = columns.iter().map(|column| FixedWidthFileReader::read_cell(&record, column)).collect();
+                return Ok(FileRow::from_cells(row_index as u64 + 1, cells, column_delimiter));
+            });
+    }
+
+    //positions are counted in characters, so a column starts in the same place whatever the encoding.
+    //a record that ends before a column does gives that column an empty cell
+    fn read_cell(record: &str, column: &FixedWidthColumn) -> String {
+        let value: String = record.chars().skip(column.start).take(column.length).collect();
+        let pad_character = column.pad_character.unwrap_or(' ');
+
+        //zeros pad numbers on the left, the zeros at the end of a number are part of it
+        if pad_character == ZERO_PAD_CHARACTER {
+            return match column.trim.unwrap_or(FixedWidthTrim::Both) {
+                FixedWidthTrim::None => value,
+                FixedWidthTrim::End => value.trim_end_matches(pad_character).to_string(),
+                FixedWidthTrim::Start | FixedWidthTrim::Both => FixedWidthFileReader::trim_zero_padding(&value),
+            };
+        }
+
+        return match column.trim.unwrap_or(FixedWidthTrim::Both) {
+            FixedWidthTrim::None => value,
+            FixedWidthTrim::Start => value.trim_start_matches(pad_character).to_string(),
+            FixedWidthTrim::End => value.trim_end_matches(pad_character).to_string(),
+            FixedWidthTrim::Both => value.trim_matches(pad_character).to_string(),
+        };
+    }
+
+    //a value that is all padding is zero, and a fraction keeps the zero in front of its decimal point
+    fn trim_zero_padding(value: &str) -> String {
+        let trimmed_value = value.trim_start_matches(ZERO_PAD_CHARACTER);
+
+        if !value.is_empty() && (trimmed_value.is_empty() || trimmed_value.starts_with(['.', ','])) {
+            return format!("{}{}", ZERO_PAD_CHARACTER, trimmed_value);
+        }
+
+        return trimmed_value.to_string();
+    }
+
+    fn get_fixed_width_read_options(read_options: &FileReadOptions) -> Result<FixedWidthReadOptions, AppError> {
+        let fixed_width_read_options = match read_options.fixed_width.clone() {
+            Some(fixed_width_read_options) if !fixed_width_read_options.columns.is_empty() => fixed_width_read_options,
+            _ => {
+                return app_error_with_msg(
+                    AppErrorKind::BadClientRequest,
+                    "please supply the columns of the fixed width file in read_options.fixed_width",
+                );
+            }
+        };
+
+        if let Some(column) = fixed_width_read_options.columns.iter().find(|column| column.length == 0) {
+            return app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!("the fixed width column {} must be at least one character long", column.name),
+            );
+        }
+
+        //a character that belongs to two columns would be read into both of them
+        let mut columns_by_start: Vec<&FixedWidthColumn> = fixed_width_read_options.columns.iter().collect();
+        columns_by_start.sort_by_key(|column| column.start);
+        for adjacent_columns in columns_by_start.windows(2) {
+            let (column, next_column) = (adjacent_columns[0], adjacent_columns[1]);
+            if column.start.saturating_add(column.length) > next_column.start {
+                return app_error_with_msg(
+                    AppErrorKind::BadClientRequest,
+                    &format!(
+                        "the fixed width columns {} (characters {} to {}) and {} (from character {}) overlap",
+                        column.name,
+                        column.start,
+                        column.start.saturating_add(column.length) - 1,
+                        next_column.name,
+                        next_column.start
+                    ),
+                );
+            }
+        }
+
+        return Ok(fixed_width_read_options);
+    }
+}
+
+//the records in a fixed width file without its header and trailer records.
+//the last trailer_record_count records are held back until the next record is read,
+//so the trailer records are the ones still held when the file ends
+struct FixedWidthRecords<R: BufRead> {
+    lines: Lines<R>,
+    header_records_left_to_skip: usize,
+    trailer_record_count: usize,
+    buffered_records: VecDeque<String>,
+}
+
+impl<R: BufRead> Iterator for FixedWidthRecords<R> {
+    type Item = Result<String, AppError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let line = match self.lines.next()? {
+                Ok(line) => line,
+                //text that cannot be decoded is a problem with the file, not with the service
+                Err(e) if e.kind() == std::io::ErrorKind::InvalidData => return Some(app_error(AppErrorKind::BadClientRequest, Box::new(e))),
+                Err(e) => return Some(app_error(AppErrorKind::InternalError, Box::new(e))),
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if self.header_records_left_to_skip > 0 {
+                self.header_records_left_to_skip = self.header_records_left_to_skip - 1;
+                continue;
+            }
+
+            self.buffered_records.push_back(line);
+            if self.buffered_records.len() > self.trailer_record_count {
+                return self.buffered_records.pop_front().map(Ok);
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::io::Cursor;
+
+use crate::external::readers::fixed_width::FixedWidthFileReader;
+use crate::external::readers::test_files::{get_dummy_file, get_dummy_file_without_path};
+use crate::internal::models::entities::file_format::FileFormat;
+use crate::internal::models::entities::file_read_options::{FileReadOptions, FixedWidthColumn, FixedWidthReadOptions, FixedWidthTrim};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
+
+fn get_column(name: &str, start: usize, length: usize) -> FixedWidthColumn {
+    return FixedWidthColumn {
+        name: name.to_string(),
+        start,
+        length,
+        trim: None,
+        pad_character: None,
+    };
+}
+
+fn get_dummy_read_options() -> FixedWidthReadOptions {
+    return FixedWidthReadOptions {
+        columns: vec![
+            get_column("account", 0, 6),
+            get_column("narration", 6, 12),
+            FixedWidthColumn {
+                trim: Some(FixedWidthTrim::Start),
+                pad_character: Some('0'),
+                ..get_column("amount", 18, 8)
+            },
+        ],
+        ..FixedWidthReadOptions::default()
+    };
+}
+
+#[test]
+fn test_records_are_cut_into_columns_and_trimmed() {
+    let contents = "ACC001Payment ref 00002000\nACC002Café        00000015\r\nACC003Short\n";
+
+    let rows: Vec<_> = FixedWidthFileReader::convert_records_into_file_rows(Cursor::new(contents), get_dummy_read_options(), ',')
+        .map(|x| x.unwrap())
+        .collect();
+
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].row_number, 1);
+    assert_eq!(rows[0].cells, vec!["ACC001", "Payment ref", "2000"]);
+    assert_eq!(rows[0].raw_data, "ACC001,Payment ref,2000");
+    assert_eq!(rows[1].cells, vec!["ACC002", "Café", "15"]);
+    assert_eq!(rows[2].cells, vec!["ACC003", "Short", ""]);
+}
+
+#[test]
+fn test_header_trailer_and_prefixed_records_are_skipped() {
+    let contents = "HDR20220301\nFILE HEADER LINE 2\nACC001Payment     00002000\nNOTE a comment\n\nACC002Refund      00000015\nTRL000002\n";
+    let read_options = FixedWidthReadOptions {
+        header_record_count: Some(2),
+        trailer_record_count: Some(1),
+        skip_record_prefixes: Some(vec![String::from("NOTE")]),
+        ..get_dummy_read_options()
+    };
+
+    let rows: Vec<_> = FixedWidthFileReader::convert_records_into_file_rows(Cursor::new(contents), read_options, ',')
+        .map(|x| x.unwrap())
+        .collect();
+
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].cells, vec!["ACC001", "Payment", "2000"]);
+    assert_eq!(rows[1].row_number, 2);
+    assert_eq!(rows[1].cells, vec!["ACC002", "Refund", "15"]);
+}
+
+#[test]
+fn test_fixed_width_format_is_chosen_by_the_read_options() {
+    let file = get_dummy_file("svc-file-reader-processor-test-fixed-width.txt", "ACC001Payment     00002000\n");
+    let read_options = FileReadOptions {
+        format: Some(FileFormat::FixedWidth),
+        fixed_width: Some(get_dummy_read_options()),
+        ..FileReadOptions::default()
+    };
+
+    let headers = FixedWidthFileReader::read_column_headers(&file, &read_options).unwrap();
+    let row_count = FixedWidthFileReader::count_file_rows(&file, &read_options).unwrap();
+
+    assert_eq!(FileFormat::from_file(&file, &read_options), FileFormat::FixedWidth);
+    assert_eq!(FileFormat::from_file(&file, &FileReadOptions::default()), FileFormat::Csv);
+    assert_eq!(headers, vec!["account", "narration", "amount"]);
+    assert_eq!(row_count, 1);
+}
+
+#[test]
+fn test_missing_or_empty_columns_are_rejected() {
+    let file = get_dummy_file_without_path();
+    let zero_length_column = FileReadOptions {
+        fixed_width: Some(FixedWidthReadOptions {
+            columns: vec![get_column("account", 0, 0)],
+            ..FixedWidthReadOptions::default()
+        }),
+        ..FileReadOptions::default()
+    };
+
+    let overlapping_columns = FileReadOptions {
+        fixed_width: Some(FixedWidthReadOptions {
+            columns: vec![get_column("narration", 6, 12), get_column("account", 0, 7)],
+            ..FixedWidthReadOptions::default()
+        }),
+        ..FileReadOptions::default()
+    };
+
+    let no_columns = FixedWidthFileReader::read_column_headers(&file, &FileReadOptions::default());
+    let zero_length = FixedWidthFileReader::read_column_headers(&file, &zero_length_column);
+    let overlapping = FixedWidthFileReader::read_column_headers(&file, &overlapping_columns);
+
+    assert_eq!(no_columns.unwrap_err().kind, AppErrorKind::BadClientRequest);
+    assert_eq!(zero_length.unwrap_err().kind, AppErrorKind::BadClientRequest);
+
+    let overlapping = overlapping.unwrap_err();
+    assert_eq!(overlapping.kind, AppErrorKind::BadClientRequest);
+    assert!(overlapping.message.contains("account (characters 0 to 6) and narration (from character 6) overlap"));
+}
+
+#[test]
+fn test_zero_padded_numbers_keep_a_digit_in_front_of_the_decimal_point() {
+    let amount_column = |trim: Option<FixedWidthTrim>| FixedWidthColumn {
+        trim,
+        pad_character: Some('0'),
+        ..get_column("amount", 0, 10)
+    };
+    let read_options = FixedWidthReadOptions {
+        columns: vec![amount_column(Some(FixedWidthTrim::Start)), amount_column(None)],
+        ..FixedWidthReadOptions::default()
+    };
+    let contents = "0000000000\n0000000.50\n0000002000\n0000012,30\n";
+
+    let rows: Vec<_> = FixedWidthFileReader::convert_records_into_file_rows(Cursor::new(contents), read_options, ';')
+        .map(|x| x.unwrap().cells)
+        .collect();
+
+    assert_eq!(rows, vec![
+        vec!["0", "0"],
+        vec!["0.50", "0.50"],
+        vec!["2000", "2000"],
+        vec!["12,30", "12,30"],
+    ]);
+}
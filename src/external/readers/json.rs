@@ -0,0 +1,432 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+
+use crate::external::readers::common::{convert_amount_to_cell_value, get_column_delimiter, get_file_path, read_text_file};
+use crate::external::readers::json_path::JsonPath;
+use crate::external::readers::parsed_files::ParsedFile;
+use crate::external::readers::text_encoding::open_text_file;
+use crate::internal::interfaces::file_reader::FileRowStream;
+use crate::internal::models::entities::{
+    file_read_options::{FileReadOptions, JsonReadOptions},
+    file_row::{CellValue, FileRow},
+};
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+//the column a record that is a single value rather than an object is put in
+const SINGLE_VALUE_COLUMN_HEADER: &str = "value";
+
+const MAX_NESTING_DEPTH: usize = 128;
+
+//a record with its nested fields flattened into dotted names, e.g. "customer.address.city"
+type FlattenedRecord = Vec<(String, JsonValue)>;
+
+//a json value that keeps its numbers as they are written in the file, e.g. "2000.50" rather than 2000.5.
+//the fields of an object are kept in the order they are written in
+#[derive(Clone, PartialEq, Debug)]
+pub(crate) enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+pub struct JsonFileReader {}
+
+impl JsonFileReader {
+    //a json document has to be parsed in full before the records in it can be found,
+    //so its column headers and rows are all read from a single parse
+    pub fn read_file(file: &File, read_options: &FileReadOptions) -> Result<ParsedFile, AppError> {
+        let json_read_options = read_options.json.clone().unwrap_or_default();
+        let records_path = get_records_path(&json_read_options)?;
+
+        let text = read_text_file(file, "json", json_read_options.encoding.as_deref())?;
+
+        let document = match parse_json_value(&text) {
+            Ok(document) => document,
+            Err(e) => { return app_error(AppErrorKind::BadClientRequest, Box::new(e)); }
+        };
+
+        let records: Vec<FlattenedRecord> = records_path.select_records(&document).into_iter().map(flatten_record).collect();
+
+        let column_headers = match json_read_options.fields {
+            Some(fields) => fields,
+            None => {
+                let mut column_headers = vec![];
+                for record in &records {
+                    add_column_headers(&mut column_headers, record);
+                }
+                column_headers
+            }
+        };
+
+        let column_delimiter = get_column_delimiter(file);
+        let file_rows = records
+            .iter()
+            .enumerate()
+            .map(|(row_index, record)| convert_record_into_file_row(row_index as u64 + 1, record, &column_headers, column_delimiter))
+            .collect::<Result<Vec<FileRow>, AppError>>()?;
+
+        return Ok(ParsedFile {
+            column_headers,
+            file_rows,
+            statement_summary: None,
+        });
+    }
+}
+
+//newline delimited json, one json value per line
+pub struct NdjsonFileReader {}
+
+impl NdjsonFileReader {
+    //the column headers are the fields in the read options. without them, the whole file is
+    //read to find every field that any record has, but only the field names are kept
+    pub fn read_column_headers(file: &File, read_options: &FileReadOptions) -> Result<Vec<String>, AppError> {
+        let json_read_options = read_options.json.clone().unwrap_or_default();
+        if let Some(fields) = json_read_options.fields {
+            return Ok(fields);
+        }
+
+        let mut column_headers = vec![];
+        for record in NdjsonFileReader::read_records(file, read_options)? {
+            add_column_headers(&mut column_headers, &record?);
+        }
+        return Ok(column_headers);
+    }
+
+    pub fn count_file_rows(file: &File, read_options: &FileReadOptions) -> Result<u64, AppError> {
+        let mut row_count = 0;
+        for record in NdjsonFileReader::read_records(file, read_options)? {
+            let _ = record?;
+            row_count = row_count + 1;
+        }
+        return Ok(row_count);
+    }
+
+    pub fn stream_file_rows(file: &File, read_options: &FileReadOptions) -> Result<FileRowStream, AppError> {
+        let column_headers = NdjsonFileReader::read_column_headers(file, read_options)?;
+        let column_delimiter = get_column_delimiter(file);
+
+        let file_rows = NdjsonFileReader::read_records(file, read_options)?
+            .enumerate()
+            .map(move |(row_index, record)| convert_record_into_file_row(row_index as u64 + 1, &record?, &column_headers, column_delimiter));
+
+        return Ok(Box::new(file_rows));
+    }
+
+    fn read_records(file: &File, read_options: &FileReadOptions) -> Result<impl Iterator<Item = Result<FlattenedRecord, AppError>> + Send, AppError> {
+        let json_read_options = read_options.json.clone().unwrap_or_default();
+        let records_path = get_records_path(&json_read_options)?;
+        let text_file_reader = open_text_file(&get_file_path(file, "json")?, json_read_options.encoding.as_deref())?;
+
+        return Ok(NdjsonFileReader::convert_lines_into_records(text_file_reader, records_path));
+    }
+
+    //blank lines are skipped, and the records path is followed within each line's value
+    pub(crate) fn convert_lines_into_records<R: BufRead>(reader: R, records_path: JsonPath) -> impl Iterator<Item = Result<FlattenedRecord, AppError>> {
+        return reader
+            .lines()
+            .enumerate()
+            .flat_map(move |(line_index, line)| {
+                let line = match line {
+                    Ok(line) => line,
+                    //text that cannot be decoded is a problem with the file, not with the service
+                    Err(e) if e.kind() == std::io::ErrorKind::InvalidData => return vec![app_error(AppErrorKind::BadClientRequest, Box::new(e))],
+                    Err(e) => return vec![app_error(AppErrorKind::InternalError, Box::new(e))],
+                };
+
+                if line.trim().is_empty() {
+                    return vec![];
+                }
+
+                let value = match parse_json_value(&line) {
+                    Ok(value) => value,
+                    Err(e) => {
+                        return vec![app_error_with_msg(
+                            AppErrorKind::BadClientRequest,
+                            &format!("line {} is not valid json: {}", line_index + 1, e),
+                        )];
+                    }
+                };
+
+                return records_path.select_records(&value).into_iter().map(|record| Ok(flatten_record(record))).collect();
+            });
+    }
+}
+
+fn get_records_path(json_read_options: &JsonReadOptions) -> Result<JsonPath, AppError> {
+    return JsonPath::parse(json_read_options.records_path.as_deref().unwrap_or("$"));
+}
+
+/**
+parses json text in a single pass without losing the digits its numbers are written with or the order
+its fields are written in. strings are decoded and numbers are checked by serde_json, whose
+arbitrary_precision feature would keep the digits as well, but it changes how numbers are read
+into the untagged enums in the requests this service accepts
+
+# Errors
+
+a serde_json error for text that is not valid json
+ */
+pub(crate) fn parse_json_value(text: &str) -> Result<JsonValue, serde_json::Error> {
+    let mut parser = JsonParser { text, position: 0 };
+
+    let value = parser.parse_value(0)?;
+    parser.skip_whitespace();
+    if parser.position < text.len() {
+        return Err(parser.error("trailing characters"));
+    }
+    return Ok(value);
+}
+
+struct JsonParser<'a> {
+    text: &'a str,
+
+    //the byte offset of the next character to be read
+    position: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn parse_value(&mut self, depth: usize) -> Result<JsonValue, serde_json::Error> {
+        self.skip_whitespace();
+
+        return match self.peek() {
+            Some(b'{') => self.parse_object(depth + 1),
+            Some(b'[') => self.parse_array(depth + 1),
+            Some(b'"') => Ok(JsonValue::String(self.parse_string()?)),
+            Some(b't') => self.parse_literal("true", JsonValue::Bool(true)),
+            Some(b'f') => self.parse_literal("false", JsonValue::Bool(false)),
+            Some(b'n') => self.parse_literal("null", JsonValue::Null),
+            Some(_) => self.parse_number(),
+            None => Err(self.error("EOF while parsing a value")),
+        };
+    }
+
+    //a field that is written more than once keeps the last value it is given, as it does in serde_json
+    fn parse_object(&mut self, depth: usize) -> Result<JsonValue, serde_json::Error> {
+        self.check_depth(depth)?;
+        self.position = self.position + 1;
+
+        let mut fields: Vec<(String, JsonValue)> = vec![];
+        let mut field_indexes: HashMap<String, usize> = HashMap::new();
+
+        self.skip_whitespace();
+        if self.peek() == Some(b'}') {
+            self.position = self.position + 1;
+            return Ok(JsonValue::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek() != Some(b'"') {
+                return Err(self.error("expected a field name"));
+            }
+            let name = self.parse_string()?;
+
+            self.skip_whitespace();
+            if self.next_byte() != Some(b':') {
+                return Err(self.error("expected `:`"));
+            }
+            let value = self.parse_value(depth)?;
+
+            match field_indexes.get(&name) {
+                Some(index) => fields[*index].1 = value,
+                None => {
+                    field_indexes.insert(name.clone(), fields.len());
+                    fields.push((name, value));
+                }
+            }
+
+            self.skip_whitespace();
+            match self.next_byte() {
+                Some(b',') => continue,
+                Some(b'}') => return Ok(JsonValue::Object(fields)),
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<JsonValue, serde_json::Error> {
+        self.check_depth(depth)?;
+        self.position = self.position + 1;
+
+        let mut items = vec![];
+
+        self.skip_whitespace();
+        if self.peek() == Some(b']') {
+            self.position = self.position + 1;
+            return Ok(JsonValue::Array(items));
+        }
+
+        loop {
+            items.push(self.parse_value(depth)?);
+
+            self.skip_whitespace();
+            match self.next_byte() {
+                Some(b',') => continue,
+                Some(b']') => return Ok(JsonValue::Array(items)),
+                _ => return Err(self.error("expected `,` or `]`")),
+            }
+        }
+    }
+
+    //the end of the string is found here and its escapes are decoded by serde_json
+    fn parse_string(&mut self) -> Result<String, serde_json::Error> {
+        let bytes = self.text.as_bytes();
+        let start = self.position;
+        let mut end = start + 1;
+
+        loop {
+            match bytes.get(end) {
+                Some(b'"') => break,
+                Some(b'\\') => end = end + 2,
+                Some(_) => end = end + 1,
+                None => {
+                    self.position = bytes.len();
+                    return Err(self.error("EOF while parsing a string"));
+                }
+            }
+        }
+
+        self.position = end + 1;
+        return serde_json::from_str(&self.text[start..=end]);
+    }
+
+    //only text that serde_json accepts as a number is kept as one
+    fn parse_number(&mut self) -> Result<JsonValue, serde_json::Error> {
+        let start = self.position;
+        while let Some(b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E') = self.peek() {
+            self.position = self.position + 1;
+        }
+
+        if start == self.position {
+            return Err(self.error("expected value"));
+        }
+
+        let number = &self.text[start..self.position];
+        serde_json::from_str::<serde_json::Number>(number)?;
+        return Ok(JsonValue::Number(number.to_string()));
+    }
+
+    fn parse_literal(&mut self, literal: &str, value: JsonValue) -> Result<JsonValue, serde_json::Error> {
+        if !self.text[self.position..].starts_with(literal) {
+            return Err(self.error("expected value"));
+        }
+
+        self.position = self.position + literal.len();
+        return Ok(value);
+    }
+
+    //the same limit serde_json has, so that deeply nested text cannot overflow the stack
+    fn check_depth(&self, depth: usize) -> Result<(), serde_json::Error> {
+        if depth > MAX_NESTING_DEPTH {
+            return Err(self.error("recursion limit exceeded"));
+        }
+        return Ok(());
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\n' | b'\r' | b'\t') = self.peek() {
+            self.position = self.position + 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        return self.text.as_bytes().get(self.position).copied();
+    }
+
+    fn next_byte(&mut self) -> Option<u8> {
+        let next_byte = self.peek();
+        self.position = self.position + 1;
+        return next_byte;
+    }
+
+    fn error(&self, message: &str) -> serde_json::Error {
+        let bytes_read = &self.text.as_bytes()[..self.position.min(self.text.len())];
+        let line = bytes_read.iter().filter(|byte| **byte == b'\n').count() + 1;
+        let column = bytes_read.len() - bytes_read.iter().rposition(|byte| *byte == b'\n').map(|index| index + 1).unwrap_or(0) + 1;
+
+        return serde::de::Error::custom(format!("{} at line {} column {}", message, line, column));
+    }
+}
+
+//nested objects and arrays are flattened into dotted names, e.g. "customer.name" or "tags.0"
+pub(crate) fn flatten_record(record: &JsonValue) -> FlattenedRecord {
+    let mut flattened_record = vec![];
+    flatten_value("", record, &mut flattened_record);
+    return flattened_record;
+}
+
+fn flatten_value(name: &str, value: &JsonValue, flattened_record: &mut FlattenedRecord) {
+    let get_nested_name = |field: &str| if name.is_empty() { field.to_string() } else { format!("{}.{}", name, field) };
+
+    match value {
+        JsonValue::Object(fields) if !fields.is_empty() => {
+            for (field, field_value) in fields {
+                flatten_value(&get_nested_name(field), field_value, flattened_record);
+            }
+        }
+        JsonValue::Array(items) if !items.is_empty() => {
+            for (index, item) in items.iter().enumerate() {
+                flatten_value(&get_nested_name(&index.to_string()), item, flattened_record);
+            }
+        }
+        _ => {
+            let name = if name.is_empty() { SINGLE_VALUE_COLUMN_HEADER } else { name };
+            flattened_record.push((name.to_string(), value.clone()));
+        }
+    }
+}
+
+//the column headers are the union of the fields in the records, in the order they are first found
+fn add_column_headers(column_headers: &mut Vec<String>, record: &FlattenedRecord) {
+    for (name, _) in record {
+        if !column_headers.contains(name) {
+            column_headers.push(name.clone());
+        }
+    }
+}
+
+//a record without one of the columns gets an empty cell for it
+fn convert_record_into_file_row(row_number: u64, record: &FlattenedRecord, column_headers: &[String], column_delimiter: char) -> Result<FileRow, AppError> {
+    let fields: Vec<&JsonValue> = column_headers
+        .iter()
+        .map(|column_header| match record.iter().find(|(name, _)| name == column_header) {
+            Some((_, value)) => value,
+            None => &JsonValue::Null,
+        })
+        .collect();
+
+    let cells = fields.iter().map(|value| convert_value_to_text(value)).collect();
+    let values = fields.iter().map(|value| convert_value_to_cell_value(value)).collect::<Result<Vec<CellValue>, AppError>>()?;
+
+    return Ok(FileRow::from_cells(row_number, cells, column_delimiter).with_values(values));
+}
+
+fn convert_value_to_cell_value(value: &JsonValue) -> Result<CellValue, AppError> {
+    return match value {
+        JsonValue::String(text) => Ok(CellValue::Text(text.clone())),
+        JsonValue::Bool(boolean) => Ok(CellValue::Boolean(*boolean)),
+        JsonValue::Number(number) => match number.parse::<i64>() {
+            Ok(integer) => Ok(CellValue::Integer(integer)),
+            Err(_) => convert_amount_to_cell_value(number),
+        },
+        //null, and the empty objects and arrays that are left after flattening
+        _ => Ok(CellValue::Empty),
+    };
+}
+
+//numbers keep the digits they were written with in the file
+fn convert_value_to_text(value: &JsonValue) -> String {
+    return match value {
+        JsonValue::String(text) => text.clone(),
+        JsonValue::Bool(boolean) => boolean.to_string(),
+        JsonValue::Number(number) => number.clone(),
+        _ => String::new(),
+    };
+}
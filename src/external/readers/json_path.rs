@@ -0,0 +1,110 @@
+use crate::external::readers::json::JsonValue;
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error_with_msg;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{AppError, AppErrorKind};
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum JsonPathSegment {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+/**
+a JSONPath-style path to the records in a json document, such as `$.data.transactions[*]`,
+`$.statements[*].entries` or `$['data'].items[0]`. it supports keys, array indexes and the `*` wildcard,
+which is all that is needed to point at an array of records
+ */
+#[derive(Clone, PartialEq, Debug)]
+pub struct JsonPath {
+    pub segments: Vec<JsonPathSegment>,
+}
+
+impl JsonPath {
+    pub fn parse(path: &str) -> Result<JsonPath, AppError> {
+        let invalid_path = || app_error_with_msg(AppErrorKind::BadClientRequest, &format!("the records path {} is not a valid path", path));
+
+        let characters: Vec<char> = path.trim().chars().collect();
+        let mut segments = vec![];
+        let mut position = 0;
+
+        if characters.first() == Some(&'$') {
+            position = 1;
+        }
+
+        while position < characters.len() {
+            match characters[position] {
+                '.' => {
+                    let key: String = characters[position + 1..].iter().take_while(|character| **character != '.' && **character != '[').collect();
+                    if key.is_empty() {
+                        return invalid_path();
+                    }
+                    position = position + 1 + key.chars().count();
+                    segments.push(if key == "*" { JsonPathSegment::Wildcard } else { JsonPathSegment::Key(key) });
+                }
+                '[' => {
+                    let closing_position = match characters[position..].iter().position(|character| *character == ']') {
+                        Some(offset) => position + offset,
+                        None => return invalid_path(),
+                    };
+                    let selector: String = characters[position + 1..closing_position].iter().collect();
+                    let selector = selector.trim();
+
+                    let segment = if selector == "*" {
+                        JsonPathSegment::Wildcard
+                    } else if let Ok(index) = selector.parse::<usize>() {
+                        JsonPathSegment::Index(index)
+                    } else if selector.len() >= 2 && (selector.starts_with('\'') && selector.ends_with('\'') || selector.starts_with('"') && selector.ends_with('"')) {
+                        JsonPathSegment::Key(selector[1..selector.len() - 1].to_string())
+                    } else {
+                        return invalid_path();
+                    };
+
+                    segments.push(segment);
+                    position = closing_position + 1;
+                }
+                //a path can leave out the leading $.
+                _ if segments.is_empty() && position == 0 => {
+                    let key: String = characters.iter().take_while(|character| **character != '.' && **character != '[').collect();
+                    position = key.chars().count();
+                    segments.push(if key == "*" { JsonPathSegment::Wildcard } else { JsonPathSegment::Key(key) });
+                }
+                _ => return invalid_path(),
+            }
+        }
+
+        return Ok(JsonPath { segments });
+    }
+
+    //every value in the document that the path points at
+    pub fn select<'a>(&self, document: &'a JsonValue) -> Vec<&'a JsonValue> {
+        let mut selected_values = vec![document];
+
+        for segment in &self.segments {
+            selected_values = selected_values
+                .into_iter()
+                .flat_map(|value| match (segment, value) {
+                    (JsonPathSegment::Key(key), JsonValue::Object(fields)) => fields.iter().filter(|(name, _)| name == key).map(|(_, value)| value).collect(),
+                    (JsonPathSegment::Index(index), JsonValue::Array(items)) => items.get(*index).into_iter().collect(),
+                    (JsonPathSegment::Wildcard, JsonValue::Array(items)) => items.iter().collect(),
+                    (JsonPathSegment::Wildcard, JsonValue::Object(fields)) => fields.iter().map(|(_, value)| value).collect(),
+                    _ => vec![],
+                })
+                .collect();
+        }
+
+        return selected_values;
+    }
+
+    //the records the path points at. an array that the path ends on is a list of records,
+    //anything else the path ends on is a record of its own
+    pub fn select_records<'a>(&self, document: &'a JsonValue) -> Vec<&'a JsonValue> {
+        return self
+            .select(document)
+            .into_iter()
+            .flat_map(|value| match value {
+                JsonValue::Array(items) => items.iter().collect(),
+                _ => vec![value],
+            })
+            .collect();
+    }
+}
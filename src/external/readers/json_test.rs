@@ -0,0 +1,155 @@
+use std::io::Cursor;
+
+use crate::external::readers::json::{flatten_record, parse_json_value, JsonFileReader, JsonValue, NdjsonFileReader};
+use crate::external::readers::json_path::{JsonPath, JsonPathSegment};
+use crate::external::readers::test_files::get_dummy_file;
+use crate::internal::models::entities::file_format::FileFormat;
+use crate::internal::models::entities::file_read_options::{FileReadOptions, JsonReadOptions};
+use crate::internal::models::entities::file_row::CellValue;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
+
+fn get_read_options(format: FileFormat, records_path: Option<&str>, fields: Option<Vec<&str>>) -> FileReadOptions {
+    return FileReadOptions {
+        format: Some(format),
+        json: Some(JsonReadOptions {
+            records_path: records_path.map(|path| path.to_string()),
+            fields: fields.map(|fields| fields.iter().map(|field| field.to_string()).collect()),
+            encoding: None,
+        }),
+        ..FileReadOptions::default()
+    };
+}
+
+#[test]
+fn test_records_paths_are_parsed() {
+    let dotted = JsonPath::parse("$.data.transactions[*]").unwrap();
+    let bracketed = JsonPath::parse("$['data'][0].items").unwrap();
+    let without_root = JsonPath::parse("statements[*].entries").unwrap();
+
+    assert_eq!(dotted.segments, vec![
+        JsonPathSegment::Key(String::from("data")),
+        JsonPathSegment::Key(String::from("transactions")),
+        JsonPathSegment::Wildcard,
+    ]);
+    assert_eq!(bracketed.segments, vec![
+        JsonPathSegment::Key(String::from("data")),
+        JsonPathSegment::Index(0),
+        JsonPathSegment::Key(String::from("items")),
+    ]);
+    assert_eq!(without_root.segments.len(), 3);
+    assert!(JsonPath::parse("$.data[").is_err());
+    assert!(JsonPath::parse("$..data").is_err());
+}
+
+#[test]
+fn test_records_are_selected_from_every_match_of_the_path() {
+    let document = parse_json_value(r#"{"statements": [{"entries": [{"id": 1}, {"id": 2}]}, {"entries": [{"id": 3}]}]}"#).unwrap();
+
+    let records = JsonPath::parse("$.statements[*].entries").unwrap().select_records(&document);
+
+    assert_eq!(records, vec![
+        &parse_json_value(r#"{"id": 1}"#).unwrap(),
+        &parse_json_value(r#"{"id": 2}"#).unwrap(),
+        &parse_json_value(r#"{"id": 3}"#).unwrap(),
+    ]);
+}
+
+#[test]
+fn test_nested_fields_are_flattened_into_dotted_names() {
+    let record = parse_json_value(r#"{"customer": {"name": "Ann", "address": {"city": "Kampala"}}, "tags": ["a", "b"], "notes": {}}"#).unwrap();
+
+    let flattened_record = flatten_record(&record);
+    let names: Vec<&str> = flattened_record.iter().map(|(name, _)| name.as_str()).collect();
+
+    assert_eq!(names, vec!["customer.name", "customer.address.city", "tags.0", "tags.1", "notes"]);
+}
+
+#[test]
+fn test_json_file_rows_have_a_cell_for_every_field_found() {
+    let file = get_dummy_file(
+        "svc-file-reader-processor-test-records.json",
+        r#"{"data": {"transactions": [{"id": "T1", "amount": 2000.50, "customer": {"name": "Ann"}}, {"id": "T2", "amount": 15, "settled": true}]}}"#,
+    );
+    let read_options = get_read_options(FileFormat::Json, Some("$.data.transactions[*]"), None);
+
+    let parsed_file = JsonFileReader::read_file(&file, &read_options).unwrap();
+    let rows = parsed_file.file_rows;
+
+    assert_eq!(parsed_file.column_headers, vec!["id", "amount", "customer.name", "settled"]);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].cells, vec!["T1", "2000.50", "Ann", ""]);
+    assert_eq!(rows[1].row_number, 2);
+    assert_eq!(rows[1].raw_data, "T2,15,,true");
+    assert_eq!(
+        rows[1].values,
+        Some(vec![CellValue::Text(String::from("T2")), CellValue::Integer(15), CellValue::Empty, CellValue::Boolean(true)])
+    );
+}
+
+#[test]
+fn test_json_numbers_keep_the_digits_they_are_written_with() {
+    let value = parse_json_value(r#"[2000.50, 1e3, 12345678901234567890123, -0.10]"#).unwrap();
+
+    assert_eq!(value, JsonValue::Array(vec![
+        JsonValue::Number(String::from("2000.50")),
+        JsonValue::Number(String::from("1e3")),
+        JsonValue::Number(String::from("12345678901234567890123")),
+        JsonValue::Number(String::from("-0.10")),
+    ]));
+    assert!(parse_json_value("[01]").is_err());
+    assert!(parse_json_value(r#"{"amount": 12.}"#).is_err());
+}
+
+#[test]
+fn test_json_text_that_is_not_valid_is_rejected() {
+    let too_deeply_nested = format!("{}{}", "[".repeat(200), "]".repeat(200));
+
+    assert!(parse_json_value(r#"{"id": "T1",}"#).is_err());
+    assert!(parse_json_value(r#"{"id" "T1"}"#).is_err());
+    assert!(parse_json_value(r#"["T1" "T2"]"#).is_err());
+    assert!(parse_json_value(r#"{"id": "T1"} {}"#).is_err());
+    assert!(parse_json_value(r#"{"id": "T1"#).is_err());
+    assert!(parse_json_value(r#"{"id": tru}"#).is_err());
+    assert!(parse_json_value(r#"{"amount": 1e400}"#).is_err());
+    assert!(parse_json_value(&too_deeply_nested).is_err());
+}
+
+#[test]
+fn test_json_strings_are_decoded_and_repeated_fields_keep_their_last_value() {
+    let value = parse_json_value(r#" {"name": "Ann \"A\" \u00e9", "city": "Kampala", "name": "Bob"} "#).unwrap();
+
+    assert_eq!(value, JsonValue::Object(vec![
+        (String::from("name"), JsonValue::String(String::from("Bob"))),
+        (String::from("city"), JsonValue::String(String::from("Kampala"))),
+    ]));
+    assert_eq!(parse_json_value(r#""Ann \"A\" \u00e9""#).unwrap(), JsonValue::String(String::from("Ann \"A\" é")));
+}
+
+#[test]
+fn test_ndjson_file_rows_only_have_the_fields_asked_for() {
+    let file = get_dummy_file(
+        "svc-file-reader-processor-test-records.ndjson",
+        "{\"id\": \"T1\", \"amount\": 2000, \"customer\": {\"name\": \"Ann\"}}\n\n{\"id\": \"T2\", \"amount\": 15}\n",
+    );
+    let read_options = get_read_options(FileFormat::Ndjson, None, Some(vec!["id", "customer.name"]));
+
+    let headers = NdjsonFileReader::read_column_headers(&file, &read_options).unwrap();
+    let row_count = NdjsonFileReader::count_file_rows(&file, &read_options).unwrap();
+    let rows: Vec<_> = NdjsonFileReader::stream_file_rows(&file, &read_options).unwrap().map(|x| x.unwrap()).collect();
+
+    assert_eq!(headers, vec!["id", "customer.name"]);
+    assert_eq!(row_count, 2);
+    assert_eq!(rows[0].cells, vec!["T1", "Ann"]);
+    assert_eq!(rows[1].cells, vec!["T2", ""]);
+}
+
+#[test]
+fn test_ndjson_line_that_is_not_json_is_reported_with_its_line_number() {
+    let contents = "{\"id\": \"T1\"}\n{\"id\": \n";
+
+    let records: Vec<_> = NdjsonFileReader::convert_lines_into_records(Cursor::new(contents), JsonPath::parse("$").unwrap()).collect();
+
+    let error = records[1].clone().unwrap_err();
+    assert_eq!(error.kind, AppErrorKind::BadClientRequest);
+    assert!(error.message.starts_with("line 2 is not valid json"));
+}
@@ -1,9 +1,74 @@
+mod bai2;
+mod camt;
+mod common;
 mod csv;
+mod csv_sniffer;
+mod csv_tokenizer;
 mod excel;
 pub mod factory;
+mod fixed_width;
+mod json;
+mod json_path;
+mod mt940;
+mod nacha;
+mod ofx;
+pub mod parsed_files;
 mod pdf;
+mod text_encoding;
 
+#[cfg(test)]
+#[path = "./test_files.rs"]
+mod test_files;
+
+
+#[cfg(test)]
+#[path = "./bai2_test.rs"]
+mod bai2_test;
+
+#[cfg(test)]
+#[path = "./camt_test.rs"]
+mod camt_test;
 
 #[cfg(test)]
 #[path = "./csv_test.rs"]
 mod csv_test;
+
+#[cfg(test)]
+#[path = "./csv_sniffer_test.rs"]
+mod csv_sniffer_test;
+
+#[cfg(test)]
+#[path = "./excel_test.rs"]
+mod excel_test;
+
+#[cfg(test)]
+#[path = "./fixed_width_test.rs"]
+mod fixed_width_test;
+
+#[cfg(test)]
+#[path = "./json_test.rs"]
+mod json_test;
+
+#[cfg(test)]
+#[path = "./mt940_test.rs"]
+mod mt940_test;
+
+#[cfg(test)]
+#[path = "./nacha_test.rs"]
+mod nacha_test;
+
+#[cfg(test)]
+#[path = "./ofx_test.rs"]
+mod ofx_test;
+
+#[cfg(test)]
+#[path = "./parsed_files_test.rs"]
+mod parsed_files_test;
+
+#[cfg(test)]
+#[path = "./pdf_test.rs"]
+mod pdf_test;
+
+#[cfg(test)]
+#[path = "./text_encoding_test.rs"]
+mod text_encoding_test;
@@ -0,0 +1,311 @@
+use crate::external::readers::common::{convert_amount_to_cell_value, convert_yymmdd_date, get_column_delimiter, get_column_headers, read_text_file, LazyRegex};
+use crate::external::readers::parsed_files::ParsedFile;
+use crate::internal::models::entities::{
+    file_read_options::FileReadOptions,
+    file_row::{CellValue, FileRow},
+    statement_summary::{Balance, StatementBalances, StatementSummary},
+};
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error_with_msg;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+//the columns of every row read from an mt940 or mt942 statement, in order
+const MT940_COLUMN_HEADERS: [&str; 11] = [
+    "value_date",
+    "entry_date",
+    "debit_credit_mark",
+    "amount",
+    "reference",
+    "narrative",
+    "bank_reference",
+    "transaction_type",
+    "supplementary_details",
+    "account_identification",
+    "currency",
+];
+
+//the tag that starts a field, e.g. ":61:" or ":28C:"
+static FIELD_START_FORMAT: LazyRegex = LazyRegex::new(r"^:([0-9]{2}[A-Z]?):");
+
+//e.g. C220301EUR1234,56. an amount always has exactly one decimal comma
+static BALANCE_FORMAT: LazyRegex = LazyRegex::new(r"^([CD])([0-9]{6})([A-Z]{3})([0-9]+,[0-9]*)$");
+
+//e.g. 2203010301D1234,56NTRFINVOICE 42//BANKREF1
+static STATEMENT_LINE_FORMAT: LazyRegex = LazyRegex::new(r"^([0-9]{6})([0-9]{4})?(RC|RD|C|D)([A-Z])?([0-9]+,[0-9]*)([NFS][A-Z0-9]{3})(.*)$");
+
+//a tagged field in the text block of a swift message, e.g. ":61:2203010301C100,00NTRFNONREF"
+#[derive(Clone, PartialEq, Debug)]
+struct Mt940Field {
+    tag: String,
+    content: String,
+    line_number: usize,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct Mt940Statement {
+    pub balances: StatementBalances,
+    pub currency: Option<String>,
+    pub transactions: Vec<Mt940Transaction>,
+}
+
+//a :61: statement line together with the :86: narrative that follows it
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct Mt940Transaction {
+    pub value_date: String,
+    pub entry_date: Option<String>,
+    pub debit_credit_mark: String,
+    pub amount: String,
+    pub transaction_type: String,
+    pub reference: String,
+    pub bank_reference: Option<String>,
+    pub supplementary_details: Option<String>,
+    pub narrative: String,
+}
+
+pub struct Mt940FileReader {}
+
+impl Mt940FileReader {
+    //a statement's balances come after its transactions, so the whole file is parsed before any of its rows are read
+    pub fn read_file(file: &File, _read_options: &FileReadOptions) -> Result<ParsedFile, AppError> {
+        let text = read_text_file(file, "mt940", None)?;
+        let statements = Mt940FileReader::parse_statements(&text)?;
+
+        return Ok(ParsedFile {
+            column_headers: get_column_headers(&MT940_COLUMN_HEADERS),
+            file_rows: Mt940FileReader::convert_statements_into_file_rows(&statements, get_column_delimiter(file))?,
+            statement_summary: Some(StatementSummary {
+                statements: statements.into_iter().map(|statement| statement.balances).collect(),
+            }),
+        });
+    }
+
+    /**
+    parses the statements in an mt940 or mt942 file. a file can hold several swift messages,
+    with or without their {1:}{2:}{4: envelope, and a message can hold several statements,
+    each one starting at its :20: field
+     */
+    pub(crate) fn parse_statements(text: &str) -> Result<Vec<Mt940Statement>, AppError> {
+        let mut statements: Vec<Mt940Statement> = vec![];
+        let mut previous_tag = String::new();
+
+        for field in Mt940FileReader::split_into_fields(text)? {
+            if field.tag == "20" || statements.is_empty() {
+                statements.push(Mt940Statement::default());
+            }
+            let statement = statements.last_mut().unwrap();
+
+            match field.tag.as_str() {
+                "20" => statement.balances.statement_reference = Some(field.content.trim().to_string()),
+                "25" => statement.balances.account_identification = Some(field.content.trim().to_string()),
+                "28" | "28C" => statement.balances.statement_number = Some(field.content.trim().to_string()),
+                "60F" | "60M" if statement.balances.opening_balance.is_none() => {
+                    let opening_balance = Mt940Field::parse_balance(&field)?;
+                    statement.currency = Some(opening_balance.currency.clone());
+                    statement.balances.opening_balance = Some(opening_balance);
+                }
+                "62F" | "62M" => statement.balances.closing_balance = Some(Mt940Field::parse_balance(&field)?),
+                "64" => statement.balances.closing_available_balance = Some(Mt940Field::parse_balance(&field)?),
+                //mt942 interim statements have a floor limit instead of an opening balance
+                "34F" if statement.currency.is_none() => statement.currency = Some(field.content.chars().take(3).collect()),
+                "61" => statement.transactions.push(Mt940Field::parse_statement_line(&field)?),
+                //a :86: straight after a :61: is that statement line's narrative,
+                //anywhere else it is information about the whole statement
+                "86" if previous_tag == "61" => {
+                    if let Some(transaction) = statement.transactions.last_mut() {
+                        transaction.narrative = field.content.lines().map(|line| line.trim()).collect::<Vec<&str>>().join(" ");
+                    }
+                }
+                _ => {}
+            }
+
+            previous_tag = field.tag;
+        }
+
+        return Ok(statements);
+    }
+
+    //lines that do not start with a tag carry on the field before them
+    fn split_into_fields(text: &str) -> Result<Vec<Mt940Field>, AppError> {
+        let field_start = FIELD_START_FORMAT.get()?;
+        let mut fields: Vec<Mt940Field> = vec![];
+
+        for (line_index, line) in text.lines().enumerate() {
+            let mut line = line.trim_end();
+
+            //the envelope around the text block is skipped
+            if line.starts_with('{') {
+                match line.find("{4:") {
+                    Some(position) => line = &line[position + 3..],
+                    None => continue,
+                }
+            }
+            if line.is_empty() || line == "-" || line.starts_with("-}") {
+                continue;
+            }
+
+            match field_start.captures(line) {
+                Some(captures) => fields.push(Mt940Field {
+                    tag: captures[1].to_string(),
+                    content: line[captures[0].len()..].to_string(),
+                    line_number: line_index + 1,
+                }),
+                None => {
+                    if let Some(field) = fields.last_mut() {
+                        field.content.push('\n');
+                        field.content.push_str(line);
+                    }
+                }
+            }
+        }
+
+        return Ok(fields);
+    }
+
+    fn convert_statements_into_file_rows(statements: &[Mt940Statement], column_delimiter: char) -> Result<Vec<FileRow>, AppError> {
+        let mut file_rows = vec![];
+
+        for statement in statements {
+            for transaction in &statement.transactions {
+                let optional_text = |text: &Option<String>| match text {
+                    Some(text) => CellValue::Text(text.clone()),
+                    None => CellValue::Empty,
+                };
+
+                let values = vec![
+                    CellValue::Date(transaction.value_date.clone()),
+                    match &transaction.entry_date {
+                        Some(entry_date) => CellValue::Date(entry_date.clone()),
+                        None => CellValue::Empty,
+                    },
+                    CellValue::Text(transaction.debit_credit_mark.clone()),
+                    convert_amount_to_cell_value(&transaction.amount)?,
+                    CellValue::Text(transaction.reference.clone()),
+                    CellValue::Text(transaction.narrative.clone()),
+                    optional_text(&transaction.bank_reference),
+                    CellValue::Text(transaction.transaction_type.clone()),
+                    optional_text(&transaction.supplementary_details),
+                    optional_text(&statement.balances.account_identification),
+                    optional_text(&statement.currency),
+                ];
+
+                let cells = vec![
+                    transaction.value_date.clone(),
+                    transaction.entry_date.clone().unwrap_or_default(),
+                    transaction.debit_credit_mark.clone(),
+                    transaction.amount.clone(),
+                    transaction.reference.clone(),
+                    transaction.narrative.clone(),
+                    transaction.bank_reference.clone().unwrap_or_default(),
+                    transaction.transaction_type.clone(),
+                    transaction.supplementary_details.clone().unwrap_or_default(),
+                    statement.balances.account_identification.clone().unwrap_or_default(),
+                    statement.currency.clone().unwrap_or_default(),
+                ];
+
+                let row_number = file_rows.len() as u64 + 1;
+                file_rows.push(FileRow::from_cells(row_number, cells, column_delimiter).with_values(values));
+            }
+        }
+
+        return Ok(file_rows);
+    }
+}
+
+impl Mt940Field {
+    fn parse_balance(&self) -> Result<Balance, AppError> {
+        let invalid_balance = || app_error_with_msg(
+            AppErrorKind::BadClientRequest,
+            &format!("the :{}: balance on line {} is not a valid MT940 balance", self.tag, self.line_number),
+        );
+
+        let captures = match BALANCE_FORMAT.get()?.captures(self.content.trim()) {
+            Some(captures) => captures,
+            None => return invalid_balance(),
+        };
+
+        let date = match convert_yymmdd_date(&captures[2]) {
+            Some(date) => date,
+            None => return invalid_balance(),
+        };
+
+        return Ok(Balance {
+            debit_credit_mark: captures[1].to_string(),
+            date,
+            currency: captures[3].to_string(),
+            amount: convert_amount(&captures[4]),
+        });
+    }
+
+    //a statement line followed by an optional line of supplementary details
+    fn parse_statement_line(&self) -> Result<Mt940Transaction, AppError> {
+        let invalid_statement_line = || app_error_with_msg(
+            AppErrorKind::BadClientRequest,
+            &format!("the :61: statement line on line {} is not a valid MT940 statement line", self.line_number),
+        );
+
+        let mut lines = self.content.lines();
+        let first_line = lines.next().unwrap_or_default().trim();
+        let supplementary_details = lines.map(|line| line.trim()).collect::<Vec<&str>>().join(" ");
+
+        let captures = match STATEMENT_LINE_FORMAT.get()?.captures(first_line) {
+            Some(captures) => captures,
+            None => return invalid_statement_line(),
+        };
+
+        let value_date = match convert_yymmdd_date(&captures[1]) {
+            Some(value_date) => value_date,
+            None => return invalid_statement_line(),
+        };
+        let entry_date = match captures.get(2) {
+            None => None,
+            Some(entry_date) => match convert_entry_date(entry_date.as_str(), &value_date) {
+                Some(entry_date) => Some(entry_date),
+                None => return invalid_statement_line(),
+            },
+        };
+        let (reference, bank_reference) = match captures[7].split_once("//") {
+            Some((reference, bank_reference)) => (reference.to_string(), Some(bank_reference.to_string())),
+            None => (captures[7].to_string(), None),
+        };
+
+        return Ok(Mt940Transaction {
+            entry_date,
+            value_date,
+            debit_credit_mark: captures[3].to_string(),
+            amount: convert_amount(&captures[5]),
+            transaction_type: captures[6].to_string(),
+            reference,
+            bank_reference,
+            supplementary_details: if supplementary_details.is_empty() { None } else { Some(supplementary_details) },
+            narrative: String::new(),
+        });
+    }
+}
+
+//the entry date is only mmdd, so it takes the value date's year,
+//unless the two dates fall either side of the turn of a year
+fn convert_entry_date(entry_date: &str, value_date: &str) -> Option<String> {
+    if entry_date.len() != 4 || !entry_date.bytes().all(|byte| byte.is_ascii_digit()) {
+        return None;
+    }
+
+    let (entry_month, entry_day) = (entry_date.get(0..2)?, entry_date.get(2..4)?);
+    let value_year: i32 = value_date.get(0..4)?.parse().ok()?;
+    let year = match (value_date.get(5..7)?, entry_month) {
+        ("01", "12") => value_year - 1,
+        ("12", "01") => value_year + 1,
+        _ => value_year,
+    };
+    return Some(format!("{}-{}-{}", year, entry_month, entry_day));
+}
+
+//swift amounts use a comma before the decimals and may end in it, e.g. "1234,56" or "100,"
+fn convert_amount(amount: &str) -> String {
+    return match amount.split_once(',') {
+        Some((units, "")) => units.to_string(),
+        Some((units, decimals)) => format!("{}.{}", units, decimals),
+        None => amount.to_string(),
+    };
+}
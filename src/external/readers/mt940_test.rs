@@ -0,0 +1,98 @@
+use crate::external::readers::mt940::Mt940FileReader;
+use crate::external::readers::test_files::get_dummy_file;
+use crate::internal::models::entities::file_format::FileFormat;
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::models::entities::file_row::CellValue;
+use crate::internal::models::entities::statement_summary::Balance;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
+
+const DUMMY_MT940_STATEMENT: &str = "{1:F01BANKBEBBAXXX0000000000}{2:O9401200220301BANKBEBBAXXX00000000002203011200N}{4:\r
+:20:STMT-20220301\r
+:25:BE68539007547034\r
+:28C:00059/001\r
+:60F:C220228EUR1000,00\r
+:61:2203010301D250,50NTRFINVOICE 42//BANKREF1\r
+PAYMENT TO SUPPLIER\r
+:86:Invoice 42 for office\r
+supplies\r
+:61:2203020302C100,NMSCNONREF\r
+:62F:C220302EUR849,50\r
+:64:C220302EUR849,50\r
+-}";
+
+#[test]
+fn test_statement_lines_and_their_narratives_become_rows() {
+    let file = get_dummy_file("svc-file-reader-processor-test-statement.sta", DUMMY_MT940_STATEMENT);
+    let read_options = FileReadOptions { format: Some(FileFormat::Mt940), ..FileReadOptions::default() };
+
+    let parsed_file = Mt940FileReader::read_file(&file, &read_options).unwrap();
+    let rows = parsed_file.file_rows;
+
+    assert_eq!(&parsed_file.column_headers[..6], ["value_date", "entry_date", "debit_credit_mark", "amount", "reference", "narrative"]);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].cells, vec![
+        "2022-03-01", "2022-03-01", "D", "250.50", "INVOICE 42", "Invoice 42 for office supplies",
+        "BANKREF1", "NTRF", "PAYMENT TO SUPPLIER", "BE68539007547034", "EUR",
+    ]);
+    assert_eq!(rows[0].values.clone().unwrap()[3], CellValue::Decimal(250.5));
+    assert_eq!(rows[1].row_number, 2);
+    assert_eq!(rows[1].cells[2..6], ["C", "100", "NONREF", ""]);
+}
+
+#[test]
+fn test_opening_and_closing_balances_are_read_for_every_statement() {
+    let second_statement = ":20:STMT-20220303\n:25:BE68539007547034\n:60F:C220302EUR849,50\n:62F:D220303EUR10,\n";
+    let statements = Mt940FileReader::parse_statements(&format!("{}\n{}", DUMMY_MT940_STATEMENT, second_statement)).unwrap();
+
+    assert_eq!(statements.len(), 2);
+    assert_eq!(statements[0].balances.statement_reference, Some(String::from("STMT-20220301")));
+    assert_eq!(statements[0].balances.statement_number, Some(String::from("00059/001")));
+    assert_eq!(statements[0].balances.opening_balance, Some(Balance {
+        debit_credit_mark: String::from("C"),
+        date: String::from("2022-02-28"),
+        currency: String::from("EUR"),
+        amount: String::from("1000.00"),
+    }));
+    assert_eq!(statements[1].balances.closing_balance.clone().unwrap().debit_credit_mark, "D");
+    assert_eq!(statements[1].balances.closing_balance.clone().unwrap().amount, "10");
+}
+
+#[test]
+fn test_mt942_entry_date_in_the_next_year_and_floor_limit_currency() {
+    let statements = Mt940FileReader::parse_statements(":20:INTERIM1\n:25:ACC1\n:34F:USD0,\n:61:2212310101RC5,00NTRFREF1\n").unwrap();
+
+    let transaction = &statements[0].transactions[0];
+    assert_eq!(statements[0].currency, Some(String::from("USD")));
+    assert_eq!(transaction.value_date, "2022-12-31");
+    assert_eq!(transaction.entry_date, Some(String::from("2023-01-01")));
+    assert_eq!(transaction.debit_credit_mark, "RC");
+}
+
+#[test]
+fn test_malformed_fields_are_reported_with_their_line_number() {
+    let bad_statement_line = Mt940FileReader::parse_statements(":20:STMT\n:60F:C220228EUR1000,00\n:61:NOT A STATEMENT LINE\n");
+    let bad_balance = Mt940FileReader::parse_statements(":20:STMT\n:62F:C22EUR1000\n");
+
+    let bad_statement_line_error = bad_statement_line.unwrap_err();
+    assert_eq!(bad_statement_line_error.kind, AppErrorKind::BadClientRequest);
+    assert!(bad_statement_line_error.message.contains("line 3"));
+    assert!(bad_balance.unwrap_err().message.contains(":62F: balance on line 2"));
+}
+
+#[test]
+fn test_amounts_without_exactly_one_decimal_comma_are_rejected() {
+    let too_many_commas = Mt940FileReader::parse_statements(":20:STMT\n:61:2203010301D1,2,3NTRFREF\n");
+    let no_comma = Mt940FileReader::parse_statements(":20:STMT\n:62F:C220301EUR1000\n");
+
+    assert!(too_many_commas.unwrap_err().message.contains(":61: statement line on line 2"));
+    assert!(no_comma.unwrap_err().message.contains(":62F: balance on line 2"));
+}
+
+#[test]
+fn test_dates_that_are_not_ascii_digits_are_rejected_instead_of_panicking() {
+    let arabic_indic_digits = Mt940FileReader::parse_statements(":20:STMT\n:61:\u{0662}\u{0662}0301D1,00NTRFREF\n");
+    let bad_balance_date = Mt940FileReader::parse_statements(":20:STMT\n:62F:C2203\u{0663}\u{0663}EUR1,00\n");
+
+    assert!(arabic_indic_digits.unwrap_err().message.contains(":61: statement line on line 2"));
+    assert!(bad_balance_date.unwrap_err().message.contains(":62F: balance on line 2"));
+}
@@ -0,0 +1,370 @@
+use crate::external::readers::common::{convert_amount_to_cell_value, convert_yymmdd_date, get_column_delimiter, get_column_headers, read_text_file};
+use crate::external::readers::parsed_files::ParsedFile;
+use crate::internal::models::entities::{
+    file_read_options::FileReadOptions,
+    file_row::{CellValue, FileRow},
+};
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error_with_msg;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+//the columns of every row read from a nacha ach file, in order
+const NACHA_COLUMN_HEADERS: [&str; 15] = [
+    "effective_entry_date",
+    "batch_number",
+    "company_name",
+    "company_identification",
+    "standard_entry_class",
+    "company_entry_description",
+    "transaction_code",
+    "debit_credit_mark",
+    "receiving_dfi_identification",
+    "account_number",
+    "amount",
+    "individual_identification_number",
+    "individual_name",
+    "trace_number",
+    "addenda_information",
+];
+
+//every nacha record is exactly this many characters long
+const NACHA_RECORD_LENGTH: usize = 94;
+
+//entry hashes are the sum of the receiving dfi routing numbers, cut down to their last 10 digits
+const ENTRY_HASH_MODULUS: u64 = 10_000_000_000;
+
+//a record of the file, with its fields read by their 1-based positions as the nacha rules give them
+#[derive(Clone, PartialEq, Debug)]
+struct NachaRecord {
+    characters: Vec<char>,
+    record_number: usize,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct NachaEntry {
+    pub effective_entry_date: String,
+    pub batch_number: String,
+    pub company_name: String,
+    pub company_identification: String,
+    pub standard_entry_class: String,
+    pub company_entry_description: String,
+    pub transaction_code: String,
+    pub receiving_dfi_identification: String,
+    pub account_number: String,
+    pub amount: u64,
+    pub individual_identification_number: String,
+    pub individual_name: String,
+    pub trace_number: String,
+    pub addenda_information: Vec<String>,
+}
+
+//what has been added up so far for a file or batch, to check against its control record
+#[derive(Clone, PartialEq, Debug, Default)]
+struct NachaTotals {
+    entry_and_addenda_count: u64,
+    entry_hash: u64,
+    total_debit_amount: u64,
+    total_credit_amount: u64,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+struct NachaBatch {
+    batch_number: String,
+    company_name: String,
+    company_identification: String,
+    standard_entry_class: String,
+    company_entry_description: String,
+    effective_entry_date: String,
+    totals: NachaTotals,
+}
+
+pub struct NachaFileReader {}
+
+impl NachaFileReader {
+    //the controls are only known at the end of each batch and of the file, so the whole
+    //file is parsed and checked before any of its rows are read
+    pub fn read_file(file: &File, _read_options: &FileReadOptions) -> Result<ParsedFile, AppError> {
+        let text = read_text_file(file, "nacha", None)?;
+        let entries = NachaFileReader::parse_file(&text)?;
+        let column_delimiter = get_column_delimiter(file);
+
+        let file_rows = entries
+            .iter()
+            .enumerate()
+            .map(|(row_index, entry)| NachaFileReader::convert_entry_into_file_row(row_index as u64 + 1, entry, column_delimiter))
+            .collect::<Result<Vec<FileRow>, AppError>>()?;
+
+        return Ok(ParsedFile {
+            column_headers: get_column_headers(&NACHA_COLUMN_HEADERS),
+            file_rows,
+            statement_summary: None,
+        });
+    }
+
+    /**
+    parses the entry details of a nacha ach file, with the batch each one is in and the text of its addenda,
+    checking the entry/addenda count, entry hash and total debit and credit amounts in every batch control (8)
+    and the file control (9) against the records before it
+
+    # Errors
+
+    This function will return an error if a record is malformed or out of place, or if a control record does not match
+     */
+    pub(crate) fn parse_file(text: &str) -> Result<Vec<NachaEntry>, AppError> {
+        let mut entries: Vec<NachaEntry> = vec![];
+        let mut file_totals: Option<NachaTotals> = None;
+        let mut batch_count: u64 = 0;
+        let mut batch: Option<NachaBatch> = None;
+        let mut has_file_control = false;
+
+        for record in NachaFileReader::split_into_records(text)? {
+            let record_type_code = record.field(1, 1);
+
+            if has_file_control {
+                //the last block of the file is filled up with records of all 9s
+                if record.characters.iter().all(|character| *character == '9') {
+                    continue;
+                }
+                return record.error("comes after the file control record");
+            }
+            if record_type_code != "1" && file_totals.is_none() {
+                return record.error("comes before the file header record");
+            }
+
+            match record_type_code.as_str() {
+                "1" => {
+                    if file_totals.is_some() {
+                        return record.error("is a second file header record");
+                    }
+                    file_totals = Some(NachaTotals::default());
+                }
+                "5" => {
+                    if batch.is_some() {
+                        return record.error("starts a batch before the last batch's control record");
+                    }
+                    batch = Some(NachaBatch {
+                        company_name: record.field(5, 20),
+                        company_identification: record.field(41, 50),
+                        standard_entry_class: record.field(51, 53),
+                        company_entry_description: record.field(54, 63),
+                        effective_entry_date: record.parse_date(70, 75, "an effective entry date")?,
+                        batch_number: record.field(88, 94),
+                        totals: NachaTotals::default(),
+                    });
+                }
+                "6" => {
+                    let current_batch = match batch.as_mut() {
+                        Some(current_batch) => current_batch,
+                        None => return record.error("is an entry detail outside of a batch"),
+                    };
+
+                    let transaction_code = record.field(2, 3);
+                    let amount = record.parse_number(30, 39, "an amount")?;
+                    let receiving_dfi_routing_number = record.parse_number(4, 11, "a receiving DFI identification")?;
+
+                    let totals = &mut current_batch.totals;
+                    totals.entry_and_addenda_count = totals.entry_and_addenda_count + 1;
+                    totals.entry_hash = (totals.entry_hash + receiving_dfi_routing_number) % ENTRY_HASH_MODULUS;
+                    match get_debit_credit_mark(&transaction_code).as_str() {
+                        "D" => totals.total_debit_amount = totals.total_debit_amount + amount,
+                        "C" => totals.total_credit_amount = totals.total_credit_amount + amount,
+                        _ => return record.error(&format!("has a transaction code {} that is not used in NACHA files", transaction_code)),
+                    }
+
+                    entries.push(NachaEntry {
+                        effective_entry_date: current_batch.effective_entry_date.clone(),
+                        batch_number: current_batch.batch_number.clone(),
+                        company_name: current_batch.company_name.clone(),
+                        company_identification: current_batch.company_identification.clone(),
+                        standard_entry_class: current_batch.standard_entry_class.clone(),
+                        company_entry_description: current_batch.company_entry_description.clone(),
+                        transaction_code,
+                        receiving_dfi_identification: record.field(4, 12),
+                        account_number: record.field(13, 29),
+                        amount,
+                        individual_identification_number: record.field(40, 54),
+                        individual_name: record.field(55, 76),
+                        trace_number: record.field(80, 94),
+                        addenda_information: vec![],
+                    });
+                }
+                "7" => {
+                    let (current_batch, entry) = match (batch.as_mut(), entries.last_mut()) {
+                        (Some(current_batch), Some(entry)) if current_batch.totals.entry_and_addenda_count > 0 => (current_batch, entry),
+                        _ => return record.error("is an addenda without an entry detail before it"),
+                    };
+
+                    current_batch.totals.entry_and_addenda_count = current_batch.totals.entry_and_addenda_count + 1;
+                    let payment_related_information = record.field(4, 83);
+                    if !payment_related_information.is_empty() {
+                        entry.addenda_information.push(payment_related_information);
+                    }
+                }
+                "8" => {
+                    let current_batch = match batch.take() {
+                        Some(current_batch) => current_batch,
+                        None => return record.error("is a batch control without a batch"),
+                    };
+                    let totals = &current_batch.totals;
+                    record.check_control_field(5, 10, "entry/addenda count", totals.entry_and_addenda_count)?;
+                    record.check_control_field(11, 20, "entry hash", totals.entry_hash)?;
+                    record.check_control_field(21, 32, "total debit amount", totals.total_debit_amount)?;
+                    record.check_control_field(33, 44, "total credit amount", totals.total_credit_amount)?;
+                    if record.field(88, 94) != current_batch.batch_number {
+                        return record.error(&format!("has a batch number of {} but closes batch {}", record.field(88, 94), current_batch.batch_number));
+                    }
+
+                    if let Some(file_totals) = file_totals.as_mut() {
+                        file_totals.entry_and_addenda_count = file_totals.entry_and_addenda_count + totals.entry_and_addenda_count;
+                        file_totals.entry_hash = (file_totals.entry_hash + totals.entry_hash) % ENTRY_HASH_MODULUS;
+                        file_totals.total_debit_amount = file_totals.total_debit_amount + totals.total_debit_amount;
+                        file_totals.total_credit_amount = file_totals.total_credit_amount + totals.total_credit_amount;
+                    }
+                    batch_count = batch_count + 1;
+                }
+                "9" => {
+                    if batch.is_some() {
+                        return record.error("ends the file before the last batch's control record");
+                    }
+                    let totals = file_totals.clone().unwrap_or_default();
+                    record.check_control_field(2, 7, "batch count", batch_count)?;
+                    record.check_control_field(14, 21, "entry/addenda count", totals.entry_and_addenda_count)?;
+                    record.check_control_field(22, 31, "entry hash", totals.entry_hash)?;
+                    record.check_control_field(32, 43, "total debit amount", totals.total_debit_amount)?;
+                    record.check_control_field(44, 55, "total credit amount", totals.total_credit_amount)?;
+                    has_file_control = true;
+                }
+                _ => return record.error("has a record type code that is not used in NACHA files"),
+            }
+        }
+
+        if !has_file_control {
+            return app_error_with_msg(AppErrorKind::BadClientRequest, "the NACHA file has no file control record");
+        }
+
+        return Ok(entries);
+    }
+
+    //records are normally one to a line, but some files run them together without line breaks, in which
+    //case every 94 characters are a record. records whose trailing spaces were cut off are padded back
+    fn split_into_records(text: &str) -> Result<Vec<NachaRecord>, AppError> {
+        let mut records = vec![];
+
+        for (line_index, line) in text.lines().enumerate() {
+            let characters: Vec<char> = line.trim_end_matches(['\r', ' ']).chars().collect();
+            if characters.is_empty() {
+                continue;
+            }
+
+            if characters.len() > NACHA_RECORD_LENGTH && !characters.chunks_exact(NACHA_RECORD_LENGTH).remainder().is_empty() {
+                return app_error_with_msg(
+                    AppErrorKind::BadClientRequest,
+                    &format!("line {} of the NACHA file is not made up of {} character records", line_index + 1, NACHA_RECORD_LENGTH),
+                );
+            }
+
+            for record_characters in characters.chunks(NACHA_RECORD_LENGTH) {
+                let mut record_characters = record_characters.to_vec();
+                record_characters.resize(NACHA_RECORD_LENGTH, ' ');
+                records.push(NachaRecord { characters: record_characters, record_number: records.len() + 1 });
+            }
+        }
+
+        return Ok(records);
+    }
+
+    fn convert_entry_into_file_row(row_number: u64, entry: &NachaEntry, column_delimiter: char) -> Result<FileRow, AppError> {
+        let cells = vec![
+            entry.effective_entry_date.clone(),
+            entry.batch_number.clone(),
+            entry.company_name.clone(),
+            entry.company_identification.clone(),
+            entry.standard_entry_class.clone(),
+            entry.company_entry_description.clone(),
+            entry.transaction_code.clone(),
+            get_debit_credit_mark(&entry.transaction_code),
+            entry.receiving_dfi_identification.clone(),
+            entry.account_number.clone(),
+            format!("{}.{:02}", entry.amount / 100, entry.amount % 100),
+            entry.individual_identification_number.clone(),
+            entry.individual_name.clone(),
+            entry.trace_number.clone(),
+            entry.addenda_information.join(" "),
+        ];
+
+        let values = cells
+            .iter()
+            .enumerate()
+            .map(|(column_index, cell)| match NACHA_COLUMN_HEADERS[column_index] {
+                _ if cell.is_empty() => Ok(CellValue::Empty),
+                "effective_entry_date" => Ok(CellValue::Date(cell.clone())),
+                "amount" => convert_amount_to_cell_value(cell),
+                _ => Ok(CellValue::Text(cell.clone())),
+            })
+            .collect::<Result<Vec<CellValue>, AppError>>()?;
+
+        return Ok(FileRow::from_cells(row_number, cells, column_delimiter).with_values(values));
+    }
+}
+
+impl NachaRecord {
+    //the trimmed field from the start to the end position, both 1-based and included
+    fn field(&self, start: usize, end: usize) -> String {
+        return self.characters[start - 1..end].iter().collect::<String>().trim().to_string();
+    }
+
+    fn error<T>(&self, problem: &str) -> Result<T, AppError> {
+        let record_name = match self.characters[0] {
+            '1' => "file header",
+            '5' => "batch header",
+            '6' => "entry detail",
+            '7' => "addenda",
+            '8' => "batch control",
+            '9' => "file control",
+            _ => "unknown",
+        };
+        return app_error_with_msg(
+            AppErrorKind::BadClientRequest,
+            &format!("the {} record (record {} of the file) {}", record_name, self.record_number, problem),
+        );
+    }
+
+    fn parse_number(&self, start: usize, end: usize, field_name: &str) -> Result<u64, AppError> {
+        let number = self.field(start, end);
+        return match number.parse::<u64>() {
+            Ok(parsed_number) if number.chars().all(|character| character.is_ascii_digit()) => Ok(parsed_number),
+            _ => self.error(&format!("has {} that is not a number: {}", field_name, number)),
+        };
+    }
+
+    //a yymmdd date as yyyy-mm-dd, a date left blank stays blank
+    fn parse_date(&self, start: usize, end: usize, field_name: &str) -> Result<String, AppError> {
+        let date = self.field(start, end);
+        if date.is_empty() {
+            return Ok(date);
+        }
+        return match convert_yymmdd_date(&date) {
+            Some(converted_date) => Ok(converted_date),
+            None => self.error(&format!("has {} that is not a yymmdd date: {}", field_name, date)),
+        };
+    }
+
+    fn check_control_field(&self, start: usize, end: usize, field_name: &str, expected_value: u64) -> Result<(), AppError> {
+        let control_value = self.field(start, end);
+        return match control_value.parse::<u64>() {
+            Ok(control_value) if control_value == expected_value => Ok(()),
+            _ => self.error(&format!("says the {} is {} but the records before it add up to {}", field_name, control_value, expected_value)),
+        };
+    }
+}
+
+//the last digit of a transaction code says whether it credits (1 to 4) or debits (5 to 9) the receiver's account
+fn get_debit_credit_mark(transaction_code: &str) -> String {
+    return match transaction_code.chars().nth(1) {
+        Some('1'..='4') => String::from("C"),
+        Some('5'..='9') => String::from("D"),
+        _ => String::new(),
+    };
+}
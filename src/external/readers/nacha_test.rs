@@ -0,0 +1,109 @@
+use crate::external::readers::nacha::NachaFileReader;
+use crate::external::readers::test_files::get_dummy_file;
+use crate::internal::models::entities::file_format::FileFormat;
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::models::entities::file_row::CellValue;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
+
+//a 94 character record with each value starting at its 1-based position
+fn get_dummy_record(values: &[(usize, &str)]) -> String {
+    let mut record = vec![' '; 94];
+    for (position, value) in values {
+        for (offset, character) in value.chars().enumerate() {
+            record[position - 1 + offset] = character;
+        }
+    }
+    return record.into_iter().collect();
+}
+
+fn get_dummy_records() -> Vec<String> {
+    return vec![
+        get_dummy_record(&[(1, "101"), (4, " 121000248"), (14, "1234567890"), (24, "2203010800A094101")]),
+        get_dummy_record(&[(1, "5200"), (5, "ACME CORP"), (41, "1234567890PPDPAYROLL"), (70, "220302"), (79, "112100024"), (88, "0000001")]),
+        get_dummy_record(&[(1, "622121000248123456789"), (30, "0000150000EMP-1"), (55, "JANE DOE"), (79, "1121000240000001")]),
+        get_dummy_record(&[(1, "705BONUS MARCH"), (84, "00010000001")]),
+        get_dummy_record(&[(1, "627021000021987654321"), (30, "0000004950INV-42"), (55, "JOHN SMITH"), (79, "0121000240000002")]),
+        get_dummy_record(&[(1, "82000000030014200026000000004950000000150000"), (45, "1234567890"), (80, "121000240000001")]),
+        get_dummy_record(&[(1, "9000001000001000000030014200026000000004950000000150000")]),
+        "9".repeat(94),
+    ];
+}
+
+#[test]
+fn test_entry_details_become_rows_with_their_batch_and_addenda() {
+    let file = get_dummy_file("svc-file-reader-processor-test-payroll.ach", get_dummy_records().join("\r\n"));
+    let read_options = FileReadOptions { format: Some(FileFormat::Nacha), ..FileReadOptions::default() };
+
+    let parsed_file = NachaFileReader::read_file(&file, &read_options).unwrap();
+    let rows = parsed_file.file_rows;
+
+    assert_eq!(parsed_file.column_headers.len(), 15);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(parsed_file.statement_summary, None);
+    assert_eq!(rows[0].cells, vec![
+        "2022-03-02", "0000001", "ACME CORP", "1234567890", "PPD", "PAYROLL", "22", "C", "121000248", "123456789",
+        "1500.00", "EMP-1", "JANE DOE", "121000240000001", "BONUS MARCH",
+    ]);
+    assert_eq!(rows[1].cells[6..11], ["27", "D", "021000021", "987654321", "49.50"]);
+    assert_eq!(rows[1].values.clone().unwrap()[10], CellValue::Decimal(49.5));
+    assert_eq!(rows[1].values.clone().unwrap()[14], CellValue::Empty);
+}
+
+#[test]
+fn test_records_run_together_without_line_breaks_are_read() {
+    let entries = NachaFileReader::parse_file(&get_dummy_records().concat()).unwrap();
+
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].addenda_information, vec!["BONUS MARCH"]);
+    assert_eq!(entries[1].amount, 4950);
+}
+
+#[test]
+fn test_control_records_that_do_not_match_their_entries_are_rejected() {
+    let mut wrong_entry_hash = get_dummy_records();
+    wrong_entry_hash[5] = wrong_entry_hash[5].replacen("0014200026", "0014200027", 1);
+    let mut wrong_total_debit = get_dummy_records();
+    wrong_total_debit[6] = wrong_total_debit[6].replacen("000000004950", "000000004900", 1);
+    let mut wrong_entry_count = get_dummy_records();
+    wrong_entry_count[5] = wrong_entry_count[5].replacen("8200000003", "8200000002", 1);
+    let mut missing_file_control = get_dummy_records();
+    missing_file_control.truncate(6);
+
+    let wrong_entry_hash_error = NachaFileReader::parse_file(&wrong_entry_hash.join("\n")).unwrap_err();
+    assert_eq!(wrong_entry_hash_error.kind, AppErrorKind::BadClientRequest);
+    assert_eq!(
+        wrong_entry_hash_error.message,
+        "the batch control record (record 6 of the file) says the entry hash is 0014200027 but the records before it add up to 14200026"
+    );
+    assert_eq!(
+        NachaFileReader::parse_file(&wrong_total_debit.join("\n")).unwrap_err().message,
+        "the file control record (record 7 of the file) says the total debit amount is 000000004900 but the records before it add up to 4950"
+    );
+    assert!(NachaFileReader::parse_file(&wrong_entry_count.join("\n")).unwrap_err().message.contains("entry/addenda count is 000002"));
+    assert_eq!(NachaFileReader::parse_file(&missing_file_control.join("\n")).unwrap_err().message, "the NACHA file has no file control record");
+}
+
+#[test]
+fn test_records_out_of_place_are_rejected() {
+    let mut addenda_before_entry = get_dummy_records();
+    addenda_before_entry.swap(2, 3);
+    let mut entry_outside_batch = get_dummy_records();
+    entry_outside_batch.remove(1);
+
+    assert!(NachaFileReader::parse_file(&addenda_before_entry.join("\n")).unwrap_err().message.contains("is an addenda without an entry detail before it"));
+    assert!(NachaFileReader::parse_file(&entry_outside_batch.join("\n")).unwrap_err().message.contains("is an entry detail outside of a batch"));
+}
+
+#[test]
+fn test_effective_entry_dates_that_are_not_yymmdd_dates_are_rejected() {
+    let mut multibyte_date = get_dummy_records();
+    multibyte_date[1] = multibyte_date[1].replacen("220302", "22030é", 1);
+    let mut blank_date = get_dummy_records();
+    blank_date[1] = blank_date[1].replacen("220302", "      ", 1);
+
+    assert_eq!(
+        NachaFileReader::parse_file(&multibyte_date.join("\n")).unwrap_err().message,
+        "the batch header record (record 2 of the file) has an effective entry date that is not a yymmdd date: 22030é"
+    );
+    assert_eq!(NachaFileReader::parse_file(&blank_date.join("\n")).unwrap()[0].effective_entry_date, "");
+}
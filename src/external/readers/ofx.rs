@@ -0,0 +1,339 @@
+use crate::external::readers::common::{convert_amount_to_cell_value, convert_yyyymmdd_date, get_column_delimiter, get_column_headers, read_text_file, LazyRegex};
+use crate::external::readers::parsed_files::ParsedFile;
+use crate::internal::models::entities::{
+    file_read_options::FileReadOptions,
+    file_row::{CellValue, FileRow},
+    statement_summary::{Balance, StatementBalances, StatementSummary},
+};
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error_with_msg;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+//the columns of every row read from an ofx or qfx statement, in order
+const OFX_COLUMN_HEADERS: [&str; 9] = [
+    "fit_id",
+    "posted_date",
+    "amount",
+    "name",
+    "memo",
+    "transaction_type",
+    "check_number",
+    "account_id",
+    "currency",
+];
+
+//the aggregates holding a bank statement and a credit card statement
+const OFX_STATEMENT_AGGREGATES: [&str; 2] = ["STMTRS", "CCSTMTRS"];
+
+//the aggregates naming the account a statement is for
+const OFX_ACCOUNT_AGGREGATES: [&str; 2] = ["BANKACCTFROM", "CCACCTFROM"];
+
+//an opening or closing tag and the text after it, e.g. <TRNAMT>-49.50
+static TAG_FORMAT: LazyRegex = LazyRegex::new(r"<(/?)([A-Za-z0-9._]+)>([^<]*)");
+
+//a signed decimal amount once any comma before the decimals is a point, e.g. -49.50
+static AMOUNT_FORMAT: LazyRegex = LazyRegex::new(r"^[+-]?([0-9]+(\.[0-9]*)?|\.[0-9]+)$");
+
+//a tag in the body of an ofx file, with the text that follows it up to the next tag
+#[derive(Clone, PartialEq, Debug)]
+struct OfxTag {
+    name: String,
+    is_closing: bool,
+    text: String,
+    line_number: usize,
+}
+
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct OfxStatement {
+    pub balances: StatementBalances,
+    pub currency: Option<String>,
+    pub transactions: Vec<OfxTransaction>,
+}
+
+//a STMTTRN aggregate
+#[derive(Clone, PartialEq, Debug, Default)]
+pub(crate) struct OfxTransaction {
+    pub fit_id: String,
+    pub posted_date: String,
+    pub amount: String,
+    pub name: String,
+    pub memo: String,
+    pub transaction_type: String,
+    pub check_number: String,
+    pub line_number: usize,
+}
+
+pub struct OfxFileReader {}
+
+impl OfxFileReader {
+    //a statement's balances come after its transactions, so the whole file is parsed before any of its rows are read
+    pub fn read_file(file: &File, _read_options: &FileReadOptions) -> Result<ParsedFile, AppError> {
+        let text = read_text_file(file, "ofx", None)?;
+        let statements = OfxFileReader::parse_statements(&text)?;
+        let file_rows = OfxFileReader::convert_statements_into_file_rows(&statements, get_column_delimiter(file))?;
+
+        return Ok(ParsedFile {
+            column_headers: get_column_headers(&OFX_COLUMN_HEADERS),
+            file_rows,
+            statement_summary: Some(StatementSummary {
+                statements: statements.into_iter().map(|statement| statement.balances).collect(),
+            }),
+        });
+    }
+
+    /**
+    parses the bank and credit card statements in an ofx or qfx file. both the sgml (ofx 1.x) and
+    the xml (ofx 2.x) flavours are read the same way: the header before the <OFX> tag is skipped,
+    a tag followed by text is a value whether or not it is closed, and any other tag opens an
+    aggregate that lasts until its closing tag, or until the aggregate around it is closed
+     */
+    pub(crate) fn parse_statements(text: &str) -> Result<Vec<OfxStatement>, AppError> {
+        let tags = OfxFileReader::split_into_tags(text)?;
+        let mut open_aggregates: Vec<String> = vec![];
+        let mut statements = vec![];
+        let mut statement: Option<OfxStatement> = None;
+        let mut transaction: Option<OfxTransaction> = None;
+        let mut balance_amount: Option<String> = None;
+        let mut balance_date: Option<String> = None;
+
+        for tag in tags {
+            if tag.is_closing {
+                //a closing tag without a matching open aggregate is the end of an xml value
+                let position = match open_aggregates.iter().rposition(|aggregate| *aggregate == tag.name) {
+                    Some(position) => position,
+                    None => continue,
+                };
+
+                //sgml aggregates inside it that were never closed are closed along with it
+                while open_aggregates.len() > position {
+                    let closed_aggregate = open_aggregates.pop().unwrap_or_default();
+                    match closed_aggregate.as_str() {
+                        "STMTTRN" => {
+                            if let Some(closed_transaction) = transaction.take() {
+                                closed_transaction.check_required_values()?;
+                                if let Some(statement) = statement.as_mut() {
+                                    statement.transactions.push(closed_transaction);
+                                }
+                            }
+                        }
+                        "LEDGERBAL" | "AVAILBAL" => {
+                            let (amount, statement) = match (balance_amount.take(), statement.as_mut()) {
+                                (Some(amount), Some(statement)) => (amount, statement),
+                                _ => continue,
+                            };
+                            let (debit_credit_mark, amount) = match amount.strip_prefix('-') {
+                                Some(amount) => ("D", amount.to_string()),
+                                None => ("C", amount.trim_start_matches('+').to_string()),
+                            };
+                            let balance = Balance {
+                                debit_credit_mark: debit_credit_mark.to_string(),
+                                date: balance_date.take().unwrap_or_default(),
+                                currency: statement.currency.clone().unwrap_or_default(),
+                                amount,
+                            };
+                            if closed_aggregate == "LEDGERBAL" {
+                                statement.balances.closing_balance = Some(balance);
+                            } else {
+                                statement.balances.closing_available_balance = Some(balance);
+                            }
+                        }
+                        name if OFX_STATEMENT_AGGREGATES.contains(&name) => {
+                            if let Some(closed_statement) = statement.take() {
+                                statements.push(closed_statement);
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                continue;
+            }
+
+            if tag.text.is_empty() {
+                match tag.name.as_str() {
+                    "STMTTRN" => {
+                        transaction = Some(OfxTransaction { line_number: tag.line_number, ..OfxTransaction::default() });
+                    }
+                    "LEDGERBAL" | "AVAILBAL" => {
+                        balance_amount = None;
+                        balance_date = None;
+                    }
+                    name if OFX_STATEMENT_AGGREGATES.contains(&name) => {
+                        statement = Some(OfxStatement::default());
+                    }
+                    _ => {}
+                }
+                open_aggregates.push(tag.name);
+                continue;
+            }
+
+            let is_inside = |aggregate: &str| open_aggregates.iter().any(|open_aggregate| open_aggregate == aggregate);
+
+            if let Some(transaction) = transaction.as_mut() {
+                match tag.name.as_str() {
+                    "FITID" => transaction.fit_id = tag.text,
+                    "DTPOSTED" => transaction.posted_date = convert_date(&tag.text, "DTPOSTED", tag.line_number)?,
+                    "TRNAMT" => transaction.amount = convert_amount(&tag.text, tag.line_number)?,
+                    "NAME" => transaction.name = tag.text,
+                    "MEMO" => transaction.memo = tag.text,
+                    "TRNTYPE" => transaction.transaction_type = tag.text,
+                    "CHECKNUM" => transaction.check_number = tag.text,
+                    _ => {}
+                }
+                continue;
+            }
+
+            let statement = match statement.as_mut() {
+                Some(statement) => statement,
+                None => continue,
+            };
+
+            match tag.name.as_str() {
+                "CURDEF" => statement.currency = Some(tag.text),
+                "ACCTID" if OFX_ACCOUNT_AGGREGATES.iter().any(|aggregate| is_inside(aggregate)) => {
+                    statement.balances.account_identification = Some(tag.text);
+                }
+                "BALAMT" => balance_amount = Some(convert_amount(&tag.text, tag.line_number)?),
+                "DTASOF" => balance_date = Some(convert_date(&tag.text, "DTASOF", tag.line_number)?),
+                _ => {}
+            }
+        }
+
+        //an sgml file may leave its last statement unclosed
+        if let Some(statement) = statement {
+            statements.push(statement);
+        }
+
+        return Ok(statements);
+    }
+
+    fn split_into_tags(text: &str) -> Result<Vec<OfxTag>, AppError> {
+        let body_start = match text.find("<OFX>") {
+            Some(body_start) => body_start,
+            None => {
+                return app_error_with_msg(AppErrorKind::BadClientRequest, "the file is not an OFX file, it has no <OFX> tag");
+            }
+        };
+
+        let tag_format = TAG_FORMAT.get()?;
+        let mut line_number = text[..body_start].matches('\n').count() + 1;
+        let mut line_counted_up_to = body_start;
+        let mut tags = vec![];
+
+        for captures in tag_format.captures_iter(&text[body_start..]) {
+            let tag_start = body_start + captures.get(0).map(|whole_tag| whole_tag.start()).unwrap_or_default();
+            line_number = line_number + text[line_counted_up_to..tag_start].matches('\n').count();
+            line_counted_up_to = tag_start;
+
+            tags.push(OfxTag {
+                name: captures[2].to_uppercase(),
+                is_closing: !captures[1].is_empty(),
+                text: unescape_text(captures[3].trim()),
+                line_number,
+            });
+        }
+
+        return Ok(tags);
+    }
+
+    fn convert_statements_into_file_rows(statements: &[OfxStatement], column_delimiter: char) -> Result<Vec<FileRow>, AppError> {
+        let mut file_rows = vec![];
+
+        for statement in statements {
+            for transaction in &statement.transactions {
+                let optional_text = |text: &str| match text {
+                    "" => CellValue::Empty,
+                    text => CellValue::Text(text.to_string()),
+                };
+
+                let account_id = statement.balances.account_identification.clone().unwrap_or_default();
+                let currency = statement.currency.clone().unwrap_or_default();
+
+                let values = vec![
+                    CellValue::Text(transaction.fit_id.clone()),
+                    CellValue::Date(transaction.posted_date.clone()),
+                    convert_amount_to_cell_value(&transaction.amount)?,
+                    optional_text(&transaction.name),
+                    optional_text(&transaction.memo),
+                    optional_text(&transaction.transaction_type),
+                    optional_text(&transaction.check_number),
+                    optional_text(&account_id),
+                    optional_text(&currency),
+                ];
+
+                let cells = vec![
+                    transaction.fit_id.clone(),
+                    transaction.posted_date.clone(),
+                    transaction.amount.clone(),
+                    transaction.name.clone(),
+                    transaction.memo.clone(),
+                    transaction.transaction_type.clone(),
+                    transaction.check_number.clone(),
+                    account_id,
+                    currency,
+                ];
+
+                let row_number = file_rows.len() as u64 + 1;
+                file_rows.push(FileRow::from_cells(row_number, cells, column_delimiter).with_values(values));
+            }
+        }
+
+        return Ok(file_rows);
+    }
+}
+
+impl OfxTransaction {
+    //a transaction cannot be reconciled without its id, date and amount
+    fn check_required_values(&self) -> Result<(), AppError> {
+        let missing_value = if self.fit_id.is_empty() {
+            "FITID"
+        } else if self.posted_date.is_empty() {
+            "DTPOSTED"
+        } else if self.amount.is_empty() {
+            "TRNAMT"
+        } else {
+            return Ok(());
+        };
+
+        return app_error_with_msg(
+            AppErrorKind::BadClientRequest,
+            &format!("the STMTTRN on line {} has no {}", self.line_number, missing_value),
+        );
+    }
+}
+
+//ofx dates are yyyymmdd, optionally followed by a time and a time zone, e.g. 20220301120000.000[-5:EST]
+fn convert_date(date: &str, tag_name: &str, line_number: usize) -> Result<String, AppError> {
+    return match date.get(..8).and_then(convert_yyyymmdd_date) {
+        Some(date) => Ok(date),
+        None => app_error_with_msg(
+            AppErrorKind::BadClientRequest,
+            &format!("the {} on line {} is not a valid OFX date: {}", tag_name, line_number, date),
+        ),
+    };
+}
+
+//amounts are signed, and some banks write them with a comma before the decimals
+fn convert_amount(amount: &str, line_number: usize) -> Result<String, AppError> {
+    let amount = if amount.contains('.') { amount.to_string() } else { amount.replace(',', ".") };
+
+    //a float parse would also let through inf, NaN and exponents such as 1e5
+    if !AMOUNT_FORMAT.get()?.is_match(&amount) {
+        return app_error_with_msg(
+            AppErrorKind::BadClientRequest,
+            &format!("the amount on line {} is not a valid OFX amount: {}", line_number, amount),
+        );
+    }
+    return Ok(amount);
+}
+
+fn unescape_text(text: &str) -> String {
+    return text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&");
+}
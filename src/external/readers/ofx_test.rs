@@ -0,0 +1,161 @@
+use crate::external::readers::ofx::OfxFileReader;
+use crate::external::readers::test_files::get_dummy_file;
+use crate::internal::models::entities::file_format::FileFormat;
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::models::entities::file_row::CellValue;
+use crate::internal::models::entities::statement_summary::Balance;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
+
+const DUMMY_SGML_OFX_FILE: &str = "OFXHEADER:100
+DATA:OFXSGML
+VERSION:102
+CHARSET:1252
+
+<OFX>
+<SIGNONMSGSRSV1><SONRS><STATUS><CODE>0<SEVERITY>INFO</STATUS><DTSERVER>20220302</SONRS></SIGNONMSGSRSV1>
+<BANKMSGSRSV1>
+<STMTTRNRS>
+<TRNUID>1
+<STMTRS>
+<CURDEF>USD
+<BANKACCTFROM>
+<BANKID>121000248
+<ACCTID>0012345678
+<ACCTTYPE>CHECKING
+</BANKACCTFROM>
+<BANKTRANLIST>
+<DTSTART>20220301
+<DTEND>20220302
+<STMTTRN>
+<TRNTYPE>DEBIT
+<DTPOSTED>20220301120000.000[-5:EST]
+<TRNAMT>-49.50
+<FITID>2022030101
+<CHECKNUM>1001
+<NAME>Smith &amp; Sons
+<MEMO>Invoice 42
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT
+<DTPOSTED>20220302
+<TRNAMT>300.00
+<FITID>2022030201
+<NAME>Payroll
+</STMTTRN>
+</BANKTRANLIST>
+<LEDGERBAL>
+<BALAMT>1250.50
+<DTASOF>20220302
+</LEDGERBAL>
+<AVAILBAL>
+<BALAMT>-20.00
+<DTASOF>20220302
+</AVAILBAL>
+</STMTRS>
+</STMTTRNRS>
+</BANKMSGSRSV1>
+</OFX>
+";
+
+const DUMMY_XML_OFX_FILE: &str = r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>
+<?OFX OFXHEADER="200" VERSION="220" SECURITY="NONE" OLDFILEUID="NONE" NEWFILEUID="NONE"?>
+<OFX>
+  <CREDITCARDMSGSRSV1>
+    <CCSTMTTRNRS>
+      <TRNUID>1</TRNUID>
+      <CCSTMTRS>
+        <CURDEF>EUR</CURDEF>
+        <CCACCTFROM><ACCTID>4111111111111111</ACCTID></CCACCTFROM>
+        <BANKTRANLIST>
+          <STMTTRN>
+            <TRNTYPE>DEBIT</TRNTYPE>
+            <DTPOSTED>20220305</DTPOSTED>
+            <TRNAMT>-12,30</TRNAMT>
+            <FITID>CC-1</FITID>
+            <NAME>Coffee Shop</NAME>
+            <MEMO></MEMO>
+          </STMTTRN>
+        </BANKTRANLIST>
+        <LEDGERBAL><BALAMT>-512.30</BALAMT><DTASOF>20220305</DTASOF></LEDGERBAL>
+      </CCSTMTRS>
+    </CCSTMTTRNRS>
+  </CREDITCARDMSGSRSV1>
+</OFX>
+"#;
+
+#[test]
+fn test_statement_transactions_in_an_sgml_file_become_rows() {
+    let file = get_dummy_file("svc-file-reader-processor-test-statement.qfx", DUMMY_SGML_OFX_FILE);
+    let read_options = FileReadOptions { format: Some(FileFormat::Ofx), ..FileReadOptions::default() };
+
+    let parsed_file = OfxFileReader::read_file(&file, &read_options).unwrap();
+    let rows = parsed_file.file_rows;
+
+    assert_eq!(parsed_file.column_headers, vec!["fit_id", "posted_date", "amount", "name", "memo", "transaction_type", "check_number", "account_id", "currency"]);
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].cells, vec!["2022030101", "2022-03-01", "-49.50", "Smith & Sons", "Invoice 42", "DEBIT", "1001", "0012345678", "USD"]);
+    assert_eq!(rows[1].cells, vec!["2022030201", "2022-03-02", "300.00", "Payroll", "", "CREDIT", "", "0012345678", "USD"]);
+    assert_eq!(rows[0].values.clone().unwrap()[2], CellValue::Decimal(-49.5));
+    assert_eq!(rows[1].values.clone().unwrap()[4], CellValue::Empty);
+}
+
+#[test]
+fn test_statement_transactions_in_an_xml_file_are_read() {
+    let statements = OfxFileReader::parse_statements(DUMMY_XML_OFX_FILE).unwrap();
+
+    assert_eq!(statements.len(), 1);
+    assert_eq!(statements[0].currency, Some(String::from("EUR")));
+    assert_eq!(statements[0].balances.account_identification, Some(String::from("4111111111111111")));
+    assert_eq!(statements[0].transactions.len(), 1);
+    assert_eq!(statements[0].transactions[0].fit_id, "CC-1");
+    assert_eq!(statements[0].transactions[0].amount, "-12.30");
+    assert_eq!(statements[0].transactions[0].memo, "");
+    assert_eq!(statements[0].balances.closing_balance.clone().unwrap().debit_credit_mark, "D");
+}
+
+#[test]
+fn test_ledger_and_available_balances_are_read() {
+    let statements = OfxFileReader::parse_statements(DUMMY_SGML_OFX_FILE).unwrap();
+
+    assert_eq!(statements[0].balances.closing_balance, Some(Balance {
+        debit_credit_mark: String::from("C"),
+        date: String::from("2022-03-02"),
+        currency: String::from("USD"),
+        amount: String::from("1250.50"),
+    }));
+    assert_eq!(statements[0].balances.closing_available_balance, Some(Balance {
+        debit_credit_mark: String::from("D"),
+        date: String::from("2022-03-02"),
+        currency: String::from("USD"),
+        amount: String::from("20.00"),
+    }));
+    assert_eq!(statements[0].balances.opening_balance, None);
+}
+
+#[test]
+fn test_invalid_statement_transactions_are_rejected() {
+    let missing_fit_id = OfxFileReader::parse_statements(&DUMMY_SGML_OFX_FILE.replace("<FITID>2022030201\n", ""));
+    let invalid_amount = OfxFileReader::parse_statements(&DUMMY_SGML_OFX_FILE.replace("<TRNAMT>300.00", "<TRNAMT>three hundred"));
+    let not_an_ofx_file = OfxFileReader::parse_statements("date,amount\n2022-03-01,10.00\n");
+
+    assert_eq!(missing_fit_id.unwrap_err().message, "the STMTTRN on line 30 has no FITID");
+    assert_eq!(invalid_amount.unwrap_err().message, "the amount on line 33 is not a valid OFX amount: three hundred");
+    assert_eq!(not_an_ofx_file.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_amounts_and_dates_that_are_not_plain_numbers_are_rejected() {
+    let comma_amount = OfxFileReader::parse_statements(&DUMMY_SGML_OFX_FILE.replace("<TRNAMT>300.00", "<TRNAMT>300,00")).unwrap();
+    let infinite_amount = OfxFileReader::parse_statements(&DUMMY_SGML_OFX_FILE.replace("<TRNAMT>300.00", "<TRNAMT>inf"));
+    let not_a_number_amount = OfxFileReader::parse_statements(&DUMMY_SGML_OFX_FILE.replace("<TRNAMT>300.00", "<TRNAMT>NaN"));
+    let exponent_amount = OfxFileReader::parse_statements(&DUMMY_SGML_OFX_FILE.replace("<TRNAMT>300.00", "<TRNAMT>1e5"));
+    let multibyte_date = OfxFileReader::parse_statements(&DUMMY_SGML_OFX_FILE.replace("<DTPOSTED>20220302", "<DTPOSTED>2022030é"));
+    let short_date = OfxFileReader::parse_statements(&DUMMY_SGML_OFX_FILE.replace("<DTPOSTED>20220302", "<DTPOSTED>202203"));
+
+    assert_eq!(comma_amount[0].transactions[1].amount, "300.00");
+    assert_eq!(infinite_amount.unwrap_err().message, "the amount on line 33 is not a valid OFX amount: inf");
+    assert_eq!(not_a_number_amount.unwrap_err().message, "the amount on line 33 is not a valid OFX amount: NaN");
+    assert_eq!(exponent_amount.unwrap_err().message, "the amount on line 33 is not a valid OFX amount: 1e5");
+    assert_eq!(multibyte_date.unwrap_err().message, "the DTPOSTED on line 32 is not a valid OFX date: 2022030é");
+    assert_eq!(short_date.unwrap_err().message, "the DTPOSTED on line 32 is not a valid OFX date: 202203");
+}
@@ -0,0 +1,140 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, RwLock};
+use std::time::SystemTime;
+
+use crate::internal::models::entities::{file_read_options::FileReadOptions, file_row::FileRow, statement_summary::StatementSummary};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{app_errors::AppError, file::File};
+
+//the oldest parsed files are forgotten once more than this many are kept
+const DEFAULT_MAX_PARSED_FILES: usize = 16;
+
+//the parsed files, oldest first
+type ParsedFiles = VecDeque<(ParsedFileKey, Arc<ParsedFile>)>;
+
+//everything read from a file that has to be parsed in full before any of its rows can be read
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct ParsedFile {
+    pub column_headers: Vec<String>,
+    pub file_rows: Vec<FileRow>,
+
+    //the balances of a bank statement file, none for other files
+    pub statement_summary: Option<StatementSummary>,
+}
+
+/**
+keeps the files that have been parsed in full, so that the column headers, row count, balances and rows
+of a split file job all come from a single parse of its file. clones share the same parsed files
+so a single cache can be handed to every http worker and the background worker
+
+a parsed file is forgotten once its rows are taken, which is the last thing a job reads from it.
+a file is parsed again when it has changed on disk or is read with different read options
+ */
+#[derive(Clone)]
+pub struct ParsedFileCache {
+    parsed_files: Arc<RwLock<ParsedFiles>>,
+
+    //files whose jobs never get as far as reading their rows are dropped, oldest first
+    max_parsed_files: usize,
+}
+
+//a file is only the same file while its path, size, modification time and read options are
+#[derive(Clone, PartialEq, Debug)]
+struct ParsedFileKey {
+    file_path: String,
+    file_size: u64,
+    modified_at: Option<SystemTime>,
+    read_options: FileReadOptions,
+}
+
+impl Default for ParsedFileCache {
+    fn default() -> Self {
+        return ParsedFileCache::new(DEFAULT_MAX_PARSED_FILES);
+    }
+}
+
+impl ParsedFileCache {
+    pub fn new(max_parsed_files: usize) -> Self {
+        return ParsedFileCache {
+            parsed_files: Arc::new(RwLock::new(VecDeque::new())),
+            max_parsed_files,
+        };
+    }
+
+    //the file parsed earlier, or else the file parsed now and kept for the reads that follow
+    pub fn read<F>(&self, file: &File, read_options: &FileReadOptions, parse_file: F) -> Result<Arc<ParsedFile>, AppError>
+    where
+        F: FnOnce(&File, &FileReadOptions) -> Result<ParsedFile, AppError>,
+    {
+        let key = match ParsedFileKey::new(file, read_options) {
+            Some(key) => key,
+            //a file that cannot be found on disk is left to the parser to report
+            None => return parse_file(file, read_options).map(Arc::new),
+        };
+
+        if let Some(parsed_file) = self.find(&key) {
+            return Ok(parsed_file);
+        }
+
+        //the file is parsed without holding the lock, so that other jobs are not held up by a large file
+        let parsed_file = Arc::new(parse_file(file, read_options)?);
+
+        if let Ok(mut parsed_files) = self.parsed_files.write() {
+            parsed_files.retain(|(parsed_file_key, _)| parsed_file_key.file_path != key.file_path);
+            parsed_files.push_back((key, parsed_file.clone()));
+
+            while parsed_files.len() > self.max_parsed_files {
+                parsed_files.pop_front();
+            }
+        }
+
+        return Ok(parsed_file);
+    }
+
+    //the file parsed earlier, or else the file parsed now, without keeping it any longer
+    pub fn take<F>(&self, file: &File, read_options: &FileReadOptions, parse_file: F) -> Result<ParsedFile, AppError>
+    where
+        F: FnOnce(&File, &FileReadOptions) -> Result<ParsedFile, AppError>,
+    {
+        let parsed_file = match ParsedFileKey::new(file, read_options).and_then(|key| self.remove(&key)) {
+            Some(parsed_file) => parsed_file,
+            None => return parse_file(file, read_options),
+        };
+
+        //another read of the same file may still be holding on to it
+        return Ok(Arc::try_unwrap(parsed_file).unwrap_or_else(|parsed_file| parsed_file.as_ref().clone()));
+    }
+
+    fn find(&self, key: &ParsedFileKey) -> Option<Arc<ParsedFile>> {
+        return match self.parsed_files.read() {
+            Ok(parsed_files) => parsed_files
+                .iter()
+                .find(|(parsed_file_key, _)| parsed_file_key == key)
+                .map(|(_, parsed_file)| parsed_file.clone()),
+            Err(_) => None,
+        };
+    }
+
+    fn remove(&self, key: &ParsedFileKey) -> Option<Arc<ParsedFile>> {
+        return match self.parsed_files.write() {
+            Ok(mut parsed_files) => {
+                let position = parsed_files.iter().position(|(parsed_file_key, _)| parsed_file_key == key)?;
+                parsed_files.remove(position).map(|(_, parsed_file)| parsed_file)
+            }
+            Err(_) => None,
+        };
+    }
+}
+
+impl ParsedFileKey {
+    fn new(file: &File, read_options: &FileReadOptions) -> Option<ParsedFileKey> {
+        let file_path = file.file_path.clone()?;
+        let file_metadata = std::fs::metadata(&file_path).ok()?;
+
+        return Some(ParsedFileKey {
+            file_path,
+            file_size: file_metadata.len(),
+            modified_at: file_metadata.modified().ok(),
+            read_options: read_options.clone(),
+        });
+    }
+}
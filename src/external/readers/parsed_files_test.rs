@@ -0,0 +1,64 @@
+use std::cell::Cell;
+
+use crate::external::readers::parsed_files::{ParsedFile, ParsedFileCache};
+use crate::external::readers::test_files::get_dummy_file;
+use crate::internal::models::entities::file_format::FileFormat;
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file::File;
+
+fn get_parsed_file(column_header: &str) -> ParsedFile {
+    return ParsedFile {
+        column_headers: vec![column_header.to_string()],
+        ..ParsedFile::default()
+    };
+}
+
+#[test]
+fn test_a_file_is_parsed_once_until_its_rows_are_taken() {
+    let file = get_dummy_file("svc-file-reader-processor-test-parsed-once.json", "[]");
+    let read_options = FileReadOptions::default();
+    let parsed_files = ParsedFileCache::default();
+    let times_parsed = Cell::new(0);
+    let parse_file = |_: &File, _: &FileReadOptions| {
+        times_parsed.set(times_parsed.get() + 1);
+        Ok(get_parsed_file("amount"))
+    };
+
+    parsed_files.read(&file, &read_options, parse_file).unwrap();
+    parsed_files.clone().read(&file, &read_options, parse_file).unwrap();
+    let parsed_file = parsed_files.take(&file, &read_options, parse_file).unwrap();
+    parsed_files.take(&file, &read_options, parse_file).unwrap();
+
+    assert_eq!(parsed_file.column_headers, vec!["amount"]);
+    assert_eq!(times_parsed.get(), 2);
+}
+
+#[test]
+fn test_a_file_is_parsed_again_once_it_changes_or_is_read_differently() {
+    let file = get_dummy_file("svc-file-reader-processor-test-parsed-again.json", "[]");
+    let parsed_files = ParsedFileCache::default();
+
+    parsed_files.read(&file, &FileReadOptions::default(), |_, _| Ok(get_parsed_file("first"))).unwrap();
+    let read_differently = parsed_files
+        .read(&file, &FileReadOptions { format: Some(FileFormat::Ndjson), ..FileReadOptions::default() }, |_, _| Ok(get_parsed_file("second")))
+        .unwrap();
+    std::fs::write(file.file_path.clone().unwrap(), "[{}]").unwrap();
+    let changed = parsed_files.read(&file, &FileReadOptions::default(), |_, _| Ok(get_parsed_file("third"))).unwrap();
+
+    assert_eq!(read_differently.column_headers, vec!["second"]);
+    assert_eq!(changed.column_headers, vec!["third"]);
+}
+
+#[test]
+fn test_the_oldest_parsed_files_are_forgotten() {
+    let first_file = get_dummy_file("svc-file-reader-processor-test-oldest-1.json", "[]");
+    let second_file = get_dummy_file("svc-file-reader-processor-test-oldest-2.json", "[]");
+    let read_options = FileReadOptions::default();
+    let parsed_files = ParsedFileCache::new(1);
+
+    parsed_files.read(&first_file, &read_options, |_, _| Ok(get_parsed_file("first"))).unwrap();
+    parsed_files.read(&second_file, &read_options, |_, _| Ok(get_parsed_file("second"))).unwrap();
+    let first_parsed_again = parsed_files.read(&first_file, &read_options, |_, _| Ok(get_parsed_file("first again"))).unwrap();
+
+    assert_eq!(first_parsed_again.column_headers, vec!["first again"]);
+}
@@ -1,29 +1,207 @@
-use crate::internal::shared_reconciler_rust_libraries::models::entities::{
-    app_errors::AppError,
-    file::{File, FileThatHasBeenRead},
+use regex::Regex;
+
+use crate::external::readers::common::{get_column_delimiter, get_file_path, LazyRegex};
+use crate::external::readers::parsed_files::ParsedFile;
+use crate::internal::models::entities::{
+    file_read_options::{FileReadOptions, PdfReadOptions},
     file_row::FileRow,
 };
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+//columns in text extracted from a pdf are separated by
+//at least two spaces when no layout has been supplied
+static COLUMN_SEPARATOR: LazyRegex = LazyRegex::new(r"\s{2,}");
 
 pub struct PdfFileReader {}
 
 impl PdfFileReader {
-    pub fn read_file(file: &File) -> Result<FileThatHasBeenRead, AppError> {
-        let file_that_has_been_read = FileThatHasBeenRead {
-            id: file.id.clone(),
-            upload_request_id: file.upload_request_id.clone(),
-            file_type: file.file_type.clone(),
-            column_headers: PdfFileReader::read_column_headers(file),
-            file_rows: PdfFileReader::read_file_rows(file),
-            file_metadata: file.file_metadata.clone(),
+    //the text of every page is extracted up front, so the whole file is read before any of its rows are
+    pub fn read_file(file: &File, read_options: &FileReadOptions) -> Result<ParsedFile, AppError> {
+        let pdf_read_options = read_options.pdf.clone().unwrap_or_default();
+        let pages = PdfFileReader::extract_text_from_pages(file)?;
+        let column_delimiter = get_column_delimiter(file);
+
+        let (column_headers, file_rows) = PdfFileReader::read_column_headers_and_file_rows(
+            &pages,
+            &pdf_read_options,
+            column_delimiter,
+        )?;
+
+        return Ok(ParsedFile {
+            column_headers,
+            file_rows,
+            statement_summary: None,
+        });
+    }
+
+    fn extract_text_from_pages(file: &File) -> Result<Vec<String>, AppError> {
+        let file_path = get_file_path(file, "pdf")?;
+
+        return match pdf_extract::extract_text_by_pages(file_path) {
+            Ok(pages) => Ok(pages),
+            Err(e) => app_error(AppErrorKind::BadClientRequest, Box::new(e)),
+        };
+    }
+
+    pub(crate) fn read_column_headers_and_file_rows(
+        pages: &[String],
+        pdf_read_options: &PdfReadOptions,
+        column_delimiter: char,
+    ) -> Result<(Vec<String>, Vec<FileRow>), AppError> {
+        let lines: Vec<&str> = pages
+            .iter()
+            .flat_map(|page| page.lines())
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+
+        let (column_headers, rows_of_cells) = match (
+            pdf_read_options.row_pattern.clone(),
+            pdf_read_options.column_start_positions.clone(),
+        ) {
+            (Some(row_pattern), _) => {
+                let (column_headers, rows_of_cells) = PdfFileReader::extract_rows_matching_pattern(&lines, &row_pattern)?;
+                (pdf_read_options.column_headers.clone().unwrap_or(column_headers), rows_of_cells)
+            }
+            (None, Some(column_start_positions)) => PdfFileReader::separate_header_from_rows(
+                PdfFileReader::extract_rows_at_fixed_positions(&lines, &column_start_positions),
+                pdf_read_options.column_headers.clone(),
+            ),
+            (None, None) => PdfFileReader::separate_header_from_rows(
+                PdfFileReader::extract_rows_separated_by_spaces(&lines)?,
+                pdf_read_options.column_headers.clone(),
+            ),
+        };
+
+        PdfFileReader::check_row_widths(&column_headers, &rows_of_cells)?;
+
+        let file_rows = rows_of_cells
+            .into_iter()
+            .enumerate()
+            .map(|(row_index, cells)| FileRow::from_cells((row_index + 1) as u64, cells, column_delimiter))
+            .collect();
+
+        return Ok((column_headers, file_rows));
+    }
+
+    //every line that matches the pattern is a row, everything else
+    //on the page (titles, repeated headers, page numbers) is ignored
+    fn extract_rows_matching_pattern(lines: &[&str], row_pattern: &str) -> Result<(Vec<String>, Vec<Vec<String>>), AppError> {
+        let regex = match Regex::new(row_pattern) {
+            Ok(regex) => regex,
+            Err(e) => { return app_error(AppErrorKind::BadClientRequest, Box::new(e)); }
         };
-        return Ok(file_that_has_been_read);
+
+        let column_headers = regex
+            .capture_names()
+            .enumerate()
+            .skip(1)
+            .map(|(group_index, group_name)| match group_name {
+                Some(group_name) => group_name.to_string(),
+                None => format!("column_{}", group_index),
+            })
+            .collect();
+
+        let rows_of_cells = lines
+            .iter()
+            .filter_map(|line| regex.captures(line))
+            .map(|captures| {
+                captures
+                    .iter()
+                    .skip(1)
+                    .map(|capture| capture.map_or(String::new(), |x| x.as_str().trim().to_string()))
+                    .collect()
+            })
+            .collect();
+
+        return Ok((column_headers, rows_of_cells));
+    }
+
+    //only lines that fill in both the first and the last column are treated as part of the table
+    fn extract_rows_at_fixed_positions(lines: &[&str], column_start_positions: &[usize]) -> Vec<Vec<String>> {
+        return lines
+            .iter()
+            .map(|line| PdfFileReader::split_line_at_positions(line, column_start_positions))
+            .filter(|cells| cells.len() >= 2)
+            .filter(|cells| cells.first().map_or(false, |x| !x.is_empty()) && cells.last().map_or(false, |x| !x.is_empty()))
+            .collect();
+    }
+
+    //without a layout, columns are taken to be separated by runs of spaces
+    //and every line with at least two columns is part of the table
+    fn extract_rows_separated_by_spaces(lines: &[&str]) -> Result<Vec<Vec<String>>, AppError> {
+        let column_separator = COLUMN_SEPARATOR.get()?;
+
+        return Ok(lines
+            .iter()
+            .map(|line| column_separator.split(line.trim()).map(|cell| cell.to_string()).collect::<Vec<String>>())
+            .filter(|cells| cells.len() >= 2)
+            .collect());
+    }
+
+    //the column headers supplied in the read options name the columns of a statement that may not print them,
+    //so every line is a row. otherwise the first line is the header and later lines that repeat it
+    //(for example at the top of each page) are skipped
+    fn separate_header_from_rows(
+        rows_of_cells: Vec<Vec<String>>,
+        column_headers: Option<Vec<String>>,
+    ) -> (Vec<String>, Vec<Vec<String>>) {
+        if let Some(column_headers) = column_headers {
+            return (column_headers, rows_of_cells);
+        }
+
+        let mut rows = rows_of_cells.into_iter();
+
+        let column_headers = match rows.next() {
+            None => return (vec![], vec![]),
+            Some(column_headers) => column_headers,
+        };
+
+        let file_rows = rows.filter(|cells| *cells != column_headers).collect();
+
+        return (column_headers, file_rows);
     }
 
-    fn read_column_headers(_file: &File) -> Vec<String> {
-        vec![]
+    //a line that splits into a different number of columns than the header is not a line item that can be
+    //read reliably, so rather than dropping it the file is rejected and a layout or row pattern has to be supplied
+    fn check_row_widths(column_headers: &[String], rows_of_cells: &[Vec<String>]) -> Result<(), AppError> {
+        return match rows_of_cells.iter().find(|cells| cells.len() != column_headers.len()) {
+            Some(cells) => app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!(
+                    "the line '{}' of the pdf has {} columns but there are {} column headers, please supply a row_pattern or column_start_positions",
+                    cells.join("  "),
+                    cells.len(),
+                    column_headers.len()
+                ),
+            ),
+            None => Ok(()),
+        };
     }
 
-    fn read_file_rows(_file: &File) -> Vec<FileRow> {
-        vec![]
+    fn split_line_at_positions(line: &str, column_start_positions: &[usize]) -> Vec<String> {
+        let characters: Vec<char> = line.chars().collect();
+        let mut cells = vec![];
+
+        for (column_index, column_start) in column_start_positions.iter().enumerate() {
+            let column_end = column_start_positions
+                .get(column_index + 1)
+                .cloned()
+                .unwrap_or(characters.len())
+                .min(characters.len());
+
+            let cell: String = if *column_start < column_end {
+                characters[*column_start..column_end].iter().collect()
+            } else {
+                String::new()
+            };
+
+            cells.push(cell.trim().to_string());
+        }
+
+        return cells;
     }
 }
@@ -0,0 +1,97 @@
+use crate::external::readers::pdf::PdfFileReader;
+use crate::internal::models::entities::file_read_options::PdfReadOptions;
+
+fn get_dummy_pages() -> Vec<String> {
+    vec![
+        String::from("ACME BANK\nStatement of account 0012345\n\nDate        Description            Amount\n01/03/2022  Opening deposit        1000.00\n02/03/2022  Payment, ref 123       -250.00\nPage 1 of 2\n"),
+        String::from("ACME BANK\nDate        Description            Amount\n03/03/2022  Card refund            45.10\nPage 2 of 2\n"),
+    ]
+}
+
+//the same statement without the header line printed above its line items
+fn get_dummy_pages_without_header() -> Vec<String> {
+    return get_dummy_pages()
+        .iter()
+        .map(|page| page.replace("Date        Description            Amount\n", ""))
+        .collect();
+}
+
+#[test]
+fn test_line_items_are_detected_and_repeated_headers_are_skipped() {
+    let (headers, rows) = PdfFileReader::read_column_headers_and_file_rows(&get_dummy_pages(), &PdfReadOptions::default(), ',').unwrap();
+
+    assert_eq!(headers, vec!["Date", "Description", "Amount"]);
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].cells, vec!["01/03/2022", "Opening deposit", "1000.00"]);
+    assert_eq!(rows[1].raw_data, "02/03/2022,\"Payment, ref 123\",-250.00");
+    assert_eq!(rows[2].row_number, 3);
+    assert_eq!(rows[2].cells, vec!["03/03/2022", "Card refund", "45.10"]);
+}
+
+#[test]
+fn test_rows_are_read_using_a_row_pattern() {
+    let read_options = PdfReadOptions {
+        row_pattern: Some(String::from(r"^(?P<date>\d{2}/\d{2}/\d{4})\s+(?P<description>.+?)\s+(?P<amount>-?\d+\.\d{2})$")),
+        ..PdfReadOptions::default()
+    };
+
+    let (headers, rows) = PdfFileReader::read_column_headers_and_file_rows(&get_dummy_pages(), &read_options, ',').unwrap();
+
+    assert_eq!(headers, vec!["date", "description", "amount"]);
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[1].cells, vec!["02/03/2022", "Payment, ref 123", "-250.00"]);
+}
+
+#[test]
+fn test_rows_are_read_at_fixed_column_positions() {
+    let read_options = PdfReadOptions {
+        column_start_positions: Some(vec![0, 12, 35]),
+        column_headers: Some(vec![String::from("date"), String::from("narration"), String::from("amount")]),
+        ..PdfReadOptions::default()
+    };
+
+    let (headers, rows) = PdfFileReader::read_column_headers_and_file_rows(&get_dummy_pages_without_header(), &read_options, ',').unwrap();
+
+    assert_eq!(headers, vec!["date", "narration", "amount"]);
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].cells, vec!["01/03/2022", "Opening deposit", "1000.00"]);
+    assert_eq!(rows[2].cells, vec!["03/03/2022", "Card refund", "45.10"]);
+}
+
+#[test]
+fn test_supplied_column_headers_keep_the_first_line_item_of_a_statement_without_a_header_line() {
+    let read_options = PdfReadOptions {
+        column_headers: Some(vec![String::from("date"), String::from("narration"), String::from("amount")]),
+        ..PdfReadOptions::default()
+    };
+
+    let (headers, rows) = PdfFileReader::read_column_headers_and_file_rows(&get_dummy_pages_without_header(), &read_options, ',').unwrap();
+
+    assert_eq!(headers, vec!["date", "narration", "amount"]);
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[0].cells, vec!["01/03/2022", "Opening deposit", "1000.00"]);
+}
+
+#[test]
+fn test_lines_with_a_different_number_of_columns_than_the_header_are_rejected() {
+    let pages = vec![get_dummy_pages()[0].replace("Page 1 of 2", "Balance carried forward    750.00")];
+
+    let result = PdfFileReader::read_column_headers_and_file_rows(&pages, &PdfReadOptions::default(), ',');
+
+    assert_eq!(
+        result.unwrap_err().message,
+        "the line 'Balance carried forward  750.00' of the pdf has 2 columns but there are 3 column headers, please supply a row_pattern or column_start_positions"
+    );
+}
+
+#[test]
+fn test_invalid_row_pattern_is_rejected() {
+    let read_options = PdfReadOptions {
+        row_pattern: Some(String::from("(unclosed")),
+        ..PdfReadOptions::default()
+    };
+
+    let result = PdfFileReader::read_column_headers_and_file_rows(&get_dummy_pages(), &read_options, ',');
+
+    assert!(result.is_err());
+}
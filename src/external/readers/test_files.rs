@@ -0,0 +1,50 @@
+use std::io::Write;
+use std::ops::Deref;
+
+use tempfile::TempPath;
+
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file::{File, FileStorageLocation, SupportedFileExtension};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::ReconFileType;
+
+//a file on the local disk that is deleted again once the test using it is done with it
+pub struct DummyFile {
+    file: File,
+    _temp_path: TempPath,
+}
+
+impl Deref for DummyFile {
+    type Target = File;
+
+    fn deref(&self) -> &File {
+        return &self.file;
+    }
+}
+
+//a file written to the temp directory with the contents given. its name is unique to the test
+//and ends with file_name, so that the file keeps the extension its format is worked out from
+pub fn get_dummy_file(file_name: &str, contents: impl AsRef<[u8]>) -> DummyFile {
+    let mut temp_file = tempfile::Builder::new().suffix(&format!("-{}", file_name)).tempfile().unwrap();
+    temp_file.write_all(contents.as_ref()).unwrap();
+    let temp_path = temp_file.into_temp_path();
+
+    return DummyFile {
+        file: File {
+            file_path: Some(temp_path.to_string_lossy().to_string()),
+            ..get_dummy_file_without_path()
+        },
+        _temp_path: temp_path,
+    };
+}
+
+//a file that has not been retrieved to the local disk
+pub fn get_dummy_file_without_path() -> File {
+    return File {
+        id: None,
+        upload_request_id: None,
+        file_storage_location: FileStorageLocation::LocalFileSystem,
+        file_extension: SupportedFileExtension::Csv,
+        file_metadata: None,
+        file_path: None,
+        file_type: ReconFileType::PrimaryFile,
+    };
+}
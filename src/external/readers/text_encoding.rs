@@ -0,0 +1,359 @@
+use std::io::{BufReader, Chain, Cursor, ErrorKind, Read};
+
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{AppError, AppErrorKind};
+
+//the byte order marks that can start a text file
+const UTF8_BOM: [u8; 3] = [0xEF, 0xBB, 0xBF];
+const UTF16_LE_BOM: [u8; 2] = [0xFF, 0xFE];
+const UTF16_BE_BOM: [u8; 2] = [0xFE, 0xFF];
+
+//how many bytes at the start of a file are looked at to detect its encoding
+const ENCODING_DETECTION_SAMPLE_SIZE_IN_BYTES: u64 = 64 * 1024;
+
+//how many bytes are read from a file and decoded at a time
+const TRANSCODING_BLOCK_SIZE_IN_BYTES: usize = 8 * 1024;
+
+//the characters windows-1252 puts in 0x80 to 0x9F, where latin-1 has control characters.
+//the five bytes windows-1252 leaves undefined map to the same control characters as in latin-1
+const WINDOWS_1252_HIGH_CONTROL_CHARACTERS: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{8D}', '\u{017D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{9D}', '\u{017E}', '\u{0178}',
+];
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum TextEncoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Windows1252,
+    Latin1,
+}
+
+impl TextEncoding {
+    pub fn get_label(&self) -> &'static str {
+        return match self {
+            TextEncoding::Utf8 => "utf-8",
+            TextEncoding::Utf16Le => "utf-16le",
+            TextEncoding::Utf16Be => "utf-16be",
+            TextEncoding::Windows1252 => "windows-1252",
+            TextEncoding::Latin1 => "iso-8859-1",
+        };
+    }
+
+    /**
+    works out the encoding of a file from the first bytes in it.
+    a byte order mark decides the encoding, otherwise utf-16 is recognised by the zero bytes
+    next to ascii characters, and anything that is not valid utf-8 is taken to be windows-1252,
+    the encoding most spreadsheet exports on Windows are saved in
+
+    returns the encoding and the length of the byte order mark, if there is one
+     */
+    pub fn detect(sample: &[u8]) -> (TextEncoding, usize) {
+        if sample.starts_with(&UTF8_BOM) {
+            return (TextEncoding::Utf8, UTF8_BOM.len());
+        }
+        if sample.starts_with(&UTF16_LE_BOM) {
+            return (TextEncoding::Utf16Le, UTF16_LE_BOM.len());
+        }
+        if sample.starts_with(&UTF16_BE_BOM) {
+            return (TextEncoding::Utf16Be, UTF16_BE_BOM.len());
+        }
+
+        let pairs: Vec<&[u8]> = sample.chunks_exact(2).collect();
+        let zero_bytes_at_odd_positions = pairs.iter().filter(|pair| pair[0] != 0 && pair[1] == 0).count();
+        let zero_bytes_at_even_positions = pairs.iter().filter(|pair| pair[0] == 0 && pair[1] != 0).count();
+        if !pairs.is_empty() && zero_bytes_at_odd_positions * 2 > pairs.len() {
+            return (TextEncoding::Utf16Le, 0);
+        }
+        if !pairs.is_empty() && zero_bytes_at_even_positions * 2 > pairs.len() {
+            return (TextEncoding::Utf16Be, 0);
+        }
+
+        return match std::str::from_utf8(sample) {
+            Ok(_) => (TextEncoding::Utf8, 0),
+            //the sample may have been cut off part way through a character
+            Err(e) if e.error_len().is_none() => (TextEncoding::Utf8, 0),
+            Err(_) => (TextEncoding::Windows1252, 0),
+        };
+    }
+
+    //decodes the bytes, replacing anything that cannot be decoded instead of failing
+    pub fn decode_lossy(&self, bytes: &[u8]) -> String {
+        return match self {
+            TextEncoding::Utf8 => String::from_utf8_lossy(bytes).to_string(),
+            TextEncoding::Utf16Le => {
+                let code_units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_le_bytes([pair[0], pair[1]])).collect();
+                String::from_utf16_lossy(&code_units)
+            }
+            TextEncoding::Utf16Be => {
+                let code_units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+                String::from_utf16_lossy(&code_units)
+            }
+            TextEncoding::Windows1252 => bytes.iter().map(|byte| TextEncoding::decode_windows_1252_byte(*byte)).collect(),
+            TextEncoding::Latin1 => bytes.iter().map(|byte| *byte as char).collect(),
+        };
+    }
+
+    fn decode_windows_1252_byte(byte: u8) -> char {
+        return match byte {
+            0x80..=0x9F => WINDOWS_1252_HIGH_CONTROL_CHARACTERS[(byte - 0x80) as usize],
+            _ => byte as char,
+        };
+    }
+
+    pub fn from_label(label: &str) -> Option<TextEncoding> {
+        return match label.trim().to_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Some(TextEncoding::Utf8),
+            "utf-16le" | "utf-16" | "utf16le" | "utf16" => Some(TextEncoding::Utf16Le),
+            "utf-16be" | "utf16be" => Some(TextEncoding::Utf16Be),
+            "windows-1252" | "cp1252" => Some(TextEncoding::Windows1252),
+            "iso-8859-1" | "latin1" | "latin-1" => Some(TextEncoding::Latin1),
+            _ => None,
+        };
+    }
+
+    fn get_byte_order_mark(&self) -> &'static [u8] {
+        return match self {
+            TextEncoding::Utf8 => &UTF8_BOM,
+            TextEncoding::Utf16Le => &UTF16_LE_BOM,
+            TextEncoding::Utf16Be => &UTF16_BE_BOM,
+            TextEncoding::Windows1252 | TextEncoding::Latin1 => &[],
+        };
+    }
+
+    /**
+    decodes as much of the bytes as possible, leaving behind a character that has been cut off at the end
+    unless there are no more bytes to come
+
+    returns the decoded text and how many bytes it was decoded from, or the offset of the first byte that
+    cannot be decoded along with the text decoded before it
+     */
+    fn decode(&self, bytes: &[u8], is_at_end_of_input: bool) -> Result<(String, usize), (String, usize)> {
+        return match self {
+            TextEncoding::Utf8 => match std::str::from_utf8(bytes) {
+                Ok(text) => Ok((text.to_string(), bytes.len())),
+                Err(e) => {
+                    let valid_text = String::from_utf8_lossy(&bytes[..e.valid_up_to()]).to_string();
+                    if e.error_len().is_none() && !is_at_end_of_input {
+                        return Ok((valid_text, e.valid_up_to()));
+                    }
+                    Err((valid_text, e.valid_up_to()))
+                }
+            },
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => self.decode_utf16(bytes, is_at_end_of_input),
+            TextEncoding::Windows1252 => {
+                let mut text = String::with_capacity(bytes.len());
+                for (offset, byte) in bytes.iter().enumerate() {
+                    //bytes that windows-1252 leaves undefined
+                    if matches!(byte, 0x81 | 0x8D | 0x8F | 0x90 | 0x9D) {
+                        return Err((text, offset));
+                    }
+                    text.push(TextEncoding::decode_windows_1252_byte(*byte));
+                }
+                Ok((text, bytes.len()))
+            }
+            TextEncoding::Latin1 => Ok((self.decode_lossy(bytes), bytes.len())),
+        };
+    }
+
+    fn decode_utf16(&self, bytes: &[u8], is_at_end_of_input: bool) -> Result<(String, usize), (String, usize)> {
+        let mut text = String::with_capacity(bytes.len() / 2);
+        let mut offset = 0;
+
+        while offset + 2 <= bytes.len() {
+            let code_unit = self.get_utf16_code_unit(&bytes[offset..offset + 2]);
+
+            if !(0xD800..=0xDFFF).contains(&code_unit) {
+                text.push(char::from_u32(code_unit as u32).unwrap_or(char::REPLACEMENT_CHARACTER));
+                offset = offset + 2;
+                continue;
+            }
+
+            //a high surrogate has to be followed by a low surrogate
+            if offset + 4 > bytes.len() {
+                break;
+            }
+            let next_code_unit = self.get_utf16_code_unit(&bytes[offset + 2..offset + 4]);
+            match char::decode_utf16([code_unit, next_code_unit]).next() {
+                Some(Ok(character)) if character.len_utf16() == 2 => text.push(character),
+                _ => return Err((text, offset)),
+            }
+            offset = offset + 4;
+        }
+
+        if offset < bytes.len() && is_at_end_of_input {
+            return Err((text, offset));
+        }
+
+        return Ok((text, offset));
+    }
+
+    fn get_utf16_code_unit(&self, pair: &[u8]) -> u16 {
+        return match self {
+            TextEncoding::Utf16Be => u16::from_be_bytes([pair[0], pair[1]]),
+            _ => u16::from_le_bytes([pair[0], pair[1]]),
+        };
+    }
+}
+
+//a text file opened for reading, transcoded to utf-8 as it is read
+pub type TextFileReader = BufReader<TranscodingReader<Chain<Cursor<Vec<u8>>, std::fs::File>>>;
+
+/**
+opens a text file for reading as utf-8. the encoding is detected from the start of the file
+if none is supplied, and a byte order mark at the start of the file is never passed on
+
+# Errors
+
+This function will return an error if the file cannot be opened or the encoding is not one that is supported
+ */
+pub fn open_text_file(file_path: &str, encoding_label: Option<&str>) -> Result<TextFileReader, AppError> {
+    let mut opened_file = match std::fs::File::open(file_path) {
+        Ok(opened_file) => opened_file,
+        Err(e) => { return app_error(AppErrorKind::BadClientRequest, Box::new(e)); }
+    };
+
+    //the bytes used to detect the encoding are put back in front of the rest of the file
+    let mut sample = vec![];
+    if let Err(e) = (&mut opened_file).take(ENCODING_DETECTION_SAMPLE_SIZE_IN_BYTES).read_to_end(&mut sample) {
+        return app_error(AppErrorKind::InternalError, Box::new(e));
+    }
+
+    let encoding = match encoding_label {
+        None => TextEncoding::detect(&sample).0,
+        Some(encoding_label) => match TextEncoding::from_label(encoding_label) {
+            Some(encoding) => encoding,
+            None => {
+                return app_error_with_msg(
+                    AppErrorKind::BadClientRequest,
+                    &format!("the encoding {} is not supported, please use utf-8, utf-16le, utf-16be, windows-1252 or iso-8859-1", encoding_label),
+                );
+            }
+        },
+    };
+
+    let reader = TranscodingReader::new(Cursor::new(sample).chain(opened_file), encoding);
+    return Ok(BufReader::new(reader));
+}
+
+/**
+turns text in any supported encoding into utf-8 as it is read, a block at a time.
+bytes that cannot be decoded fail the read with an InvalidData error that gives their
+byte offset in the file and the line they are on
+ */
+pub struct TranscodingReader<R: Read> {
+    reader: R,
+    encoding: TextEncoding,
+    undecoded_bytes: Vec<u8>,
+    decoded_bytes: Vec<u8>,
+    next_decoded_byte_index: usize,
+
+    //the offset in the file of the first undecoded byte
+    file_offset: u64,
+    line_number: u64,
+    is_at_start_of_input: bool,
+    is_at_end_of_input: bool,
+}
+
+impl<R: Read> TranscodingReader<R> {
+    pub fn new(reader: R, encoding: TextEncoding) -> TranscodingReader<R> {
+        return TranscodingReader {
+            reader,
+            encoding,
+            undecoded_bytes: vec![],
+            decoded_bytes: vec![],
+            next_decoded_byte_index: 0,
+            file_offset: 0,
+            line_number: 1,
+            is_at_start_of_input: true,
+            is_at_end_of_input: false,
+        };
+    }
+
+    fn read_block(&mut self) -> std::io::Result<()> {
+        let mut block = [0u8; TRANSCODING_BLOCK_SIZE_IN_BYTES];
+        let bytes_read = self.reader.read(&mut block)?;
+        if bytes_read == 0 {
+            self.is_at_end_of_input = true;
+        }
+        self.undecoded_bytes.extend_from_slice(&block[..bytes_read]);
+        return Ok(());
+    }
+
+    fn skip_byte_order_mark(&mut self) -> std::io::Result<()> {
+        let byte_order_mark = self.encoding.get_byte_order_mark();
+
+        while self.undecoded_bytes.len() < byte_order_mark.len() && !self.is_at_end_of_input {
+            self.read_block()?;
+        }
+
+        if !byte_order_mark.is_empty() && self.undecoded_bytes.starts_with(byte_order_mark) {
+            self.undecoded_bytes.drain(..byte_order_mark.len());
+            self.file_offset = byte_order_mark.len() as u64;
+        }
+
+        self.is_at_start_of_input = false;
+        return Ok(());
+    }
+
+    //decodes the next block, returning false once there is nothing left to decode
+    fn decode_next_block(&mut self) -> std::io::Result<bool> {
+        if self.is_at_start_of_input {
+            self.skip_byte_order_mark()?;
+        }
+
+        loop {
+            if self.undecoded_bytes.is_empty() || !self.is_at_end_of_input {
+                if self.is_at_end_of_input {
+                    return Ok(false);
+                }
+                self.read_block()?;
+            }
+
+            let (text, bytes_decoded) = match self.encoding.decode(&self.undecoded_bytes, self.is_at_end_of_input) {
+                Ok(decoded) => decoded,
+                Err((text_before_error, error_offset)) => {
+                    let line_number = self.line_number + text_before_error.matches('\n').count() as u64;
+                    return Err(std::io::Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "the file is not valid {}: cannot decode byte 0x{:02X} at byte offset {} on line {}",
+                            self.encoding.get_label(),
+                            self.undecoded_bytes[error_offset],
+                            self.file_offset + error_offset as u64,
+                            line_number
+                        ),
+                    ));
+                }
+            };
+
+            self.undecoded_bytes.drain(..bytes_decoded);
+            self.file_offset = self.file_offset + bytes_decoded as u64;
+
+            if !text.is_empty() {
+                self.line_number = self.line_number + text.matches('\n').count() as u64;
+                self.decoded_bytes = text.into_bytes();
+                self.next_decoded_byte_index = 0;
+                return Ok(true);
+            }
+        }
+    }
+}
+
+impl<R: Read> Read for TranscodingReader<R> {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        if self.next_decoded_byte_index >= self.decoded_bytes.len() && !self.decode_next_block()? {
+            return Ok(0);
+        }
+
+        let remaining_decoded_bytes = &self.decoded_bytes[self.next_decoded_byte_index..];
+        let bytes_to_copy = remaining_decoded_bytes.len().min(buffer.len());
+        buffer[..bytes_to_copy].copy_from_slice(&remaining_decoded_bytes[..bytes_to_copy]);
+        self.next_decoded_byte_index = self.next_decoded_byte_index + bytes_to_copy;
+
+        return Ok(bytes_to_copy);
+    }
+}
@@ -0,0 +1,87 @@
+use std::io::Read;
+
+use crate::external::readers::text_encoding::{TextEncoding, TranscodingReader};
+
+//hands out one byte at a time so that characters are cut off between reads
+struct OneByteAtATimeReader {
+    bytes: Vec<u8>,
+    position: usize,
+}
+
+impl Read for OneByteAtATimeReader {
+    fn read(&mut self, buffer: &mut [u8]) -> std::io::Result<usize> {
+        if self.position >= self.bytes.len() || buffer.is_empty() {
+            return Ok(0);
+        }
+        buffer[0] = self.bytes[self.position];
+        self.position = self.position + 1;
+        return Ok(1);
+    }
+}
+
+fn transcode(bytes: &[u8], encoding: TextEncoding) -> std::io::Result<String> {
+    let mut text = String::new();
+    let reader = OneByteAtATimeReader { bytes: bytes.to_vec(), position: 0 };
+    TranscodingReader::new(reader, encoding).read_to_string(&mut text)?;
+    return Ok(text);
+}
+
+#[test]
+fn test_encoding_labels_are_recognised() {
+    assert_eq!(TextEncoding::from_label("UTF-8"), Some(TextEncoding::Utf8));
+    assert_eq!(TextEncoding::from_label("utf_16be"), Some(TextEncoding::Utf16Be));
+    assert_eq!(TextEncoding::from_label("cp1252"), Some(TextEncoding::Windows1252));
+    assert_eq!(TextEncoding::from_label(" Latin1 "), Some(TextEncoding::Latin1));
+    assert_eq!(TextEncoding::from_label("ebcdic"), None);
+}
+
+#[test]
+fn test_byte_order_mark_is_not_passed_on() {
+    let utf8 = transcode(b"\xEF\xBB\xBFid,amount\n", TextEncoding::Utf8).unwrap();
+    let utf16_le = transcode(&[0xFF, 0xFE, b'i', 0, b'd', 0, b'\n', 0], TextEncoding::Utf16Le).unwrap();
+    let utf16_be = transcode(&[0xFE, 0xFF, 0, b'i', 0, b'd', 0, b'\n'], TextEncoding::Utf16Be).unwrap();
+
+    assert_eq!(utf8, "id,amount\n");
+    assert_eq!(utf16_le, "id\n");
+    assert_eq!(utf16_be, "id\n");
+}
+
+#[test]
+fn test_characters_cut_off_between_reads_are_decoded() {
+    //"é" and "€" in utf-8, and a character outside the basic multilingual plane in utf-16
+    let utf8 = transcode("caf\u{e9} 5\u{20AC}".as_bytes(), TextEncoding::Utf8).unwrap();
+    let utf16_le = transcode(&[0x3D, 0xD8, 0x00, 0xDE, b'!', 0], TextEncoding::Utf16Le).unwrap();
+
+    assert_eq!(utf8, "caf\u{e9} 5\u{20AC}");
+    assert_eq!(utf16_le, "\u{1F600}!");
+}
+
+#[test]
+fn test_single_byte_encodings_are_decoded() {
+    let windows_1252 = transcode(b"caf\xE9 \x80 5\n", TextEncoding::Windows1252).unwrap();
+    let latin1 = transcode(b"caf\xE9 \x80\n", TextEncoding::Latin1).unwrap();
+
+    assert_eq!(windows_1252, "caf\u{e9} \u{20AC} 5\n");
+    assert_eq!(latin1, "caf\u{e9} \u{80}\n");
+}
+
+#[test]
+fn test_bytes_that_cannot_be_decoded_are_reported_with_their_position() {
+    let invalid_utf8 = transcode(b"id,amount\n001,2000\n002,caf\xE9\n", TextEncoding::Utf8).unwrap_err();
+    let undefined_windows_1252 = transcode(b"id\n\x81\n", TextEncoding::Windows1252).unwrap_err();
+    let unpaired_surrogate = transcode(&[b'a', 0, 0x3D, 0xD8, b'b', 0], TextEncoding::Utf16Le).unwrap_err();
+
+    assert_eq!(invalid_utf8.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(
+        invalid_utf8.to_string(),
+        "the file is not valid utf-8: cannot decode byte 0xE9 at byte offset 26 on line 3"
+    );
+    assert_eq!(
+        undefined_windows_1252.to_string(),
+        "the file is not valid windows-1252: cannot decode byte 0x81 at byte offset 3 on line 2"
+    );
+    assert_eq!(
+        unpaired_surrogate.to_string(),
+        "the file is not valid utf-16le: cannot decode byte 0x3D at byte offset 2 on line 1"
+    );
+}
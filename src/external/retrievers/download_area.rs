@@ -0,0 +1,53 @@
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{
+    AppError, AppErrorKind,
+};
+
+//a directory owned by this service into which remote files are downloaded.
+//only files inside it are ever deleted when a retrieved file is released
+#[derive(Clone, Debug)]
+pub struct DownloadArea {
+    pub directory: PathBuf,
+}
+
+impl DownloadArea {
+    pub fn new(directory: PathBuf) -> DownloadArea {
+        return DownloadArea { directory };
+    }
+
+    //a fresh, unique path inside the download area that keeps
+    //the extension of the remote file it will hold
+    pub fn new_file_path(&self, remote_file_path: &str) -> Result<PathBuf, AppError> {
+        if let Err(e) = std::fs::create_dir_all(&self.directory) {
+            return app_error(AppErrorKind::InternalError, Box::new(e));
+        }
+
+        let extension = Path::new(remote_file_path.split(['?', '#']).next().unwrap_or(""))
+            .extension()
+            .and_then(|x| x.to_str())
+            .map(|x| format!(".{}", x))
+            .unwrap_or_default();
+
+        return Ok(self.directory.join(format!("{}{}", Uuid::new_v4(), extension)));
+    }
+
+    pub fn contains(&self, file_path: &Path) -> bool {
+        return file_path.parent() == Some(self.directory.as_path());
+    }
+
+    pub fn remove_file(&self, file_path: &Path) -> Result<(), AppError> {
+        if !self.contains(file_path) {
+            return Ok(());
+        }
+
+        return match std::fs::remove_file(file_path) {
+            Ok(_) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => app_error(AppErrorKind::InternalError, Box::new(e)),
+        };
+    }
+}
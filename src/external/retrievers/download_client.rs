@@ -0,0 +1,218 @@
+use std::error::Error;
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use hyper::client::connect::dns::Name;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+use reqwest::redirect::{Attempt, Policy};
+use reqwest::Url;
+
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{AppError, AppErrorKind};
+
+const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const DEFAULT_READ_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_MAX_FILE_SIZE: u64 = 1024 * 1024 * 1024;
+const MAX_REDIRECTS: usize = 10;
+
+//limits on the files downloaded over http(s) and from an s3 object store
+#[derive(Clone, Debug)]
+pub struct DownloadSettings {
+    pub connect_timeout: Duration,
+
+    //the longest a server may go without sending any more of a file
+    pub read_timeout: Duration,
+
+    //files larger than this many bytes are not downloaded
+    pub max_file_size: u64,
+
+    //hosts that files may be downloaded from even though they are on a loopback, private or link-local address
+    pub allowed_private_hosts: Vec<String>,
+}
+
+impl Default for DownloadSettings {
+    fn default() -> Self {
+        return DownloadSettings {
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            read_timeout: DEFAULT_READ_TIMEOUT,
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+            allowed_private_hosts: vec![],
+        };
+    }
+}
+
+/**
+the http client every file is downloaded with. it is built once and shared, so connections are reused,
+and it only connects to public addresses unless a host has been allowed, so that a caller cannot have
+the service fetch its own metadata endpoint or anything else on the internal network
+ */
+#[derive(Clone)]
+pub struct DownloadClient {
+    pub client: reqwest::Client,
+    pub settings: DownloadSettings,
+}
+
+//the reason a download was refused because of where it was going
+#[derive(Debug)]
+struct NonPublicAddressError {
+    message: String,
+}
+
+//resolves host names the same way as the default resolver, but refuses any host
+//that resolves to a non-public address. checking the addresses that are actually
+//connected to means a host cannot resolve to a public address first and a private one later
+struct PublicAddressResolver {
+    allowed_private_hosts: Arc<Vec<String>>,
+}
+
+impl DownloadClient {
+    /**
+    builds the shared client from the download settings
+
+    # Errors
+
+    an internal error if the http client cannot be built
+     */
+    pub fn new(settings: DownloadSettings) -> Result<DownloadClient, AppError> {
+        let allowed_private_hosts = Arc::new(settings.allowed_private_hosts.iter().map(|host| host.to_lowercase()).collect::<Vec<String>>());
+        let redirect_allowed_private_hosts = allowed_private_hosts.clone();
+
+        let client = reqwest::Client::builder()
+            .connect_timeout(settings.connect_timeout)
+            .dns_resolver(Arc::new(PublicAddressResolver { allowed_private_hosts }))
+            .redirect(Policy::custom(move |attempt: Attempt| {
+                if attempt.previous().len() >= MAX_REDIRECTS {
+                    return attempt.error("too many redirects");
+                }
+                return match check_url_host(attempt.url(), &redirect_allowed_private_hosts) {
+                    Ok(()) => attempt.follow(),
+                    Err(e) => attempt.error(e),
+                };
+            }))
+            .build();
+
+        return match client {
+            Ok(client) => Ok(DownloadClient { client, settings }),
+            Err(e) => app_error(AppErrorKind::InternalError, Box::new(e)),
+        };
+    }
+
+    /**
+    checks that a file may be downloaded from the url before any request is sent. host names are
+    checked again when they are resolved, this catches the addresses written into the url itself
+
+    # Errors
+
+    a bad client request if the url's host is a non-public address that has not been allowed
+     */
+    pub fn check_url(&self, url: &Url) -> Result<(), AppError> {
+        let allowed_private_hosts: Vec<String> = self.settings.allowed_private_hosts.iter().map(|host| host.to_lowercase()).collect();
+
+        return match check_url_host(url, &allowed_private_hosts) {
+            Ok(()) => Ok(()),
+            Err(e) => app_error_with_msg(AppErrorKind::BadClientRequest, &e.message),
+        };
+    }
+
+    //a request that could not be sent because of where it was going is the caller's doing, anything else is ours
+    pub fn send_error<T>(e: reqwest::Error) -> Result<T, AppError> {
+        let mut source: Option<&(dyn Error + 'static)> = Some(&e);
+        while let Some(error) = source {
+            if let Some(non_public_address_error) = error.downcast_ref::<NonPublicAddressError>() {
+                return app_error_with_msg(AppErrorKind::BadClientRequest, &non_public_address_error.message);
+            }
+            source = error.source();
+        }
+
+        return app_error(AppErrorKind::InternalError, Box::new(e));
+    }
+}
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let host = name.as_str().to_lowercase();
+        let is_host_allowed = self.allowed_private_hosts.contains(&host);
+
+        return Box::pin(async move {
+            let addresses: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0)).await?.collect();
+
+            if !is_host_allowed {
+                if let Some(address) = addresses.iter().find(|address| !is_public_address(address.ip())) {
+                    return Err(Box::new(NonPublicAddressError::new(&host, address.ip())) as Box<dyn Error + Send + Sync>);
+                }
+            }
+
+            let addresses: Addrs = Box::new(addresses.into_iter());
+            return Ok(addresses);
+        });
+    }
+}
+
+impl NonPublicAddressError {
+    fn new(host: &str, address: IpAddr) -> NonPublicAddressError {
+        return NonPublicAddressError {
+            message: format!("files cannot be downloaded from {}, it is on the non-public address {}", host, address),
+        };
+    }
+}
+
+impl fmt::Display for NonPublicAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.message);
+    }
+}
+
+impl Error for NonPublicAddressError {}
+
+//a url whose host is written as an address never reaches the resolver, so its address is checked here
+fn check_url_host(url: &Url, allowed_private_hosts: &[String]) -> Result<(), NonPublicAddressError> {
+    let host = url.host_str().unwrap_or_default().to_lowercase();
+    let address = match host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() {
+        Ok(address) => address,
+        Err(_) => return Ok(()),
+    };
+
+    if is_public_address(address) || allowed_private_hosts.contains(&host) {
+        return Ok(());
+    }
+    return Err(NonPublicAddressError::new(&host, address));
+}
+
+pub(crate) fn is_public_address(address: IpAddr) -> bool {
+    return match address {
+        IpAddr::V4(address) => is_public_ipv4_address(address),
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(mapped_address) => is_public_ipv4_address(mapped_address),
+            None => is_public_ipv6_address(address),
+        },
+    };
+}
+
+fn is_public_ipv4_address(address: Ipv4Addr) -> bool {
+    let octets = address.octets();
+
+    //0.0.0.0/8 and the 100.64.0.0/10 shared address space used by carrier grade nat
+    let is_this_network = octets[0] == 0;
+    let is_shared = octets[0] == 100 && (octets[1] & 0b1100_0000) == 64;
+
+    return !(address.is_loopback()
+        || address.is_private()
+        || address.is_link_local()
+        || address.is_broadcast()
+        || address.is_documentation()
+        || address.is_multicast()
+        || is_this_network
+        || is_shared);
+}
+
+fn is_public_ipv6_address(address: Ipv6Addr) -> bool {
+    let first_segment = address.segments()[0];
+
+    //fc00::/7 unique local and fe80::/10 link-local addresses
+    let is_unique_local = (first_segment & 0xfe00) == 0xfc00;
+    let is_link_local = (first_segment & 0xffc0) == 0xfe80;
+
+    return !(address.is_loopback() || address.is_unspecified() || address.is_multicast() || is_unique_local || is_link_local);
+}
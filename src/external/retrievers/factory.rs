@@ -0,0 +1,85 @@
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+
+use crate::internal::{
+    interfaces::file_retriever::FileRetriever,
+    models::entities::{file_storage::FileStorage, retrieved_file::RetrievedFile},
+    shared_reconciler_rust_libraries::models::entities::{
+        app_errors::{AppError, AppErrorKind},
+        file::File,
+    },
+};
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error_with_msg;
+
+use super::{
+    download_area::DownloadArea,
+    download_client::DownloadClient,
+    http::HttpFileRetriever,
+    local::LocalFileRetriever,
+    s3::{S3FileRetriever, S3Settings, S3_FILE_SCHEME},
+};
+
+//picks how a file is retrieved from where it is stored: http(s) urls are downloaded,
+//s3://{bucket}/{key} paths are fetched from the configured object store and local
+//files are read in place from the local files directory
+pub struct FileRetrieverFactory {
+    pub download_area: DownloadArea,
+    pub download_client: DownloadClient,
+    pub local_files_directory: Option<PathBuf>,
+    pub s3_settings: Option<S3Settings>,
+}
+
+#[async_trait]
+impl FileRetriever for FileRetrieverFactory {
+    async fn retrieve_file(&self, file: File, file_storage: FileStorage) -> Result<RetrievedFile, AppError> {
+        let file_path = match file.file_path.clone() {
+            None => {
+                return app_error_with_msg(AppErrorKind::BadClientRequest, "please supply a file_path for the file");
+            }
+            Some(path) => path
+        };
+
+        return match file_storage {
+            FileStorage::Http => {
+                if !file_path.starts_with("http://") && !file_path.starts_with("https://") {
+                    return app_error_with_msg(AppErrorKind::BadClientRequest, "the file_path of a file stored on a web server must be an http(s) url");
+                }
+                HttpFileRetriever::retrieve_file(file, &file_path, &self.download_area, &self.download_client).await
+            }
+            FileStorage::S3 => {
+                if !file_path.starts_with(S3_FILE_SCHEME) {
+                    return app_error_with_msg(AppErrorKind::BadClientRequest, "the file_path of a file stored in S3 must be an s3://{bucket}/{key} path");
+                }
+                match &self.s3_settings {
+                    Some(s3_settings) => S3FileRetriever::retrieve_file(file, &file_path, s3_settings, &self.download_area, &self.download_client).await,
+                    None => app_error_with_msg(AppErrorKind::BadClientRequest, "no S3 object store has been configured for this service"),
+                }
+            }
+            FileStorage::LocalFileSystem => LocalFileRetriever::retrieve_file(file, &file_path, self.local_files_directory.as_deref()),
+        };
+    }
+
+    async fn release_file(&self, retrieved_file: &File) -> Result<(), AppError> {
+        return match &retrieved_file.file_path {
+            None => Ok(()),
+            Some(file_path) => self.download_area.remove_file(Path::new(file_path)),
+        };
+    }
+}
+
+impl FileRetrieverFactory {
+    pub fn new(
+        download_directory: String,
+        local_files_directory: Option<String>,
+        s3_settings: Option<S3Settings>,
+        download_client: DownloadClient,
+    ) -> FileRetrieverFactory {
+        return FileRetrieverFactory {
+            download_area: DownloadArea::new(download_directory.into()),
+            download_client,
+            local_files_directory: local_files_directory.map(PathBuf::from),
+            s3_settings,
+        };
+    }
+}
@@ -0,0 +1,40 @@
+use std::io::Read;
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{AppError, AppErrorKind};
+
+//how much of the file is hashed at a time
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+pub struct FileFingerprint {}
+
+impl FileFingerprint {
+    //the hex encoded sha256 of a file that is not downloaded, read a buffer at a time
+    pub fn compute(file_path: &Path) -> Result<String, AppError> {
+        let mut opened_file = match std::fs::File::open(file_path) {
+            Ok(opened_file) => opened_file,
+            Err(e) => { return app_error(AppErrorKind::BadClientRequest, Box::new(e)); }
+        };
+
+        let mut hasher = Sha256::new();
+        let mut buffer = vec![0u8; READ_BUFFER_SIZE];
+
+        loop {
+            let bytes_read = match opened_file.read(&mut buffer) {
+                Ok(bytes_read) => bytes_read,
+                Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+            };
+
+            if bytes_read == 0 {
+                break;
+            }
+
+            hasher.update(&buffer[..bytes_read]);
+        }
+
+        return Ok(hex::encode(hasher.finalize()));
+    }
+}
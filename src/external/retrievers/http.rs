@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+use tokio::io::AsyncWriteExt;
+
+use crate::external::retrievers::download_area::DownloadArea;
+use crate::external::retrievers::download_client::DownloadClient;
+use crate::internal::models::entities::retrieved_file::RetrievedFile;
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+pub struct HttpFileRetriever {}
+
+impl HttpFileRetriever {
+    //downloads a file served over http(s) into the download area
+    pub async fn retrieve_file(
+        file: File,
+        file_url: &str,
+        download_area: &DownloadArea,
+        download_client: &DownloadClient,
+    ) -> Result<RetrievedFile, AppError> {
+        let parsed_file_url = match reqwest::Url::parse(file_url) {
+            Ok(parsed_file_url) => parsed_file_url,
+            Err(e) => { return app_error(AppErrorKind::BadClientRequest, Box::new(e)); }
+        };
+        download_client.check_url(&parsed_file_url)?;
+
+        let response = match download_client.client.get(parsed_file_url).send().await {
+            Ok(response) => response,
+            Err(e) => { return DownloadClient::send_error(e); }
+        };
+
+        let downloaded_file_path = download_area.new_file_path(file_url)?;
+        let file_hash = HttpFileRetriever::save_response_body(response, &downloaded_file_path, download_area, download_client, file_url).await?;
+
+        return Ok(RetrievedFile {
+            file: File {
+                file_path: Some(downloaded_file_path.to_string_lossy().to_string()),
+                ..file
+            },
+            file_hash,
+        });
+    }
+
+    /**
+    streams the body of a successful response to disk so that large files are never held in memory in full,
+    hashing it as it is written. a body larger than the download settings allow, or one that stops arriving
+    for longer than their read timeout, is abandoned and what was written of it is removed
+
+    returns the hex encoded sha256 of the body
+     */
+    pub async fn save_response_body(
+        mut response: reqwest::Response,
+        downloaded_file_path: &Path,
+        download_area: &DownloadArea,
+        download_client: &DownloadClient,
+        file_url: &str,
+    ) -> Result<String, AppError> {
+        if !response.status().is_success() {
+            return app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!("unable to download {}, server responded with {}", file_url, response.status()),
+            );
+        }
+
+        let max_file_size = download_client.settings.max_file_size;
+        let file_too_large_message = format!("unable to download {}, it is larger than the {} bytes a file may be", file_url, max_file_size);
+        if response.content_length().is_some_and(|content_length| content_length > max_file_size) {
+            return app_error_with_msg(AppErrorKind::BadClientRequest, &file_too_large_message);
+        }
+
+        let mut downloaded_file = match tokio::fs::File::create(downloaded_file_path).await {
+            Ok(downloaded_file) => downloaded_file,
+            Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+        };
+        let mut hasher = Sha256::new();
+        let mut downloaded_size: u64 = 0;
+
+        loop {
+            let chunk = match tokio::time::timeout(download_client.settings.read_timeout, response.chunk()).await {
+                Ok(Ok(Some(chunk))) => chunk,
+                Ok(Ok(None)) => break,
+                Ok(Err(e)) => {
+                    let _ = download_area.remove_file(downloaded_file_path);
+                    return app_error(AppErrorKind::InternalError, Box::new(e));
+                }
+                Err(_) => {
+                    let _ = download_area.remove_file(downloaded_file_path);
+                    return app_error_with_msg(
+                        AppErrorKind::InternalError,
+                        &format!("unable to download {}, the server stopped sending it", file_url),
+                    );
+                }
+            };
+
+            //a server need not say how large a body is, or may say it wrongly
+            downloaded_size = downloaded_size + chunk.len() as u64;
+            if downloaded_size > max_file_size {
+                let _ = download_area.remove_file(downloaded_file_path);
+                return app_error_with_msg(AppErrorKind::BadClientRequest, &file_too_large_message);
+            }
+
+            hasher.update(&chunk);
+            if let Err(e) = downloaded_file.write_all(&chunk).await {
+                let _ = download_area.remove_file(downloaded_file_path);
+                return app_error(AppErrorKind::InternalError, Box::new(e));
+            }
+        }
+
+        if let Err(e) = downloaded_file.flush().await {
+            let _ = download_area.remove_file(downloaded_file_path);
+            return app_error(AppErrorKind::InternalError, Box::new(e));
+        }
+
+        return Ok(hex::encode(hasher.finalize()));
+    }
+}
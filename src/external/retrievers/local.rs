@@ -0,0 +1,88 @@
+use std::path::{Component, Path};
+
+use crate::external::retrievers::fingerprint::FileFingerprint;
+use crate::internal::models::entities::retrieved_file::RetrievedFile;
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error_with_msg;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+pub const LOCAL_FILE_SCHEME: &'static str = "file://";
+
+pub struct LocalFileRetriever {}
+
+impl LocalFileRetriever {
+    /**
+    files on the service's own disk are read in place. when a directory is configured for them they are only read
+    from inside it and relative paths are taken to be inside it, otherwise any path is read as it is given.
+    nothing is copied, so the file is read once just to hash it
+
+    # Errors
+
+    This function will return an error if the file does not exist, or if it is outside of the configured directory
+     */
+    pub fn retrieve_file(file: File, file_path: &str, local_files_directory: Option<&Path>) -> Result<RetrievedFile, AppError> {
+        let local_files_directory = match local_files_directory {
+            Some(local_files_directory) => local_files_directory,
+            None => return LocalFileRetriever::retrieve_file_from_anywhere(file, file_path),
+        };
+
+        let local_file_path = Path::new(file_path.strip_prefix(LOCAL_FILE_SCHEME).unwrap_or(file_path));
+
+        //a path may never climb out of a directory, even if it would end up back inside the local files directory
+        if local_file_path.components().any(|component| component == Component::ParentDir) {
+            return app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!("the file path {} is not allowed to contain ..", file_path),
+            );
+        }
+
+        //symbolic links could still lead out of the directory, so the resolved paths are compared
+        let (resolved_directory, resolved_file_path) = match (
+            local_files_directory.canonicalize(),
+            local_files_directory.join(local_file_path).canonicalize(),
+        ) {
+            (Ok(resolved_directory), Ok(resolved_file_path)) => (resolved_directory, resolved_file_path),
+            _ => {
+                return app_error_with_msg(AppErrorKind::BadClientRequest, &format!("file {} does not exist", file_path));
+            }
+        };
+
+        if !resolved_file_path.starts_with(&resolved_directory) {
+            return app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!("file {} is outside of the directory that local files are read from", file_path),
+            );
+        }
+
+        if !resolved_file_path.is_file() {
+            return app_error_with_msg(AppErrorKind::BadClientRequest, &format!("file {} does not exist", file_path));
+        }
+
+        return Ok(RetrievedFile {
+            file_hash: FileFingerprint::compute(&resolved_file_path)?,
+            file: File {
+                file_path: Some(resolved_file_path.to_string_lossy().to_string()),
+                ..file
+            },
+        });
+    }
+
+    //without a local files directory the path is read as it is given, as it always has been
+    fn retrieve_file_from_anywhere(file: File, file_path: &str) -> Result<RetrievedFile, AppError> {
+        let local_file_path = Path::new(file_path.strip_prefix(LOCAL_FILE_SCHEME).unwrap_or(file_path));
+
+        if !local_file_path.is_file() {
+            return app_error_with_msg(AppErrorKind::BadClientRequest, &format!("file {} does not exist", file_path));
+        }
+
+        return Ok(RetrievedFile {
+            file_hash: FileFingerprint::compute(local_file_path)?,
+            file: File {
+                file_path: Some(local_file_path.to_string_lossy().to_string()),
+                ..file
+            },
+        });
+    }
+}
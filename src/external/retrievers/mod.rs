@@ -0,0 +1,11 @@
+mod download_area;
+pub mod download_client;
+pub mod factory;
+mod fingerprint;
+mod http;
+mod local;
+pub mod s3;
+
+#[cfg(test)]
+#[path = "./retrievers_test.rs"]
+mod retrievers_test;
@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use sha2::{Digest, Sha256};
+
+use crate::external::retrievers::download_area::DownloadArea;
+use crate::external::retrievers::download_client::DownloadClient;
+use crate::external::retrievers::http::HttpFileRetriever;
+use crate::internal::models::entities::retrieved_file::RetrievedFile;
+use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::{AppError, AppErrorKind},
+    file::File,
+};
+
+pub const S3_FILE_SCHEME: &'static str = "s3://";
+
+//sha256 of an empty request body
+const EMPTY_PAYLOAD_HASH: &'static str = "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855";
+
+//connection details for an S3 compatible object store.
+//buckets are addressed path style ({endpoint_url}/{bucket}/{key})
+//so that self hosted stores such as MinIO work without any DNS setup
+#[derive(Clone, Debug)]
+pub struct S3Settings {
+    pub endpoint_url: String,
+    pub region: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+}
+
+pub struct S3FileRetriever {}
+
+impl S3FileRetriever {
+    //downloads an object referenced as s3://{bucket}/{key} into the download area
+    pub async fn retrieve_file(
+        file: File,
+        file_path: &str,
+        settings: &S3Settings,
+        download_area: &DownloadArea,
+        download_client: &DownloadClient,
+    ) -> Result<RetrievedFile, AppError> {
+        let (bucket, key) = S3FileRetriever::parse_object_path(file_path)?;
+
+        let object_url = match reqwest::Url::parse(&format!(
+            "{}/{}/{}",
+            settings.endpoint_url.trim_end_matches('/'),
+            bucket,
+            S3FileRetriever::uri_encode_path(&key)
+        )) {
+            Ok(object_url) => object_url,
+            Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+        };
+
+        let host = match (object_url.host_str(), object_url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            (None, _) => {
+                return app_error_with_msg(AppErrorKind::InternalError, "the S3 endpoint url does not contain a host");
+            }
+        };
+
+        let now = Utc::now();
+        let mut headers = BTreeMap::new();
+        headers.insert("host".to_string(), host);
+        headers.insert("x-amz-content-sha256".to_string(), EMPTY_PAYLOAD_HASH.to_string());
+        headers.insert("x-amz-date".to_string(), now.format("%Y%m%dT%H%M%SZ").to_string());
+
+        let authorization = S3FileRetriever::get_authorization_header(
+            "GET",
+            object_url.path(),
+            &headers,
+            EMPTY_PAYLOAD_HASH,
+            now,
+            settings,
+        );
+
+        let response = match download_client
+            .client
+            .get(object_url.clone())
+            .header("x-amz-content-sha256", EMPTY_PAYLOAD_HASH)
+            .header("x-amz-date", headers["x-amz-date"].clone())
+            .header("authorization", authorization)
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => { return DownloadClient::send_error(e); }
+        };
+
+        let downloaded_file_path = download_area.new_file_path(&key)?;
+        let file_hash = HttpFileRetriever::save_response_body(response, &downloaded_file_path, download_area, download_client, file_path).await?;
+
+        return Ok(RetrievedFile {
+            file: File {
+                file_path: Some(downloaded_file_path.to_string_lossy().to_string()),
+                ..file
+            },
+            file_hash,
+        });
+    }
+
+    fn parse_object_path(file_path: &str) -> Result<(String, String), AppError> {
+        let bucket_and_key = file_path.strip_prefix(S3_FILE_SCHEME).unwrap_or(file_path);
+
+        return match bucket_and_key.split_once('/') {
+            Some((bucket, key)) if !bucket.is_empty() && !key.is_empty() => Ok((bucket.to_string(), key.to_string())),
+            _ => app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!("{} is not a valid object path, expected s3://{{bucket}}/{{key}}", file_path),
+            ),
+        };
+    }
+
+    /**
+    builds the Authorization header for a request signed with AWS Signature Version 4
+
+    `headers` must hold every header that is to be signed, keyed by its lowercase name
+     */
+    pub(crate) fn get_authorization_header(
+        method: &str,
+        canonical_uri: &str,
+        headers: &BTreeMap<String, String>,
+        payload_hash: &str,
+        request_time: DateTime<Utc>,
+        settings: &S3Settings,
+    ) -> String {
+        let amz_date = request_time.format("%Y%m%dT%H%M%SZ").to_string();
+        let date_stamp = request_time.format("%Y%m%d").to_string();
+        let credential_scope = format!("{}/{}/s3/aws4_request", date_stamp, settings.region);
+
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect();
+        let signed_headers = headers.keys().cloned().collect::<Vec<String>>().join(";");
+
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            method, canonical_uri, "", canonical_headers, signed_headers, payload_hash
+        );
+
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            credential_scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+
+        let signing_key = [date_stamp.as_str(), settings.region.as_str(), "s3", "aws4_request"]
+            .iter()
+            .fold(
+                format!("AWS4{}", settings.secret_access_key).into_bytes(),
+                |key, part| S3FileRetriever::hmac_sha256(&key, part.as_bytes()),
+            );
+
+        let signature = hex::encode(S3FileRetriever::hmac_sha256(&signing_key, string_to_sign.as_bytes()));
+
+        return format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            settings.access_key_id, credential_scope, signed_headers, signature
+        );
+    }
+
+    fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts keys of any length");
+        mac.update(data);
+        return mac.finalize().into_bytes().to_vec();
+    }
+
+    //percent encodes everything in an object key except
+    //the characters S3 leaves as they are, keeping the '/' separators
+    fn uri_encode_path(key: &str) -> String {
+        let mut encoded = String::new();
+        for byte in key.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => encoded.push(byte as char),
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        return encoded;
+    }
+}
@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use serde::Serialize;
+
+use crate::external::stores::file_split_file_checkpoint_store::get_checkpoint_key;
+use crate::internal::{
+    interfaces::split_file_checkpoint_store::SplitFileCheckpointStoreInterface,
+    models::entities::split_file_checkpoint::SplitFileCheckpoint,
+    shared_reconciler_rust_libraries::{
+        common::utils::{app_error, app_error_with_msg},
+        models::entities::{
+            app_errors::{AppError, AppErrorKind},
+            recon_tasks_models::ReconFileType,
+        },
+    },
+};
+
+#[derive(Serialize)]
+struct DaprStateItem<'a> {
+    key: String,
+    value: &'a SplitFileCheckpoint,
+}
+
+//keeps checkpoints in a Dapr state store through the sidecar's http api
+pub struct DaprSplitFileCheckpointStore {
+    pub dapr_connection_url: String,
+    pub state_store_name: String,
+
+    //built once so that every checkpoint saved reuses the same connections to the sidecar
+    http_client: reqwest::Client,
+}
+
+#[async_trait]
+impl SplitFileCheckpointStoreInterface for DaprSplitFileCheckpointStore {
+    async fn save_checkpoint(&self, checkpoint: &SplitFileCheckpoint) -> Result<(), AppError> {
+        let state_items = vec![DaprStateItem {
+            key: get_checkpoint_key(&checkpoint.upload_request_id, &checkpoint.file_type),
+            value: checkpoint,
+        }];
+
+        let response = match self.http_client
+            .post(self.get_state_url())
+            .json(&state_items)
+            .send()
+            .await
+        {
+            Ok(response) => response,
+            Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+        };
+
+        if !response.status().is_success() {
+            return app_error_with_msg(
+                AppErrorKind::InternalError,
+                &format!("failed to save split file checkpoint, dapr state store returned status {}", response.status()),
+            );
+        }
+
+        return Ok(());
+    }
+
+    async fn get_checkpoint(
+        &self,
+        upload_request_id: &str,
+        file_type: &ReconFileType,
+    ) -> Result<Option<SplitFileCheckpoint>, AppError> {
+        let state_url = format!("{}/{}", self.get_state_url(), get_checkpoint_key(upload_request_id, file_type));
+
+        let response = match self.http_client.get(state_url).send().await {
+            Ok(response) => response,
+            Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+        };
+
+        //dapr answers with no content when the key has never been saved
+        if response.status() == StatusCode::NO_CONTENT {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            return app_error_with_msg(
+                AppErrorKind::InternalError,
+                &format!("failed to get split file checkpoint, dapr state store returned status {}", response.status()),
+            );
+        }
+
+        return match response.json::<SplitFileCheckpoint>().await {
+            Ok(checkpoint) => Ok(Some(checkpoint)),
+            Err(e) => app_error(AppErrorKind::InternalError, Box::new(e)),
+        };
+    }
+}
+
+impl DaprSplitFileCheckpointStore {
+    pub fn new(dapr_connection_url: String, state_store_name: String) -> DaprSplitFileCheckpointStore {
+        return DaprSplitFileCheckpointStore {
+            dapr_connection_url,
+            state_store_name,
+            http_client: reqwest::Client::new(),
+        };
+    }
+
+    fn get_state_url(&self) -> String {
+        return format!(
+            "{}/v1.0/state/{}",
+            self.dapr_connection_url.trim_end_matches('/'),
+            self.state_store_name
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::internal::{
+    interfaces::split_file_checkpoint_store::SplitFileCheckpointStoreInterface,
+    models::entities::split_file_checkpoint::SplitFileCheckpoint,
+    shared_reconciler_rust_libraries::{
+        common::utils::app_error,
+        models::entities::{
+            app_errors::{AppError, AppErrorKind},
+            recon_tasks_models::ReconFileType,
+        },
+    },
+};
+
+//keeps each checkpoint as a json file in a directory on the local disk
+pub struct FileSplitFileCheckpointStore {
+    pub directory: PathBuf,
+}
+
+#[async_trait]
+impl SplitFileCheckpointStoreInterface for FileSplitFileCheckpointStore {
+    //the checkpoint is written to a temporary file first and then renamed over the old one,
+    //so a process that dies mid write never leaves a half written checkpoint behind
+    async fn save_checkpoint(&self, checkpoint: &SplitFileCheckpoint) -> Result<(), AppError> {
+        if let Err(e) = tokio::fs::create_dir_all(&self.directory).await {
+            return app_error(AppErrorKind::InternalError, Box::new(e));
+        }
+
+        let contents = match serde_json::to_vec(checkpoint) {
+            Ok(contents) => contents,
+            Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+        };
+
+        let checkpoint_path = self.get_checkpoint_path(&checkpoint.upload_request_id, &checkpoint.file_type);
+        let temporary_path = checkpoint_path.with_extension("json.tmp");
+
+        if let Err(e) = tokio::fs::write(&temporary_path, contents).await {
+            return app_error(AppErrorKind::InternalError, Box::new(e));
+        }
+
+        return match tokio::fs::rename(&temporary_path, &checkpoint_path).await {
+            Ok(_) => Ok(()),
+            Err(e) => app_error(AppErrorKind::InternalError, Box::new(e)),
+        };
+    }
+
+    async fn get_checkpoint(
+        &self,
+        upload_request_id: &str,
+        file_type: &ReconFileType,
+    ) -> Result<Option<SplitFileCheckpoint>, AppError> {
+        let contents = match tokio::fs::read(self.get_checkpoint_path(upload_request_id, file_type)).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+        };
+
+        return match serde_json::from_slice(&contents) {
+            Ok(checkpoint) => Ok(Some(checkpoint)),
+            Err(e) => app_error(AppErrorKind::InternalError, Box::new(e)),
+        };
+    }
+}
+
+impl FileSplitFileCheckpointStore {
+    pub fn new(directory: String) -> FileSplitFileCheckpointStore {
+        return FileSplitFileCheckpointStore { directory: directory.into() };
+    }
+
+    fn get_checkpoint_path(&self, upload_request_id: &str, file_type: &ReconFileType) -> PathBuf {
+        return self.directory.join(format!("{}.json", get_checkpoint_key(upload_request_id, file_type)));
+    }
+}
+
+//only characters that are safe in both file names and urls are kept in the key
+pub fn get_checkpoint_key(upload_request_id: &str, file_type: &ReconFileType) -> String {
+    let safe_upload_request_id: String = upload_request_id
+        .chars()
+        .map(|x| if x.is_ascii_alphanumeric() || x == '-' || x == '_' { x } else { '_' })
+        .collect();
+
+    return format!("{}-{:?}", safe_upload_request_id, file_type);
+}
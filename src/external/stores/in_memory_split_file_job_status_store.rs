@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::internal::{
+    interfaces::split_file_job_status_store::SplitFileJobStatusStoreInterface,
+    models::entities::split_file_job::{SplitFileJobPhase, SplitFileJobStatus},
+};
+
+const DEFAULT_FINISHED_JOB_STATUS_TIME_TO_LIVE: Duration = Duration::from_secs(60 * 60);
+const DEFAULT_MAX_FINISHED_JOB_STATUSES: usize = 10_000;
+
+//keeps job statuses in memory. clones share the same statuses
+//so a single store can be handed to every http worker and the background worker
+#[derive(Clone)]
+pub struct InMemorySplitFileJobStatusStore {
+    job_statuses: Arc<RwLock<HashMap<String, StoredJobStatus>>>,
+
+    //completed and failed jobs are forgotten once they have been finished for this long
+    finished_job_status_time_to_live: Duration,
+
+    //the oldest finished jobs are forgotten once more than this many are kept
+    max_finished_job_statuses: usize,
+}
+
+#[derive(Clone)]
+struct StoredJobStatus {
+    job_status: SplitFileJobStatus,
+    saved_at: Instant,
+}
+
+impl StoredJobStatus {
+    fn is_finished(&self) -> bool {
+        return matches!(self.job_status.phase, SplitFileJobPhase::Completed | SplitFileJobPhase::Failed);
+    }
+}
+
+impl Default for InMemorySplitFileJobStatusStore {
+    fn default() -> Self {
+        return InMemorySplitFileJobStatusStore::new(DEFAULT_FINISHED_JOB_STATUS_TIME_TO_LIVE, DEFAULT_MAX_FINISHED_JOB_STATUSES);
+    }
+}
+
+impl SplitFileJobStatusStoreInterface for InMemorySplitFileJobStatusStore {
+    fn save_job_status(&self, job_status: &SplitFileJobStatus) {
+        if let Ok(mut job_statuses) = self.job_statuses.write() {
+            let stored = StoredJobStatus {
+                job_status: job_status.clone(),
+                saved_at: Instant::now(),
+            };
+            let is_finished = stored.is_finished();
+            job_statuses.insert(job_status.upload_request_id.clone(), stored);
+
+            //progress updates of running jobs are saved for every chunk, so the
+            //statuses are only scanned when a job finishes or the map outgrows its limit
+            if is_finished || job_statuses.len() > self.max_finished_job_statuses {
+                self.evict_finished_job_statuses(&mut job_statuses);
+            }
+        }
+    }
+
+    fn get_job_status(&self, upload_request_id: &str) -> Option<SplitFileJobStatus> {
+        return match self.job_statuses.read() {
+            Ok(job_statuses) => job_statuses
+                .get(upload_request_id)
+                .filter(|stored| !(stored.is_finished() && stored.saved_at.elapsed() > self.finished_job_status_time_to_live))
+                .map(|stored| stored.job_status.clone()),
+            Err(_) => None,
+        };
+    }
+}
+
+impl InMemorySplitFileJobStatusStore {
+    pub fn new(finished_job_status_time_to_live: Duration, max_finished_job_statuses: usize) -> Self {
+        return InMemorySplitFileJobStatusStore {
+            job_statuses: Arc::new(RwLock::new(HashMap::new())),
+            finished_job_status_time_to_live,
+            max_finished_job_statuses,
+        };
+    }
+
+    //jobs that are still queued or running are never evicted, their callers are still waiting on them
+    fn evict_finished_job_statuses(&self, job_statuses: &mut HashMap<String, StoredJobStatus>) {
+        job_statuses.retain(|_, stored| !(stored.is_finished() && stored.saved_at.elapsed() > self.finished_job_status_time_to_live));
+
+        let mut finished_job_statuses: Vec<(String, Instant)> = job_statuses
+            .iter()
+            .filter(|(_, stored)| stored.is_finished())
+            .map(|(upload_request_id, stored)| (upload_request_id.clone(), stored.saved_at))
+            .collect();
+
+        if finished_job_statuses.len() <= self.max_finished_job_statuses {
+            return;
+        }
+
+        finished_job_statuses.sort_by_key(|(_, saved_at)| *saved_at);
+        let number_to_evict = finished_job_statuses.len() - self.max_finished_job_statuses;
+
+        for (upload_request_id, _) in finished_job_statuses.into_iter().take(number_to_evict) {
+            job_statuses.remove(&upload_request_id);
+        }
+    }
+}
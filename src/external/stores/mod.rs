@@ -0,0 +1,7 @@
+pub mod dapr_split_file_checkpoint_store;
+pub mod file_split_file_checkpoint_store;
+pub mod in_memory_split_file_job_status_store;
+
+#[cfg(test)]
+#[path = "./stores_test.rs"]
+mod stores_test;
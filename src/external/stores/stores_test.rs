@@ -0,0 +1,92 @@
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use crate::external::stores::file_split_file_checkpoint_store::{get_checkpoint_key, FileSplitFileCheckpointStore};
+use crate::external::stores::in_memory_split_file_job_status_store::InMemorySplitFileJobStatusStore;
+use crate::internal::interfaces::split_file_checkpoint_store::SplitFileCheckpointStoreInterface;
+use crate::internal::interfaces::split_file_job_status_store::SplitFileJobStatusStoreInterface;
+use crate::internal::models::entities::split_file_checkpoint::SplitFileCheckpoint;
+use crate::internal::models::entities::split_file_job::{SplitFileJobPhase, SplitFileJobStatus};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::ReconFileType;
+
+fn get_dummy_checkpoint() -> SplitFileCheckpoint {
+    SplitFileCheckpoint::new(
+        String::from("RECON-TASK-1234"),
+        String::from("user@example.com"),
+        ReconFileType::PrimaryFile,
+        String::from("FINGERPRINT-1234"),
+    )
+}
+
+#[test]
+fn test_checkpoint_only_moves_past_a_chunk_once_every_chunk_before_it_is_acknowledged() {
+    let mut checkpoint = get_dummy_checkpoint();
+
+    checkpoint.acknowledge_chunk(2);
+    checkpoint.acknowledge_chunk(3);
+    assert_eq!(checkpoint.last_acknowledged_chunk_sequence_number, 0);
+    assert!(checkpoint.is_chunk_acknowledged(3));
+    assert!(!checkpoint.is_chunk_acknowledged(1));
+
+    checkpoint.acknowledge_chunk(1);
+    assert_eq!(checkpoint.last_acknowledged_chunk_sequence_number, 3);
+    assert_eq!(checkpoint.chunks_acknowledged_out_of_order, BTreeSet::new());
+}
+
+#[test]
+fn test_file_checkpoint_store_saves_and_gets_checkpoints_by_upload_request_id_and_file_type() {
+    let directory = std::env::temp_dir().join(format!("checkpoints-{}", uuid::Uuid::new_v4()));
+    let store = FileSplitFileCheckpointStore::new(directory.to_string_lossy().to_string());
+
+    let mut checkpoint = get_dummy_checkpoint();
+    checkpoint.acknowledge_chunk(1);
+    checkpoint.acknowledge_chunk(3);
+
+    tokio_test::block_on(store.save_checkpoint(&checkpoint)).unwrap();
+
+    let saved_checkpoint = tokio_test::block_on(store.get_checkpoint("RECON-TASK-1234", &ReconFileType::PrimaryFile)).unwrap();
+    let other_file_type_checkpoint = tokio_test::block_on(store.get_checkpoint("RECON-TASK-1234", &ReconFileType::ComparisonFile)).unwrap();
+
+    assert_eq!(saved_checkpoint, Some(checkpoint));
+    assert_eq!(other_file_type_checkpoint, None);
+
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn test_checkpoint_keys_only_contain_characters_safe_in_file_names_and_urls() {
+    assert_eq!(get_checkpoint_key("../RECON TASK/1234", &ReconFileType::ComparisonFile), "___RECON_TASK_1234-ComparisonFile");
+}
+
+fn get_dummy_job_status(upload_request_id: &str, phase: SplitFileJobPhase) -> SplitFileJobStatus {
+    let mut job_status = SplitFileJobStatus::new(String::from(upload_request_id), String::from("user@example.com"));
+    job_status.phase = phase;
+    job_status
+}
+
+#[test]
+fn test_job_status_store_forgets_the_oldest_finished_jobs_once_it_holds_too_many() {
+    let store = InMemorySplitFileJobStatusStore::new(Duration::from_secs(60), 2);
+
+    store.save_job_status(&get_dummy_job_status("RECON-TASK-1", SplitFileJobPhase::Completed));
+    store.save_job_status(&get_dummy_job_status("RECON-TASK-2", SplitFileJobPhase::Queued));
+    store.save_job_status(&get_dummy_job_status("RECON-TASK-3", SplitFileJobPhase::Failed));
+    store.save_job_status(&get_dummy_job_status("RECON-TASK-4", SplitFileJobPhase::Completed));
+
+    assert_eq!(store.get_job_status("RECON-TASK-1"), None);
+    assert_eq!(store.get_job_status("RECON-TASK-2"), Some(get_dummy_job_status("RECON-TASK-2", SplitFileJobPhase::Queued)));
+    assert_eq!(store.get_job_status("RECON-TASK-3"), Some(get_dummy_job_status("RECON-TASK-3", SplitFileJobPhase::Failed)));
+    assert_eq!(store.get_job_status("RECON-TASK-4"), Some(get_dummy_job_status("RECON-TASK-4", SplitFileJobPhase::Completed)));
+}
+
+#[test]
+fn test_job_status_store_forgets_finished_jobs_after_their_time_to_live_but_keeps_running_ones() {
+    let store = InMemorySplitFileJobStatusStore::new(Duration::from_millis(10), 100);
+
+    store.save_job_status(&get_dummy_job_status("RECON-TASK-1", SplitFileJobPhase::Completed));
+    store.save_job_status(&get_dummy_job_status("RECON-TASK-2", SplitFileJobPhase::Uploading));
+    std::thread::sleep(Duration::from_millis(20));
+
+    assert_eq!(store.get_job_status("RECON-TASK-1"), None);
+    assert_eq!(store.get_job_status("RECON-TASK-2"), Some(get_dummy_job_status("RECON-TASK-2", SplitFileJobPhase::Uploading)));
+}
@@ -0,0 +1,12 @@
+use std::collections::HashMap;
+
+use mockall::automock;
+
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppError;
+
+#[automock]
+pub trait CallerAuthenticatorInterface: Send + Sync {
+    //works out the user_id of the caller from the headers of its request.
+    //header names are expected in lowercase
+    fn authenticate_caller(&self, request_headers: &HashMap<String, String>) -> Result<String, AppError>;
+}
@@ -1,12 +1,33 @@
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::models::entities::file_row::FileRow;
+use crate::internal::models::entities::inferred_file_settings::InferredFileSettings;
+use crate::internal::models::entities::statement_summary::StatementSummary;
 use crate::internal::shared_reconciler_rust_libraries::models::entities::{
     app_errors::AppError,
-    file::{File, FileThatHasBeenRead},
+    file::File,
 };
 use async_trait::async_trait;
 use mockall::automock;
 
+//the records in a file, read one at a time as the stream is consumed
+pub type FileRowStream = Box<dyn Iterator<Item = Result<FileRow, AppError>> + Send>;
+
 #[automock]
 #[async_trait]
 pub trait FileReader: Send + Sync {
-    async fn read_file(&self, file: &File) -> Result<FileThatHasBeenRead, AppError>;
+    //looks at the start of the file to work out the settings that the read options leave out.
+    //formats that describe their own layout have nothing to infer and return none
+    async fn infer_file_settings(&self, file: &File, read_options: &FileReadOptions) -> Result<Option<InferredFileSettings>, AppError>;
+
+    //reads the balances a bank statement file reports alongside its transactions.
+    //files that are not bank statements return none
+    async fn read_statement_summary(&self, file: &File, read_options: &FileReadOptions) -> Result<Option<StatementSummary>, AppError>;
+
+    async fn read_column_headers(&self, file: &File, read_options: &FileReadOptions) -> Result<Vec<String>, AppError>;
+
+    //counts the records in a file without keeping them
+    async fn count_file_rows(&self, file: &File, read_options: &FileReadOptions) -> Result<u64, AppError>;
+
+    //opens a stream over the records in a file, the column headers are not part of the stream
+    async fn stream_file_rows(&self, file: &File, read_options: &FileReadOptions) -> Result<FileRowStream, AppError>;
 }
@@ -1,3 +1,5 @@
+use crate::internal::models::entities::file_storage::FileStorage;
+use crate::internal::models::entities::retrieved_file::RetrievedFile;
 use crate::internal::shared_reconciler_rust_libraries::models::entities::{
     app_errors::AppError, file::File,
 };
@@ -7,5 +9,10 @@ use mockall::automock;
 #[automock]
 #[async_trait]
 pub trait FileRetriever: Send + Sync {
-    async fn retrieve_file(&self, file: File) -> Result<File, AppError>;
+    //makes the file available on the local disk and returns a copy of it whose file_path points at the local copy,
+    //along with a hash of its contents worked out as it was read
+    async fn retrieve_file(&self, file: File, file_storage: FileStorage) -> Result<RetrievedFile, AppError>;
+
+    //removes any local copy made by retrieve_file once it is no longer needed
+    async fn release_file(&self, retrieved_file: &File) -> Result<(), AppError>;
 }
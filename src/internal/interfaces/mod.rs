@@ -1,5 +1,9 @@
+pub mod caller_authenticator;
 pub mod file_reader;
 pub mod file_retriever;
+pub mod split_file_checkpoint_store;
+pub mod split_file_job_queue;
+pub mod split_file_job_status_store;
 pub mod split_file_service;
 pub mod file_chunks_upload_service_connector;
 pub mod recon_tasks_service_connector;
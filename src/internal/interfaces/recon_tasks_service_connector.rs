@@ -1,15 +1,20 @@
 use async_trait::async_trait;
 use mockall::automock;
 
-use crate::internal::shared_reconciler_rust_libraries::models::entities::{
-    app_errors::AppError,
-    file::FileThatHasBeenRead,
-};
+use crate::internal::models::entities::file::FileThatHasBeenRead;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppError;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::{ComparisonPair, ReconciliationConfigs};
 
 #[automock]
 #[async_trait]
 pub trait ReconTasksServiceConnectorInterface: Send + Sync {
-    async fn create_recon_task(&self, file: &FileThatHasBeenRead) -> Result<String, AppError>;
+    //creates a recon task owned by the user with the given user_id
+    async fn create_recon_task(
+        &self,
+        file: &FileThatHasBeenRead,
+        user_id: &str,
+        recon_configurations: &ReconciliationConfigs,
+    ) -> Result<String, AppError>;
 
     async fn attach_primary_file_to_task(&self, file: &FileThatHasBeenRead)
                                          -> Result<String, AppError>;
@@ -18,4 +23,10 @@ pub trait ReconTasksServiceConnectorInterface: Send + Sync {
         &self,
         file: &FileThatHasBeenRead,
     ) -> Result<String, AppError>;
+
+    //the id of a recon task that has not finished yet and already has a file with this hash attached, if there is one
+    async fn find_active_recon_task_with_file(&self, file_hash: &str) -> Result<Option<String>, AppError>;
+
+    //the comparison pairs the recon task with this id was created with
+    async fn get_recon_task_comparison_pairs(&self, recon_task_id: &str) -> Result<Vec<ComparisonPair>, AppError>;
 }
@@ -0,0 +1,22 @@
+use async_trait::async_trait;
+use mockall::automock;
+
+use crate::internal::models::entities::split_file_checkpoint::SplitFileCheckpoint;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::{
+    app_errors::AppError,
+    recon_tasks_models::ReconFileType,
+};
+
+#[automock]
+#[async_trait]
+pub trait SplitFileCheckpointStoreInterface: Send + Sync {
+    async fn save_checkpoint(&self, checkpoint: &SplitFileCheckpoint) -> Result<(), AppError>;
+
+    //the primary and comparison files of a recon task share an upload_request_id,
+    //so checkpoints are looked up by both
+    async fn get_checkpoint(
+        &self,
+        upload_request_id: &str,
+        file_type: &ReconFileType,
+    ) -> Result<Option<SplitFileCheckpoint>, AppError>;
+}
@@ -0,0 +1,9 @@
+use mockall::automock;
+
+use crate::internal::models::entities::{app_error_reason::AppErrorWithReason, split_file_job::SplitFileJob};
+
+#[automock]
+pub trait SplitFileJobQueueInterface: Send + Sync {
+    //hands the job over to be run in the background
+    fn queue_job(&self, job: SplitFileJob) -> Result<(), AppErrorWithReason>;
+}
@@ -0,0 +1,10 @@
+use mockall::automock;
+
+use crate::internal::models::entities::split_file_job::SplitFileJobStatus;
+
+#[automock]
+pub trait SplitFileJobStatusStoreInterface: Send + Sync {
+    fn save_job_status(&self, job_status: &SplitFileJobStatus);
+
+    fn get_job_status(&self, upload_request_id: &str) -> Option<SplitFileJobStatus>;
+}
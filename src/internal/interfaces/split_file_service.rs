@@ -1,7 +1,13 @@
 use crate::internal::{
-    models::view_models::{
-        requests::split_file_request::SplitFileRequest,
-        responses::split_file_response::SplitFileResponse,
+    models::{
+        entities::{
+            app_error_reason::AppErrorWithReason,
+            split_file_job::{SplitFileJob, SplitFileJobStatus},
+        },
+        view_models::{
+            requests::split_file_request::SplitFileRequest,
+            responses::split_file_response::SplitFileResponse,
+        },
     },
     shared_reconciler_rust_libraries::models::entities::app_errors::AppError,
 };
@@ -15,4 +21,26 @@ pub trait SplitFileServiceInterface: Send + Sync {
         &self,
         file: SplitFileRequest,
     ) -> Result<SplitFileResponse, AppError>;
+
+    //creates the recon task for the file and leaves reading
+    //and uploading its rows to a background worker
+    async fn queue_file_for_splitting(
+        &self,
+        file: SplitFileRequest,
+    ) -> Result<SplitFileResponse, AppErrorWithReason>;
+
+    //re-reads a file whose split was interrupted and uploads the chunks that were not acknowledged
+    async fn resume_splitting_file(
+        &self,
+        upload_request_id: String,
+        file: SplitFileRequest,
+    ) -> Result<SplitFileResponse, AppErrorWithReason>;
+
+    async fn run_split_file_job(&self, job: SplitFileJob) -> Result<(), AppError>;
+
+    async fn get_split_file_job_status(
+        &self,
+        upload_request_id: String,
+        user_id: String,
+    ) -> Result<SplitFileJobStatus, AppError>;
 }
@@ -1,13 +1,19 @@
 use mockall::automock;
 
-use crate::internal::shared_reconciler_rust_libraries::models::entities::file::FileThatHasBeenRead;
+use crate::internal::interfaces::file_reader::FileRowStream;
+use crate::internal::models::entities::file::FileThatHasBeenRead;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppError;
 use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::view_models::requests::UploadFileChunkRequest;
 
+//file chunks, each put together only once the previous one has been taken from the stream
+pub type FileChunkStream = Box<dyn Iterator<Item = Result<UploadFileChunkRequest, AppError>> + Send>;
+
 #[automock]
 pub trait TransformerInterface: Send + Sync {
     fn group_rows_into_file_chunks(
         &self,
         file_that_has_been_read: &FileThatHasBeenRead,
+        file_rows: FileRowStream,
         max_group_size: i64,
-    ) -> Vec<UploadFileChunkRequest>;
+    ) -> FileChunkStream;
 }
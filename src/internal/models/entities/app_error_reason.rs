@@ -0,0 +1,48 @@
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{AppError, AppErrorKind};
+
+//reasons for failing that the shared AppErrorKind has no kind of its own for.
+//the web api picks the status code that fits the reason an error is returned with
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum AppErrorReason {
+    //the background worker has more jobs waiting than it accepts
+    JobQueueFull,
+
+    //every chunk of the file was already acknowledged, so there is nothing left to resume
+    SplitAlreadyCompleted,
+}
+
+//an AppError, and the reason it was returned for when that reason decides how the error is reported
+#[derive(Clone, PartialEq, Debug)]
+pub struct AppErrorWithReason {
+    pub error: AppError,
+    pub reason: Option<AppErrorReason>,
+}
+
+impl AppErrorReason {
+    pub fn get_message(&self) -> &'static str {
+        return match self {
+            AppErrorReason::JobQueueFull => "the queue of files waiting to be split is full, please try again later",
+            AppErrorReason::SplitAlreadyCompleted => "the split has already been completed",
+        };
+    }
+
+    //an error for this reason, with any details about it after the reason's message
+    pub fn app_error<T>(&self, kind: AppErrorKind, details: Option<&str>) -> Result<T, AppErrorWithReason> {
+        let message = match details {
+            None => self.get_message().to_string(),
+            Some(details) => format!("{}: {}", self.get_message(), details),
+        };
+
+        return Err(AppErrorWithReason {
+            error: AppError::new(kind, message),
+            reason: Some(*self),
+        });
+    }
+}
+
+//an error without a reason of its own is reported by its kind
+impl From<AppError> for AppErrorWithReason {
+    fn from(error: AppError) -> Self {
+        return AppErrorWithReason { error, reason: None };
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+use crate::internal::models::entities::file_row::CellValue;
+
+//how each row is written into the raw_data of the chunk rows sent to the file chunks upload service
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub enum ChunkPayloadFormat {
+    //the row's original text, split into cells again downstream using the delimiters sent when the file is attached
+    #[default]
+    RawDataV1,
+
+    //a StructuredChunkRow written as json, so rows from formats that have no delimited text can be reconciled too
+    StructuredCellsV2,
+}
+
+impl ChunkPayloadFormat {
+    pub fn from_version(payload_version: u32) -> Option<ChunkPayloadFormat> {
+        return match payload_version {
+            1 => Some(ChunkPayloadFormat::RawDataV1),
+            2 => Some(ChunkPayloadFormat::StructuredCellsV2),
+            _ => None,
+        };
+    }
+
+    pub fn get_version(&self) -> u32 {
+        return match self {
+            ChunkPayloadFormat::RawDataV1 => 1,
+            ChunkPayloadFormat::StructuredCellsV2 => 2,
+        };
+    }
+}
+
+//a row that has already been split into cells. the payload version is written
+//into every row so the service reading the chunks can tell which format it is in
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct StructuredChunkRow {
+    pub payload_version: u32,
+    pub cells: Vec<String>,
+
+    //the typed value of each cell, only sent by readers that know them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub values: Option<Vec<CellValue>>,
+}
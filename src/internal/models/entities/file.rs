@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file::{File, FileMetadata};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::{ComparisonPair, ReconFileType};
+
+//describes a file that has been read, its records
+//are streamed separately rather than being kept here
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct FileThatHasBeenRead {
+    pub id: Option<String>,
+    pub upload_request_id: Option<String>,
+    pub file_type: ReconFileType,
+    pub column_headers: Vec<String>,
+    pub row_count: u64,
+    pub file_metadata: Option<FileMetadata>,
+
+    //hex encoded sha256 of the file's contents
+    pub file_hash: String,
+}
+
+impl FileThatHasBeenRead {
+    //the records in the file are counted separately
+    //so the row count starts off at zero
+    pub fn new(file: &File, column_headers: Vec<String>, file_hash: String) -> FileThatHasBeenRead {
+        return FileThatHasBeenRead {
+            id: file.id.clone(),
+            upload_request_id: file.upload_request_id.clone(),
+            file_type: file.file_type.clone(),
+            column_headers,
+            row_count: 0,
+            file_metadata: file.file_metadata.clone(),
+            file_hash,
+        };
+    }
+
+    pub fn get_comparison_pairs(&self) -> Vec<ComparisonPair> {
+        return match &self.file_metadata {
+            Some(FileMetadata { comparison_pairs: Some(comparison_pairs), .. }) => comparison_pairs.clone(),
+            _ => vec![],
+        };
+    }
+
+    //the comparison pairs whose column for this file's type is not one of its column headers
+    pub fn get_invalid_comparison_pairs(&self, comparison_pairs: &[ComparisonPair]) -> Vec<ComparisonPair> {
+        return comparison_pairs
+            .iter()
+            .filter(|comparison_pair| {
+                let column_index = match self.file_type {
+                    ReconFileType::PrimaryFile => comparison_pair.primary_file_column_index,
+                    ReconFileType::ComparisonFile => comparison_pair.comparison_file_column_index,
+                };
+                column_index >= self.column_headers.len()
+            })
+            .cloned()
+            .collect();
+    }
+}
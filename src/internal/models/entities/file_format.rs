@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file::{File, SupportedFileExtension};
+
+//the format a file is read as. the file extension decides it unless the read options name a format,
+//which is how formats that the file extensions do not cover are selected
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FileFormat {
+    Csv,
+    Excel,
+    Pdf,
+    FixedWidth,
+    Json,
+
+    //newline delimited json, one json value per line
+    Ndjson,
+
+    //swift mt940 customer statements, and the mt942 interim statements laid out the same way
+    #[serde(alias = "mt942")]
+    Mt940,
+
+    //iso 20022 bank to customer statements (camt.053) and debit/credit notifications (camt.054)
+    #[serde(alias = "camt053", alias = "camt054")]
+    Camt,
+
+    //bai2 cash management balance reports
+    Bai2,
+
+    //ofx bank and credit card statements, in the sgml (1.x) or xml (2.x) flavour. qfx files are ofx files
+    #[serde(alias = "qfx")]
+    Ofx,
+
+    //nacha ach files of fixed-width file header, batch, entry detail, addenda and control records
+    #[serde(alias = "ach")]
+    Nacha,
+}
+
+impl FileFormat {
+    pub fn from_file(file: &File, read_options: &FileReadOptions) -> FileFormat {
+        if let Some(format) = read_options.format {
+            return format;
+        }
+
+        return match file.file_extension {
+            SupportedFileExtension::Csv => FileFormat::Csv,
+            SupportedFileExtension::Excel => FileFormat::Excel,
+            SupportedFileExtension::Pdf => FileFormat::Pdf,
+        };
+    }
+}
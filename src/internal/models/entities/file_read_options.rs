@@ -0,0 +1,148 @@
+use serde::{Deserialize, Serialize};
+
+use crate::internal::models::entities::file_format::FileFormat;
+
+//format specific settings that tell a reader
+//how to find the records inside a file.
+//csv settings that are left out are worked out by looking at the start of the file
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct FileReadOptions {
+    //the format to read the file as, instead of the one its file extension implies
+    pub format: Option<FileFormat>,
+
+    pub csv: Option<CsvReadOptions>,
+    pub excel: Option<ExcelReadOptions>,
+    pub pdf: Option<PdfReadOptions>,
+    pub fixed_width: Option<FixedWidthReadOptions>,
+    pub json: Option<JsonReadOptions>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct CsvReadOptions {
+    //delimiters that can each be more than one character long, e.g. "||" or "~|~".
+    //any one of them ends a cell, and they take the place of the file metadata's column_delimiters
+    pub column_delimiters: Option<Vec<String>>,
+
+    //a regex that matches the text between two cells, e.g. "\\s*;\\s*".
+    //cannot be supplied together with column_delimiters
+    pub column_delimiter_pattern: Option<String>,
+
+    //the character wrapped around cells that contain delimiters or line breaks
+    pub quote_character: Option<char>,
+
+    //whether the first record holds the column headers. if it does not, the columns are named column_1, column_2...
+    pub has_header_row: Option<bool>,
+
+    //the encoding the file was saved in: utf-8, utf-16le, utf-16be, windows-1252 or iso-8859-1.
+    //it is detected from the start of the file if it is not supplied
+    pub encoding: Option<String>,
+}
+
+impl CsvReadOptions {
+    //the delimiters, if every one of them is a single character
+    pub fn get_single_character_column_delimiters(&self) -> Option<Vec<char>> {
+        let column_delimiters = self.column_delimiters.as_ref()?;
+
+        return column_delimiters
+            .iter()
+            .map(|delimiter| {
+                let mut characters = delimiter.chars();
+                match (characters.next(), characters.next()) {
+                    (Some(character), None) => Some(character),
+                    _ => None,
+                }
+            })
+            .collect();
+    }
+
+    //rows of raw text are split again downstream using the single character delimiters sent when the
+    //file is attached, so longer delimiters and patterns only work with rows already split into cells
+    pub fn is_structured_chunk_payload_required(&self) -> bool {
+        return self.column_delimiter_pattern.is_some()
+            || (self.column_delimiters.is_some() && self.get_single_character_column_delimiters().is_none());
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct ExcelReadOptions {
+    //the sheet to read, looked up by name first and then by its zero based index.
+    //the first sheet in the workbook is read if neither is supplied
+    pub sheet_name: Option<String>,
+    pub sheet_index: Option<usize>,
+
+    //zero based index of the header row within the sheet's used range.
+    //the header row is detected automatically if this is not supplied
+    pub header_row_index: Option<usize>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct PdfReadOptions {
+    //a regex matched against every line of page text.
+    //each line that matches becomes a row made up of the regex's capture groups,
+    //and the names of the capture groups become the column headers
+    pub row_pattern: Option<String>,
+
+    //the character positions at which each column starts in a line of page text,
+    //used for statements whose columns are laid out at fixed positions
+    pub column_start_positions: Option<Vec<usize>>,
+
+    //column headers to use for a document that does not print them. no header line is looked for,
+    //so every line of the table is a row
+    pub column_headers: Option<Vec<String>>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct FixedWidthReadOptions {
+    //where each column sits in a record, in the order the columns are read
+    pub columns: Vec<FixedWidthColumn>,
+
+    //how many records at the start and end of the file are headers and trailers rather than rows
+    pub header_record_count: Option<usize>,
+    pub trailer_record_count: Option<usize>,
+
+    //records that start with any of these, e.g. a record type code like "HDR" or "TRL", are skipped
+    pub skip_record_prefixes: Option<Vec<String>>,
+
+    //the encoding the file was saved in, detected from the start of the file if it is not supplied
+    pub encoding: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct FixedWidthColumn {
+    //the column header
+    pub name: String,
+
+    //zero based character position at which the column starts, and how many characters it takes up
+    pub start: usize,
+    pub length: usize,
+
+    //which side of the value the padding is trimmed from, both sides if this is not supplied
+    pub trim: Option<FixedWidthTrim>,
+
+    //the character the value is padded with, e.g. '0' for zero padded amounts. spaces if this is not supplied
+    pub pad_character: Option<char>,
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FixedWidthTrim {
+    None,
+    Start,
+    End,
+    Both,
+}
+
+//settings for both json and newline delimited json files
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct JsonReadOptions {
+    //a JSONPath-style path to the records, e.g. "$.data.transactions[*]".
+    //the whole document, or for ndjson the whole of each line, if this is not supplied
+    pub records_path: Option<String>,
+
+    //the dotted names of the fields that become the columns, e.g. "customer.name".
+    //every field found in the records is a column if this is not supplied
+    pub fields: Option<Vec<String>>,
+
+    //the encoding the file was saved in, detected from the start of the file if it is not supplied
+    pub encoding: Option<String>,
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::internal::models::entities::chunk_payload::{ChunkPayloadFormat, StructuredChunkRow};
+use crate::internal::shared_reconciler_rust_libraries::common::utils::app_error;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{AppError, AppErrorKind};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file_row::FileRow as ChunkFileRow;
+
+//a single record read from a file, keeping both the
+//original text of the record and the cells parsed out of it
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct FileRow {
+    pub raw_data: String,
+    pub row_number: u64,
+    pub cells: Vec<String>,
+
+    //the typed value of each cell, for formats whose cells have types.
+    //none for formats such as csv where every cell is text
+    pub values: Option<Vec<CellValue>>,
+}
+
+//the value of a cell as the file itself typed it
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(tag = "type", content = "value")]
+pub enum CellValue {
+    Empty,
+    Text(String),
+    Integer(i64),
+    Decimal(f64),
+    Boolean(bool),
+
+    //dates, times and durations are written the same way as the cell's text
+    Date(String),
+    DateTime(String),
+    Duration(String),
+}
+
+impl FileRow {
+    //builds a row for formats that have no delimited text of their own,
+    //joining the cells with the delimiter and quoting any cell that needs it
+    pub fn from_cells(row_number: u64, cells: Vec<String>, column_delimiter: char) -> FileRow {
+        let raw_data = cells
+            .iter()
+            .map(|cell| Self::quote_cell_if_required(cell, column_delimiter))
+            .collect::<Vec<String>>()
+            .join(&column_delimiter.to_string());
+
+        return FileRow {
+            raw_data,
+            row_number,
+            cells,
+            values: None,
+        };
+    }
+
+    pub fn with_values(mut self, values: Vec<CellValue>) -> FileRow {
+        self.values = Some(values);
+        return self;
+    }
+
+    /**
+    the row as it is sent to the file chunks upload service, written in the given payload format
+
+    # Errors
+
+    This function will return an error if the row cannot be written as json
+     */
+    pub fn to_chunk_row(&self, chunk_payload_format: ChunkPayloadFormat) -> Result<ChunkFileRow, AppError> {
+        let raw_data = match chunk_payload_format {
+            ChunkPayloadFormat::RawDataV1 => self.raw_data.clone(),
+            ChunkPayloadFormat::StructuredCellsV2 => {
+                let structured_row = StructuredChunkRow {
+                    payload_version: chunk_payload_format.get_version(),
+                    cells: self.cells.clone(),
+                    values: self.values.clone(),
+                };
+                match serde_json::to_string(&structured_row) {
+                    Ok(raw_data) => raw_data,
+                    Err(e) => { return app_error(AppErrorKind::InternalError, Box::new(e)); }
+                }
+            }
+        };
+
+        return Ok(ChunkFileRow {
+            raw_data,
+            row_number: self.row_number,
+        });
+    }
+
+    fn quote_cell_if_required(cell: &str, column_delimiter: char) -> String {
+        let is_quoting_required = cell.contains(column_delimiter)
+            || cell.contains('"')
+            || cell.contains('\n')
+            || cell.contains('\r');
+
+        if !is_quoting_required {
+            return cell.to_string();
+        }
+
+        return format!("\"{}\"", cell.replace('"', "\"\""));
+    }
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file::{File, FileStorageLocation};
+
+//where a file is retrieved from. the file's storage location decides it unless the request names the storage,
+//which is how storage that the file storage locations do not cover is selected
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum FileStorage {
+    LocalFileSystem,
+
+    //an http(s) url
+    Http,
+
+    //an s3://{bucket}/{key} path in the configured S3 compatible object store
+    S3,
+}
+
+impl FileStorage {
+    pub fn from_file(file: &File, requested_storage: Option<FileStorage>) -> FileStorage {
+        if let Some(storage) = requested_storage {
+            return storage;
+        }
+
+        return match file.file_storage_location {
+            FileStorageLocation::LocalFileSystem => FileStorage::LocalFileSystem,
+        };
+    }
+}
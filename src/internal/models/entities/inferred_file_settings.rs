@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+//how a file was assumed to be laid out when the request did not say.
+//only the settings that were worked out by looking at the file are filled in
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct InferredFileSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column_delimiter: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quote_character: Option<char>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_header_row: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encoding: Option<String>,
+}
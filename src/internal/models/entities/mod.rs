@@ -0,0 +1,14 @@
+pub mod app_error_reason;
+pub mod chunk_payload;
+pub mod file;
+pub mod file_format;
+pub mod file_read_options;
+pub mod file_storage;
+pub mod file_row;
+pub mod inferred_file_settings;
+pub mod named_comparison_pair;
+pub mod reconciliation_options;
+pub mod retrieved_file;
+pub mod split_file_checkpoint;
+pub mod split_file_job;
+pub mod statement_summary;
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::{ComparisonPair, ReconFileType};
+
+//a comparison pair whose columns can be given by header name instead of by position
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct NamedComparisonPair {
+    pub primary_file_column: ColumnReference,
+    pub comparison_file_column: ColumnReference,
+    pub is_row_identifier: bool,
+}
+
+//either the zero based index of a column or the name of its header.
+//names are matched ignoring case and whitespace, and a column can also be found by any of its aliases
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(untagged)]
+pub enum ColumnReference {
+    Index(usize),
+    Name {
+        name: String,
+        #[serde(default)]
+        aliases: Vec<String>,
+    },
+}
+
+impl NamedComparisonPair {
+    /**
+    turns the pair into a comparison pair made up of column indexes.
+    only the column of the file that has been read can be looked up by name,
+    since the headers of the other file are not known until it is read too
+
+    # Errors
+
+    returns a description of the problem if a name matches no column header, more than one
+    column header, or belongs to the other file
+     */
+    pub fn resolve(&self, file_type: &ReconFileType, column_headers: &[String]) -> Result<ComparisonPair, String> {
+        let primary_file_column_index = self.primary_file_column.resolve(
+            &ReconFileType::PrimaryFile,
+            file_type,
+            column_headers,
+        )?;
+        let comparison_file_column_index = self.comparison_file_column.resolve(
+            &ReconFileType::ComparisonFile,
+            file_type,
+            column_headers,
+        )?;
+
+        return Ok(ComparisonPair {
+            primary_file_column_index,
+            comparison_file_column_index,
+            is_row_identifier: self.is_row_identifier,
+        });
+    }
+}
+
+impl ColumnReference {
+    fn resolve(
+        &self,
+        column_file_type: &ReconFileType,
+        file_type: &ReconFileType,
+        column_headers: &[String],
+    ) -> Result<usize, String> {
+        let (name, aliases) = match self {
+            ColumnReference::Index(column_index) => return Ok(*column_index),
+            ColumnReference::Name { name, aliases } => (name, aliases),
+        };
+
+        if column_file_type != file_type {
+            return Err(format!(
+                "the {:?} column {:?} can only be looked up by name when the {:?} is read, please supply its column index instead",
+                column_file_type, name, column_file_type
+            ));
+        }
+
+        let names_to_match: Vec<String> = std::iter::once(name)
+            .chain(aliases.iter())
+            .map(|name_to_match| ColumnReference::normalize(name_to_match))
+            .collect();
+
+        let matching_column_indexes: Vec<usize> = column_headers
+            .iter()
+            .enumerate()
+            .filter(|(_, column_header)| names_to_match.contains(&ColumnReference::normalize(column_header)))
+            .map(|(column_index, _)| column_index)
+            .collect();
+
+        return match matching_column_indexes.as_slice() {
+            [column_index] => Ok(*column_index),
+            [] => Err(format!(
+                "no {:?} column header matches {:?} or its aliases {:?}, the column headers are {:?}",
+                column_file_type, name, aliases, column_headers
+            )),
+            _ => Err(format!(
+                "{:?} and its aliases {:?} match more than one {:?} column header: {:?}",
+                name,
+                aliases,
+                column_file_type,
+                matching_column_indexes.iter().map(|column_index| &column_headers[*column_index]).collect::<Vec<_>>()
+            )),
+        };
+    }
+
+    fn normalize(column_name: &str) -> String {
+        return column_name.split_whitespace().collect::<String>().to_lowercase();
+    }
+}
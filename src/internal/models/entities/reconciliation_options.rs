@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+use validator::{Validate, ValidationError};
+
+use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::ReconciliationConfigs;
+
+//reconciliation settings supplied by the caller when creating a new recon task.
+//any setting that is left out falls back to the server's default for it, while a
+//misspelt setting is rejected rather than quietly falling back to its default
+#[derive(Clone, PartialEq, Serialize, Deserialize, Validate, Debug, Default)]
+#[serde(deny_unknown_fields)]
+#[validate(schema(function = "validate_reconciliation_options"))]
+pub struct ReconciliationOptions {
+    pub should_check_for_duplicate_records_in_comparison_file: Option<bool>,
+    pub should_reconciliation_be_case_sensitive: Option<bool>,
+    pub should_ignore_white_space: Option<bool>,
+    pub should_do_reverse_reconciliation: Option<bool>,
+}
+
+impl ReconciliationOptions {
+    pub fn is_empty(&self) -> bool {
+        return self.should_check_for_duplicate_records_in_comparison_file.is_none()
+            && self.should_reconciliation_be_case_sensitive.is_none()
+            && self.should_ignore_white_space.is_none()
+            && self.should_do_reverse_reconciliation.is_none();
+    }
+
+    pub fn apply_to(&self, default_configs: &ReconciliationConfigs) -> ReconciliationConfigs {
+        return ReconciliationConfigs {
+            should_check_for_duplicate_records_in_comparison_file: self
+                .should_check_for_duplicate_records_in_comparison_file
+                .unwrap_or(default_configs.should_check_for_duplicate_records_in_comparison_file),
+            should_reconciliation_be_case_sensitive: self
+                .should_reconciliation_be_case_sensitive
+                .unwrap_or(default_configs.should_reconciliation_be_case_sensitive),
+            should_ignore_white_space: self
+                .should_ignore_white_space
+                .unwrap_or(default_configs.should_ignore_white_space),
+            should_do_reverse_reconciliation: self
+                .should_do_reverse_reconciliation
+                .unwrap_or(default_configs.should_do_reverse_reconciliation),
+        };
+    }
+}
+
+//options that set nothing are most likely a mistake by the caller, so they are not taken to mean the defaults
+fn validate_reconciliation_options(options: &ReconciliationOptions) -> Result<(), ValidationError> {
+    if options.is_empty() {
+        return Err(ValidationError::new("please supply at least one reconciliation option or leave reconciliation_options out"));
+    }
+    return Ok(());
+}
@@ -0,0 +1,12 @@
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file::File;
+
+//a file that is available on the local disk, along with the hash of its contents
+//that was worked out as the file was retrieved
+#[derive(Clone, PartialEq, Debug)]
+pub struct RetrievedFile {
+    //a copy of the requested file whose file_path points at the local copy
+    pub file: File,
+
+    //hex encoded sha256 of the file's contents
+    pub file_hash: String,
+}
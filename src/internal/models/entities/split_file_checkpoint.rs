@@ -0,0 +1,64 @@
+use std::collections::BTreeSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::ReconFileType;
+
+//how far the upload of a file's chunks has got, so that an interrupted split can be resumed
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct SplitFileCheckpoint {
+    pub upload_request_id: String,
+
+    //only the caller that started the split can resume it. checkpoints saved before
+    //this was recorded have no caller and cannot be resumed by anyone
+    #[serde(default)]
+    pub user_id: String,
+
+    pub file_type: ReconFileType,
+    pub file_fingerprint: String,
+
+    //every chunk up to and including this one has been acknowledged
+    pub last_acknowledged_chunk_sequence_number: i64,
+
+    //chunks after the last acknowledged one that were acknowledged
+    //while a chunk before them was still being uploaded
+    #[serde(default)]
+    pub chunks_acknowledged_out_of_order: BTreeSet<i64>,
+
+    pub is_complete: bool,
+}
+
+impl SplitFileCheckpoint {
+    pub fn new(upload_request_id: String, user_id: String, file_type: ReconFileType, file_fingerprint: String) -> SplitFileCheckpoint {
+        return SplitFileCheckpoint {
+            upload_request_id,
+            user_id,
+            file_type,
+            file_fingerprint,
+            last_acknowledged_chunk_sequence_number: 0,
+            chunks_acknowledged_out_of_order: BTreeSet::new(),
+            is_complete: false,
+        };
+    }
+
+    pub fn is_owned_by(&self, user_id: &str) -> bool {
+        return !self.user_id.is_empty() && self.user_id == user_id;
+    }
+
+    pub fn is_chunk_acknowledged(&self, chunk_sequence_number: i64) -> bool {
+        return chunk_sequence_number <= self.last_acknowledged_chunk_sequence_number
+            || self.chunks_acknowledged_out_of_order.contains(&chunk_sequence_number);
+    }
+
+    //the checkpoint only moves past a chunk once every chunk before it has been acknowledged
+    pub fn acknowledge_chunk(&mut self, chunk_sequence_number: i64) {
+        self.chunks_acknowledged_out_of_order.insert(chunk_sequence_number);
+
+        while self
+            .chunks_acknowledged_out_of_order
+            .remove(&(self.last_acknowledged_chunk_sequence_number + 1))
+        {
+            self.last_acknowledged_chunk_sequence_number = self.last_acknowledged_chunk_sequence_number + 1;
+        }
+    }
+}
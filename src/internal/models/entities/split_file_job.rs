@@ -0,0 +1,80 @@
+use serde::Serialize;
+
+use crate::internal::models::entities::file::FileThatHasBeenRead;
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::models::entities::inferred_file_settings::InferredFileSettings;
+use crate::internal::models::entities::split_file_checkpoint::SplitFileCheckpoint;
+use crate::internal::models::entities::statement_summary::StatementSummary;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppError;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file::File;
+
+//a file whose recon task is known and whose
+//rows are waiting to be read and uploaded in chunks
+#[derive(Clone, PartialEq, Debug)]
+pub struct SplitFileJob {
+    pub file: File,
+
+    //the caller that asked for the file to be split
+    pub user_id: String,
+
+    pub read_options: FileReadOptions,
+    pub file_that_has_been_read: FileThatHasBeenRead,
+
+    //set when an interrupted split is being resumed
+    pub resume_from_checkpoint: Option<SplitFileCheckpoint>,
+
+    //the settings that were worked out by looking at the file because the request left them out
+    pub inferred_settings: Option<InferredFileSettings>,
+
+    //the balances reported by a bank statement file
+    pub statement_summary: Option<StatementSummary>,
+}
+
+impl SplitFileJob {
+    pub fn get_upload_request_id(&self) -> String {
+        return self.file_that_has_been_read.upload_request_id.clone().unwrap_or("".to_string());
+    }
+}
+
+#[derive(Clone, PartialEq, Serialize, Debug)]
+pub enum SplitFileJobPhase {
+    Queued,
+    Reading,
+    Attaching,
+    Chunking,
+    Uploading,
+    Completed,
+    Failed,
+}
+
+#[derive(Clone, PartialEq, Serialize, Debug)]
+pub struct SplitFileJobStatus {
+    pub upload_request_id: String,
+
+    //only the caller that asked for the split can follow it
+    #[serde(skip_serializing)]
+    pub user_id: String,
+
+    pub phase: SplitFileJobPhase,
+    pub rows_read: u64,
+    pub chunks_uploaded: u64,
+    pub failure: Option<AppError>,
+}
+
+impl SplitFileJobStatus {
+    pub fn new(upload_request_id: String, user_id: String) -> SplitFileJobStatus {
+        return SplitFileJobStatus {
+            upload_request_id,
+            user_id,
+            phase: SplitFileJobPhase::Queued,
+            rows_read: 0,
+            chunks_uploaded: 0,
+            failure: None,
+        };
+    }
+
+    //a status saved without a caller belongs to nobody
+    pub fn is_owned_by(&self, user_id: &str) -> bool {
+        return !self.user_id.is_empty() && self.user_id == user_id;
+    }
+}
@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+//what a bank statement file says about itself alongside its transactions,
+//so callers can check the rows they reconcile add up to what the bank reported
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct StatementSummary {
+    pub statements: Vec<StatementBalances>,
+}
+
+//the balances of one statement in the file, a file can hold a statement per account or per day
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct StatementBalances {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub account_identification: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statement_reference: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub statement_number: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub opening_balance: Option<Balance>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closing_balance: Option<Balance>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closing_available_balance: Option<Balance>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct Balance {
+    //C when the balance is in credit, D when it is in debit
+    pub debit_credit_mark: String,
+
+    //yyyy-mm-dd
+    pub date: String,
+    pub currency: String,
+
+    //the amount without a sign and with a . before the decimals, e.g. "1234.56"
+    pub amount: String,
+}
@@ -1 +1,2 @@
+pub mod read_file_query_params;
 pub mod split_file_request;
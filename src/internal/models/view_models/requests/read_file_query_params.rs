@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug, Default)]
+pub struct ReadFileQueryParams {
+    //when set the request returns as soon as the recon task is known
+    //and the file's rows are uploaded by a background worker
+    #[serde(default)]
+    pub process_in_background: bool,
+}
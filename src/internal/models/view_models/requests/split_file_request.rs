@@ -1,33 +1,65 @@
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::models::entities::file_storage::FileStorage;
+use crate::internal::models::entities::named_comparison_pair::NamedComparisonPair;
+use crate::internal::models::entities::reconciliation_options::ReconciliationOptions;
 use crate::internal::shared_reconciler_rust_libraries::models::entities::file::File;
 
 #[derive(Serialize, Deserialize, Clone, Validate, Debug)]
 pub struct SplitFileRequest {
     pub file: File,
+
+    //where to retrieve the file from, when its file_storage_location does not say
+    pub file_storage: Option<FileStorage>,
+
+    //optional format specific settings for reading the file
+    pub read_options: Option<FileReadOptions>,
+
+    //optional settings for the new recon task, only accepted if no upload_request_id is supplied
+    #[validate]
+    pub reconciliation_options: Option<ReconciliationOptions>,
+
+    //comparison pairs whose columns are given by header name, resolved to column indexes
+    //once the file's column headers have been read
+    pub named_comparison_pairs: Option<Vec<NamedComparisonPair>>,
+
+    //the authenticated caller, set by the web api rather than read from the request body
+    #[serde(skip)]
+    pub user_id: Option<String>,
 }
 
 impl SplitFileRequest {
     pub fn is_metadata_required_for_new_recon_job(&self) -> bool {
 
         //if this is the second file in an existing upload job then we dont need metadata
-        if !self.file.upload_request_id.is_none() {
+        if self.file.upload_request_id.is_some() {
+            return false;
+        }
+
+        //comparison pairs given by header name are just as good as the ones in the metadata
+        if self.named_comparison_pairs.as_ref().is_some_and(|pairs| !pairs.is_empty()) {
             return false;
         }
 
-        //otherwise we need some info in the metadata
+        //otherwise we need at least one comparison pair in the metadata
         //before we can even begin to start
-        return match self.clone().file.file_metadata {
-            None => { true }
-            Some(metadata) => {
-                match metadata.comparison_pairs {
-                    None => { true }
-                    Some(_) => {
-                        false
-                    }
-                }
-            }
-        };
+        return self
+            .file
+            .file_metadata
+            .as_ref()
+            .and_then(|metadata| metadata.comparison_pairs.as_ref())
+            .is_none_or(|pairs| pairs.is_empty());
+    }
+
+    //reconciliation settings belong to the recon task, so they cannot be changed by the second file of a task
+    pub fn has_reconciliation_options_for_existing_recon_job(&self) -> bool {
+        return self.file.upload_request_id.is_some() && self.reconciliation_options.is_some();
+    }
+
+    //comparison pairs belong to the recon task, so they cannot be added by the second file of a task
+    pub fn has_named_comparison_pairs_for_existing_recon_job(&self) -> bool {
+        return self.file.upload_request_id.is_some() && self.named_comparison_pairs.is_some();
     }
 }
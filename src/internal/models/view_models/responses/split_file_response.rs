@@ -1,6 +1,17 @@
 use serde::{Deserialize, Serialize};
 
+use crate::internal::models::entities::inferred_file_settings::InferredFileSettings;
+use crate::internal::models::entities::statement_summary::StatementSummary;
+
 #[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
 pub struct SplitFileResponse {
     pub upload_request_id: String,
+
+    //what was assumed about the file's layout, so callers can check it was read the way they expected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inferred_settings: Option<InferredFileSettings>,
+
+    //the opening and closing balances of each statement in a bank statement file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statement_summary: Option<StatementSummary>,
 }
@@ -1,79 +1,99 @@
+use std::iter::Peekable;
+
 use crate::internal::{
-    interfaces::transformer::TransformerInterface,
+    interfaces::{
+        file_reader::FileRowStream,
+        transformer::{FileChunkStream, TransformerInterface},
+    },
+    models::entities::{chunk_payload::ChunkPayloadFormat, file::FileThatHasBeenRead},
     shared_reconciler_rust_libraries::models::entities::{
-        file::FileThatHasBeenRead, file_upload_chunk::FileUploadChunkSource,
+        app_errors::AppError,
+        file_upload_chunk::FileUploadChunkSource,
         recon_tasks_models::ReconFileType,
     },
 };
 use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::view_models::requests::UploadFileChunkRequest;
 
-pub struct Transformer {}
+pub struct Transformer {
+    pub chunk_payload_format: ChunkPayloadFormat,
+}
 
 impl TransformerInterface for Transformer {
     fn group_rows_into_file_chunks(
         &self,
         file_that_has_been_read: &FileThatHasBeenRead,
+        file_rows: FileRowStream,
         max_group_size: i64,
-    ) -> Vec<UploadFileChunkRequest> {
-        let mut results = vec![];
-        let mut row_count_in_group = 1;
-        let mut chunk_sequence_number = 1;
-        let mut iteration_count = 1;
-        let total_row_count = file_that_has_been_read.file_rows.len();
-
-        let mut file_upload_request = UploadFileChunkRequest {
+    ) -> FileChunkStream {
+        return Box::new(FileChunks {
             upload_request_id: file_that_has_been_read.upload_request_id.clone().unwrap_or("".to_string()),
-            chunk_sequence_number: chunk_sequence_number.clone(),
             chunk_source: self.get_chunk_source(file_that_has_been_read.file_type.clone()),
-            chunk_rows: vec![],
-            is_last_chunk: false,
-        };
-
-        for file_row in file_that_has_been_read.file_rows.clone() {
-            //add this file row to the group of rows
-            file_upload_request.chunk_rows.push(file_row.clone());
-
-            let is_last_chunk = iteration_count == total_row_count;
-            let is_chunk_full = row_count_in_group == max_group_size;
+            file_rows: file_rows.peekable(),
+            max_group_size,
+            next_chunk_sequence_number: 1,
+            chunk_payload_format: self.chunk_payload_format,
+        });
+    }
+}
 
-            if is_last_chunk || is_chunk_full {
-                file_upload_request.is_last_chunk = is_last_chunk;
-                file_upload_request.chunk_sequence_number = chunk_sequence_number.clone();
+impl Transformer {
+    fn get_chunk_source(&self, recon_file_type: ReconFileType) -> FileUploadChunkSource {
+        match recon_file_type {
+            ReconFileType::PrimaryFile => FileUploadChunkSource::PrimaryFileChunk,
+            ReconFileType::ComparisonFile => FileUploadChunkSource::ComparisonFileChunk,
+        }
+    }
+}
 
-                //add this group of rows as a new batch of rows for upload
-                results.push(file_upload_request.clone());
+//pulls rows from the stream only when the next chunk is asked for,
+//so no more than one chunk worth of rows is held at a time
+struct FileChunks {
+    upload_request_id: String,
+    chunk_source: FileUploadChunkSource,
+    file_rows: Peekable<FileRowStream>,
+    max_group_size: i64,
+    next_chunk_sequence_number: i64,
+    chunk_payload_format: ChunkPayloadFormat,
+}
 
-                //reset the group count
-                iteration_count = iteration_count + 1;
-                row_count_in_group = row_count_in_group + 1;
+impl Iterator for FileChunks {
+    type Item = Result<UploadFileChunkRequest, AppError>;
 
-                //create a new upload request for a new group of rows
-                file_upload_request = UploadFileChunkRequest {
-                    upload_request_id: file_that_has_been_read.upload_request_id.clone().unwrap_or("".to_string()),
-                    chunk_sequence_number: chunk_sequence_number.clone(),
-                    chunk_source: self.get_chunk_source(file_that_has_been_read.file_type.clone()),
-                    chunk_rows: vec![],
-                    is_last_chunk: false,
-                };
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk_rows = vec![];
 
-                //update the chunk sequence number
-                chunk_sequence_number = chunk_sequence_number + 1;
-                continue;
+        //add file rows to the group of rows until the group is full
+        while (chunk_rows.len() as i64) < self.max_group_size {
+            match self.file_rows.next() {
+                None => break,
+                Some(Err(e)) => return Some(Err(e)),
+                Some(Ok(file_row)) => match file_row.to_chunk_row(self.chunk_payload_format) {
+                    Ok(chunk_row) => chunk_rows.push(chunk_row),
+                    Err(e) => return Some(Err(e)),
+                },
             }
+        }
 
-            iteration_count = iteration_count + 1;
-            row_count_in_group = row_count_in_group + 1;
+        //a file without any rows is still sent as a single empty chunk, so that
+        //the receiving service is told the file is complete and closes the recon task
+        if chunk_rows.is_empty() && self.next_chunk_sequence_number > 1 {
+            return None;
         }
 
-        return results;
-    }
-}
+        //the chunk is the last one if there are no rows left behind it
+        let is_last_chunk = self.file_rows.peek().is_none();
 
-impl Transformer {
-    fn get_chunk_source(&self, recon_file_type: ReconFileType) -> FileUploadChunkSource {
-        match recon_file_type {
-            ReconFileType::PrimaryFile => FileUploadChunkSource::PrimaryFileChunk,
-            ReconFileType::ComparisonFile => FileUploadChunkSource::ComparisonFileChunk,
-        }
+        let file_upload_request = UploadFileChunkRequest {
+            upload_request_id: self.upload_request_id.clone(),
+            chunk_sequence_number: self.next_chunk_sequence_number,
+            chunk_source: self.chunk_source.clone(),
+            chunk_rows,
+            is_last_chunk,
+        };
+
+        //update the chunk sequence number
+        self.next_chunk_sequence_number = self.next_chunk_sequence_number + 1;
+
+        return Some(Ok(file_upload_request));
     }
 }
@@ -1,7 +1,14 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 use crate::internal::interfaces::transformer::TransformerInterface;
 use crate::internal::services::core_logic::transformer::Transformer;
-use crate::internal::shared_reconciler_rust_libraries::models::entities::file::{FileMetadata, FileThatHasBeenRead};
-use crate::internal::shared_reconciler_rust_libraries::models::entities::file_row::FileRow;
+use crate::internal::models::entities::chunk_payload::{ChunkPayloadFormat, StructuredChunkRow};
+use crate::internal::models::entities::file::FileThatHasBeenRead;
+use crate::internal::models::entities::file_row::{CellValue, FileRow};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{AppError, AppErrorKind};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file::FileMetadata;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file_row::FileRow as ChunkFileRow;
 use crate::internal::shared_reconciler_rust_libraries::models::entities::file_upload_chunk::FileUploadChunkSource;
 use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::{ComparisonPair, ReconFileType};
 use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::view_models::requests::UploadFileChunkRequest;
@@ -11,7 +18,7 @@ use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservi
 //a particular test case
 #[derive(Clone, Debug)]
 struct TestSpecifications {
-    request: (FileThatHasBeenRead, i64),
+    request: (FileThatHasBeenRead, Vec<FileRow>, i64),
     expected_final_result: Vec<UploadFileChunkRequest>,
 }
 
@@ -50,17 +57,17 @@ fn test_group_rows_into_file_chunks() {
 
 fn generate_ok_test_specification() -> TestSpecifications {
     TestSpecifications {
-        request: (get_dummy_request(), 200),
+        request: (get_dummy_request(), get_dummy_file_rows(), 200),
         expected_final_result: vec![UploadFileChunkRequest {
             upload_request_id: "RECON-TASK-1234".to_string(),
             chunk_sequence_number: 1,
             chunk_source: FileUploadChunkSource::PrimaryFileChunk,
             chunk_rows: vec![
-                FileRow {
+                ChunkFileRow {
                     raw_data: "001,2000".to_string(),
                     row_number: 1,
                 },
-                FileRow {
+                ChunkFileRow {
                     raw_data: "001,4000".to_string(),
                     row_number: 2,
                 },
@@ -72,14 +79,14 @@ fn generate_ok_test_specification() -> TestSpecifications {
 
 fn generate_is_max_rows_less_than_file_rows_handled_correctly_test_specification() -> TestSpecifications {
     TestSpecifications {
-        request: (get_dummy_request(), 1),
+        request: (get_dummy_request(), get_dummy_file_rows(), 1),
         expected_final_result: vec![
             UploadFileChunkRequest {
                 upload_request_id: "RECON-TASK-1234".to_string(),
                 chunk_sequence_number: 1,
                 chunk_source: FileUploadChunkSource::PrimaryFileChunk,
                 chunk_rows: vec![
-                    FileRow {
+                    ChunkFileRow {
                         raw_data: "001,2000".to_string(),
                         row_number: 1,
                     },
@@ -91,7 +98,7 @@ fn generate_is_max_rows_less_than_file_rows_handled_correctly_test_specification
                 chunk_sequence_number: 2,
                 chunk_source: FileUploadChunkSource::PrimaryFileChunk,
                 chunk_rows: vec![
-                    FileRow {
+                    ChunkFileRow {
                         raw_data: "001,4000".to_string(),
                         row_number: 2,
                     },
@@ -104,10 +111,10 @@ fn generate_is_max_rows_less_than_file_rows_handled_correctly_test_specification
 
 
 fn setup_service_and_send_request(test_specifications: &TestSpecifications) -> Vec<UploadFileChunkRequest> {
-    let sut = Transformer {};
-    let (file_that_has_been_read, max_rows_per_group) = test_specifications.request.clone();
-    let result = sut.group_rows_into_file_chunks(&file_that_has_been_read, max_rows_per_group);
-    return result;
+    let sut = Transformer { chunk_payload_format: ChunkPayloadFormat::RawDataV1 };
+    let (file_that_has_been_read, file_rows, max_rows_per_group) = test_specifications.request.clone();
+    let result = sut.group_rows_into_file_chunks(&file_that_has_been_read, Box::new(file_rows.into_iter().map(Ok)), max_rows_per_group);
+    return result.map(|chunk| chunk.unwrap()).collect();
 }
 
 fn get_dummy_request() -> FileThatHasBeenRead {
@@ -119,16 +126,8 @@ fn get_dummy_request() -> FileThatHasBeenRead {
             String::from("record_id"),
             String::from("transaction_amount"),
         ],
-        file_rows: vec![
-            FileRow {
-                raw_data: "001,2000".to_string(),
-                row_number: 1,
-            },
-            FileRow {
-                raw_data: "001,4000".to_string(),
-                row_number: 2,
-            },
-        ],
+        row_count: 2,
+        file_hash: String::from("FILE-HASH-1234"),
 
         file_metadata: Some(FileMetadata {
             column_delimiters: Some(vec![',']),
@@ -139,4 +138,115 @@ fn get_dummy_request() -> FileThatHasBeenRead {
             }]),
         }),
     }
-}
\ No newline at end of file
+}
+
+fn get_dummy_file_rows() -> Vec<FileRow> {
+    vec![
+        FileRow {
+            raw_data: "001,2000".to_string(),
+            row_number: 1,
+            cells: vec!["001".to_string(), "2000".to_string()],
+            values: None,
+        },
+        FileRow {
+            raw_data: "001,4000".to_string(),
+            row_number: 2,
+            cells: vec!["001".to_string(), "4000".to_string()],
+            values: None,
+        },
+    ]
+}
+
+#[test]
+fn test_group_rows_into_file_chunks_returns_row_errors() {
+    let sut = Transformer { chunk_payload_format: ChunkPayloadFormat::RawDataV1 };
+    let file_rows: Vec<Result<FileRow, AppError>> = vec![
+        Ok(get_dummy_file_rows()[0].clone()),
+        Err(AppError::new(AppErrorKind::BadClientRequest, "error occurred".to_string())),
+    ];
+
+    let result: Result<Vec<UploadFileChunkRequest>, AppError> = sut
+        .group_rows_into_file_chunks(&get_dummy_request(), Box::new(file_rows.into_iter()), 200)
+        .collect();
+
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_group_rows_into_file_chunks_sends_a_single_empty_last_chunk_for_a_file_without_rows() {
+    let sut = Transformer { chunk_payload_format: ChunkPayloadFormat::RawDataV1 };
+    let file_rows: Vec<Result<FileRow, AppError>> = vec![];
+
+    let file_chunks: Vec<UploadFileChunkRequest> = sut
+        .group_rows_into_file_chunks(&get_dummy_request(), Box::new(file_rows.into_iter()), 200)
+        .collect::<Result<Vec<UploadFileChunkRequest>, AppError>>()
+        .unwrap();
+
+    assert_eq!(file_chunks.len(), 1);
+    assert_eq!(file_chunks[0].chunk_sequence_number, 1);
+    assert!(file_chunks[0].chunk_rows.is_empty());
+    assert!(file_chunks[0].is_last_chunk);
+}
+
+#[test]
+fn test_group_rows_into_file_chunks_only_reads_rows_for_the_next_chunk() {
+    let sut = Transformer { chunk_payload_format: ChunkPayloadFormat::RawDataV1 };
+    let rows_read = Arc::new(AtomicUsize::new(0));
+    let rows_read_clone = rows_read.clone();
+    let file_rows = (1..=1000).map(move |row_number| {
+        rows_read_clone.fetch_add(1, Ordering::SeqCst);
+        Ok(FileRow {
+            raw_data: format!("{},100", row_number),
+            row_number,
+            cells: vec![row_number.to_string(), "100".to_string()],
+            values: None,
+        })
+    });
+
+    let mut file_chunks = sut.group_rows_into_file_chunks(&get_dummy_request(), Box::new(file_rows), 10);
+    let first_chunk = file_chunks.next().unwrap().unwrap();
+
+    //one chunk of rows plus the row peeked at to decide if the chunk is the last one
+    assert_eq!(first_chunk.chunk_rows.len(), 10);
+    assert_eq!(rows_read.load(Ordering::SeqCst), 11);
+    assert_eq!(file_chunks.count(), 99);
+}
+
+#[test]
+fn test_group_rows_into_file_chunks_writes_structured_cells_in_payload_version_2() {
+    let sut = Transformer { chunk_payload_format: ChunkPayloadFormat::StructuredCellsV2 };
+    let file_rows: Vec<Result<FileRow, AppError>> = vec![
+        Ok(get_dummy_file_rows()[0].clone()),
+        Ok(FileRow::from_cells(2, vec!["002".to_string(), "15.5".to_string()], ',')
+            .with_values(vec![CellValue::Text("002".to_string()), CellValue::Decimal(15.5)])),
+    ];
+
+    let file_chunks: Vec<UploadFileChunkRequest> = sut
+        .group_rows_into_file_chunks(&get_dummy_request(), Box::new(file_rows.into_iter()), 200)
+        .collect::<Result<Vec<UploadFileChunkRequest>, AppError>>()
+        .unwrap();
+
+    let structured_rows: Vec<StructuredChunkRow> = file_chunks[0]
+        .chunk_rows
+        .iter()
+        .map(|chunk_row| serde_json::from_str(&chunk_row.raw_data).unwrap())
+        .collect();
+
+    assert_eq!(file_chunks[0].chunk_rows[1].row_number, 2);
+    assert_eq!(structured_rows, vec![
+        StructuredChunkRow {
+            payload_version: 2,
+            cells: vec!["001".to_string(), "2000".to_string()],
+            values: None,
+        },
+        StructuredChunkRow {
+            payload_version: 2,
+            cells: vec!["002".to_string(), "15.5".to_string()],
+            values: Some(vec![CellValue::Text("002".to_string()), CellValue::Decimal(15.5)]),
+        },
+    ]);
+    assert_eq!(
+        file_chunks[0].chunk_rows[1].raw_data,
+        r#"{"payload_version":2,"cells":["002","15.5"],"values":[{"type":"Text","value":"002"},{"type":"Decimal","value":15.5}]}"#
+    );
+}
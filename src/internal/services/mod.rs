@@ -1,4 +1,5 @@
 pub mod core_logic;
+pub mod split_file_job_worker;
 pub mod split_file_service;
 
 #[cfg(test)]
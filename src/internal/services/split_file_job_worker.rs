@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use tokio::sync::mpsc::{channel, error::TrySendError, Receiver, Sender};
+
+use crate::internal::{
+    interfaces::{
+        split_file_job_queue::SplitFileJobQueueInterface,
+        split_file_service::SplitFileServiceInterface,
+    },
+    models::entities::{
+        app_error_reason::{AppErrorReason, AppErrorWithReason},
+        split_file_job::SplitFileJob,
+    },
+    shared_reconciler_rust_libraries::{
+        common::utils::app_error_with_msg,
+        models::entities::app_errors::AppErrorKind,
+    },
+};
+
+//hands queued jobs over to the background worker, turning jobs away once the worker has capacity jobs waiting
+#[derive(Clone)]
+pub struct SplitFileJobQueue {
+    sender: Sender<SplitFileJob>,
+}
+
+impl SplitFileJobQueueInterface for SplitFileJobQueue {
+    fn queue_job(&self, job: SplitFileJob) -> Result<(), AppErrorWithReason> {
+        return match self.sender.try_send(job) {
+            Ok(_) => Ok(()),
+            Err(TrySendError::Full(_)) => AppErrorReason::JobQueueFull.app_error(AppErrorKind::InternalError, None),
+            Err(TrySendError::Closed(_)) => app_error_with_msg(AppErrorKind::InternalError, "the split file job worker is no longer running").map_err(AppErrorWithReason::from),
+        };
+    }
+}
+
+impl SplitFileJobQueue {
+    pub fn new(capacity: usize) -> (SplitFileJobQueue, Receiver<SplitFileJob>) {
+        let (sender, receiver) = channel(capacity);
+        return (SplitFileJobQueue { sender }, receiver);
+    }
+}
+
+/**
+runs queued jobs one after the other until every queue handing jobs to it has been dropped
+
+failed jobs do not stop the worker, their errors are recorded in the job's status by the service
+ */
+pub async fn run_split_file_job_worker(
+    split_file_service: Arc<dyn SplitFileServiceInterface>,
+    mut receiver: Receiver<SplitFileJob>,
+) {
+    while let Some(job) = receiver.recv().await {
+        let upload_request_id = job.get_upload_request_id();
+
+        if let Err(e) = split_file_service.run_split_file_job(job).await {
+            log::error!("split file job for upload_request_id {} failed: {}", upload_request_id, e);
+        }
+    }
+}
@@ -1,29 +1,57 @@
+use std::future::Future;
+
 use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
 use validator::Validate;
 
 use crate::internal::{
     interfaces::{
         file_chunks_upload_service_connector::FileChunksUploadHandlerServiceConnectorInterface, file_reader::FileReader,
+        file_retriever::FileRetriever,
         recon_tasks_service_connector::ReconTasksServiceConnectorInterface,
-        split_file_service::SplitFileServiceInterface, transformer::TransformerInterface,
+        split_file_checkpoint_store::SplitFileCheckpointStoreInterface,
+        split_file_job_queue::SplitFileJobQueueInterface,
+        split_file_job_status_store::SplitFileJobStatusStoreInterface,
+        split_file_service::SplitFileServiceInterface, transformer::{FileChunkStream, TransformerInterface},
     },
     models::view_models::{
         requests::split_file_request::SplitFileRequest,
         responses::split_file_response::SplitFileResponse,
     },
-    shared_reconciler_rust_libraries::models::entities::recon_tasks_models::ReconFileType,
+    shared_reconciler_rust_libraries::models::entities::recon_tasks_models::{ReconciliationConfigs, ReconFileType},
 };
 use crate::internal::shared_reconciler_rust_libraries::common::utils::{app_error, app_error_with_msg};
 use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::{
     AppError, AppErrorKind,
 };
-use crate::internal::shared_reconciler_rust_libraries::models::entities::file::FileThatHasBeenRead;
+use crate::internal::models::entities::app_error_reason::{AppErrorReason, AppErrorWithReason};
+use crate::internal::models::entities::chunk_payload::ChunkPayloadFormat;
+use crate::internal::models::entities::file::FileThatHasBeenRead;
+use crate::internal::models::entities::file_read_options::FileReadOptions;
+use crate::internal::models::entities::file_storage::FileStorage;
+use crate::internal::models::entities::inferred_file_settings::InferredFileSettings;
+use crate::internal::models::entities::named_comparison_pair::NamedComparisonPair;
+use crate::internal::models::entities::split_file_checkpoint::SplitFileCheckpoint;
+use crate::internal::models::entities::split_file_job::{SplitFileJob, SplitFileJobPhase, SplitFileJobStatus};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file::{File, FileMetadata};
+use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::view_models::requests::UploadFileChunkRequest;
+
+//the most rows that are put into a single file chunk
+const MAX_ROWS_PER_FILE_CHUNK: i64 = 200;
 
 pub struct SplitFileService {
+    pub file_retriever: Box<dyn FileRetriever>,
     pub file_reader: Box<dyn FileReader>,
     pub transformer: Box<dyn TransformerInterface>,
     pub file_chunks_uploader: Box<dyn FileChunksUploadHandlerServiceConnectorInterface>,
     pub recon_tasks_handler: Box<dyn ReconTasksServiceConnectorInterface>,
+    pub job_queue: Box<dyn SplitFileJobQueueInterface>,
+    pub job_status_store: Box<dyn SplitFileJobStatusStoreInterface>,
+    pub checkpoint_store: Box<dyn SplitFileCheckpointStoreInterface>,
+    pub max_concurrent_chunk_uploads: usize,
+    pub should_reject_duplicate_files: bool,
+    pub default_reconciliation_configs: ReconciliationConfigs,
+    pub chunk_payload_format: ChunkPayloadFormat,
 }
 
 #[async_trait]
@@ -39,7 +67,147 @@ impl SplitFileServiceInterface for SplitFileService {
         &self,
         request: SplitFileRequest,
     ) -> Result<SplitFileResponse, AppError> {
+        let job = self.prepare_split_file_job(request).await?;
+        let upload_request_id = job.get_upload_request_id();
+        let inferred_settings = job.inferred_settings.clone();
+        let statement_summary = job.statement_summary.clone();
+
+        self.run_split_file_job(job).await?;
+
+        return Ok(SplitFileResponse { upload_request_id, inferred_settings, statement_summary });
+    }
+
+    /**
+    creates the recon task for the file (if none was supplied) and queues the rest of the work
+
+    # Errors
+
+    This function will return an error if the request fails validation, the file's column headers
+    cannot be read or the recon task cannot be created. failures after the job has been queued
+    are reported through the job's status instead
+     */
+    async fn queue_file_for_splitting(
+        &self,
+        request: SplitFileRequest,
+    ) -> Result<SplitFileResponse, AppErrorWithReason> {
+        let job = self.prepare_split_file_job(request).await?;
+        let upload_request_id = job.get_upload_request_id();
+        let inferred_settings = job.inferred_settings.clone();
+        let statement_summary = job.statement_summary.clone();
+        let file = job.file.clone();
+
+        let mut job_status = SplitFileJobStatus::new(upload_request_id.clone(), job.user_id.clone());
+        self.job_status_store.save_job_status(&job_status);
+
+        if let Err(e) = self.job_queue.queue_job(job) {
+            job_status.failure = Some(e.error.clone());
+            self.save_job_status(&mut job_status, SplitFileJobPhase::Failed);
+
+            self.release_file(&file).await;
+            return Err(e);
+        }
+
+        return Ok(SplitFileResponse { upload_request_id, inferred_settings, statement_summary });
+    }
+
+    /**
+    picks up an interrupted split where it stopped, skipping the chunks that were already acknowledged
 
+    # Errors
+
+    This function will return an error if no split was started by the caller for the upload_request_id and file type,
+    if that split has already been completed, or if the resubmitted file is not the same file that was being split
+     */
+    async fn resume_splitting_file(
+        &self,
+        upload_request_id: String,
+        mut request: SplitFileRequest,
+    ) -> Result<SplitFileResponse, AppErrorWithReason> {
+        let user_id = request.user_id.clone().unwrap_or_default();
+
+        //a split started by another caller is reported as missing so that its existence is not given away
+        let checkpoint = match self.checkpoint_store.get_checkpoint(&upload_request_id, &request.file.file_type).await? {
+            Some(checkpoint) if checkpoint.is_owned_by(&user_id) => checkpoint,
+            _ => {
+                return app_error_with_msg(
+                    AppErrorKind::NotFound,
+                    &format!("no split of a {:?} was started for upload_request_id {}", request.file.file_type, upload_request_id),
+                )
+                .map_err(AppErrorWithReason::from);
+            }
+        };
+
+        if checkpoint.is_complete {
+            return AppErrorReason::SplitAlreadyCompleted.app_error(
+                AppErrorKind::BadClientRequest,
+                Some(&format!("every chunk of the {:?} for upload_request_id {} was acknowledged", checkpoint.file_type, upload_request_id)),
+            );
+        }
+
+        //the recon task already exists, so any reconciliation options or named comparison pairs resubmitted with the file no longer apply
+        request.file.upload_request_id = Some(upload_request_id.clone());
+        request.reconciliation_options = None;
+        request.named_comparison_pairs = None;
+        let mut job = self.prepare_split_file_job(request).await?;
+
+        if job.file_that_has_been_read.file_hash != checkpoint.file_fingerprint {
+            self.release_file(&job.file).await;
+            return app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                "the file supplied is not the same file whose split is being resumed",
+            )
+            .map_err(AppErrorWithReason::from);
+        }
+
+        job.resume_from_checkpoint = Some(checkpoint);
+        let inferred_settings = job.inferred_settings.clone();
+        let statement_summary = job.statement_summary.clone();
+        self.run_split_file_job(job).await?;
+
+        return Ok(SplitFileResponse { upload_request_id, inferred_settings, statement_summary });
+    }
+
+    //reads the rows in the job's file and uploads them in chunks,
+    //recording how far the job has got as it goes
+    async fn run_split_file_job(&self, job: SplitFileJob) -> Result<(), AppError> {
+        let mut job_status = SplitFileJobStatus::new(job.get_upload_request_id(), job.user_id.clone());
+
+        let split_result = self.split_file_into_chunks(&job, &mut job_status).await;
+
+        //the local copy of the file is not needed once all its chunks have been uploaded
+        self.release_file(&job.file).await;
+
+        match &split_result {
+            Ok(_) => self.save_job_status(&mut job_status, SplitFileJobPhase::Completed),
+            Err(e) => {
+                job_status.failure = Some(e.clone());
+                self.save_job_status(&mut job_status, SplitFileJobPhase::Failed);
+            }
+        }
+
+        return split_result;
+    }
+
+    async fn get_split_file_job_status(
+        &self,
+        upload_request_id: String,
+        user_id: String,
+    ) -> Result<SplitFileJobStatus, AppError> {
+        //a job started by another caller is reported as missing so that its existence is not given away
+        return match self.job_status_store.get_job_status(&upload_request_id) {
+            Some(job_status) if job_status.is_owned_by(&user_id) => Ok(job_status),
+            _ => app_error_with_msg(
+                AppErrorKind::NotFound,
+                &format!("no split file job was found for upload_request_id {}", upload_request_id),
+            ),
+        };
+    }
+}
+
+impl SplitFileService {
+    //validates the request, makes sure the file is available on the local disk,
+    //reads its column headers and creates the recon task if this is a new recon job
+    async fn prepare_split_file_job(&self, request: SplitFileRequest) -> Result<SplitFileJob, AppError> {
         //validate request
         match request.validate() {
             Ok(_) => (),
@@ -52,51 +220,412 @@ impl SplitFileServiceInterface for SplitFileService {
             return app_error_with_msg(AppErrorKind::BadClientRequest, "please supply comparison pairs if no upload_request_id supplied");
         }
 
-        //get a handle to the underlying file
-        let file = request.file;
+        if request.has_reconciliation_options_for_existing_recon_job() {
+            return app_error_with_msg(AppErrorKind::BadClientRequest, "reconciliation options can only be supplied if no upload_request_id is supplied");
+        }
+
+        if request.has_named_comparison_pairs_for_existing_recon_job() {
+            return app_error_with_msg(AppErrorKind::BadClientRequest, "named comparison pairs can only be supplied if no upload_request_id is supplied");
+        }
 
-        //read the records in the file
-        let mut file_that_has_been_read = self.file_reader.read_file(&file).await?;
+        //every new recon task is owned by the caller that created it
+        if request.file.upload_request_id.is_none() && request.user_id.is_none() {
+            return app_error_with_msg(AppErrorKind::BadClientRequest, "please supply the user_id of the caller creating the recon task");
+        }
 
-        match file_that_has_been_read.upload_request_id {
-            None => {
+        let mut read_options = request.read_options.unwrap_or_default();
+        let user_id = request.user_id.unwrap_or_default();
 
-                //since this is a new recon task, we create the recon task
-                let upload_request_id = self.recon_tasks_handler.create_recon_task(&file_that_has_been_read).await?;
+        //settings the caller left out fall back to the server's defaults
+        let recon_configurations = request
+            .reconciliation_options
+            .unwrap_or_default()
+            .apply_to(&self.default_reconciliation_configs);
 
-                //we set the recon task id
-                file_that_has_been_read.upload_request_id = Some(upload_request_id);
+        let mut requested_file = request.file;
+        self.apply_csv_column_delimiters(&mut requested_file, &read_options)?;
 
-                //then we attach the file to the recon task
-                //depending on the file type
-                self.attach_file_to_task(&mut file_that_has_been_read).await?
+        //make sure the file is available on the local disk
+        let file_storage = FileStorage::from_file(&requested_file, request.file_storage);
+        let retrieved_file = self.file_retriever.retrieve_file(requested_file, file_storage).await?;
+        let mut file = retrieved_file.file;
+
+        //the settings the request left out are worked out from the start of the file
+        let inferred_settings = match self.file_reader.infer_file_settings(&file, &read_options).await {
+            Ok(inferred_settings) => inferred_settings,
+            Err(e) => {
+                self.release_file(&file).await;
+                return Err(e);
             }
+        };
+        if let Some(inferred_settings) = &inferred_settings {
+            SplitFileService::apply_inferred_file_settings(&mut file, &mut read_options, inferred_settings);
+        }
 
-            Some(_) => {
-                //we attach the file to the recon task
-                //depending on the file type
-                self.attach_file_to_task(&mut file_that_has_been_read).await?
+        let statement_summary = match self.file_reader.read_statement_summary(&file, &read_options).await {
+            Ok(statement_summary) => statement_summary,
+            Err(e) => {
+                self.release_file(&file).await;
+                return Err(e);
+            }
+        };
+
+        let named_comparison_pairs = request.named_comparison_pairs.unwrap_or_default();
+
+        return match self
+            .create_recon_task_if_required(&file, &retrieved_file.file_hash, &read_options, &user_id, &recon_configurations, &named_comparison_pairs)
+            .await
+        {
+            Ok(file_that_has_been_read) => Ok(SplitFileJob {
+                file,
+                user_id,
+                read_options,
+                file_that_has_been_read,
+                resume_from_checkpoint: None,
+                inferred_settings,
+                statement_summary,
+            }),
+            Err(e) => {
+                self.release_file(&file).await;
+                Err(e)
+            }
+        };
+    }
+
+    //rows of raw text are split again downstream using the delimiters sent when the file is attached, so single
+    //character csv delimiters go into the file's metadata and any others need rows that are already split into cells
+    fn apply_csv_column_delimiters(&self, file: &mut File, read_options: &FileReadOptions) -> Result<(), AppError> {
+        let csv_read_options = match &read_options.csv {
+            Some(csv_read_options) => csv_read_options,
+            None => return Ok(()),
+        };
+
+        if self.chunk_payload_format == ChunkPayloadFormat::RawDataV1 && csv_read_options.is_structured_chunk_payload_required() {
+            return app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                "column delimiters longer than one character and column_delimiter_pattern can only be used when the server sends chunk payload version 2",
+            );
+        }
+
+        if let Some(column_delimiters) = csv_read_options.get_single_character_column_delimiters() {
+            let mut file_metadata = file.file_metadata.clone().unwrap_or(FileMetadata {
+                column_delimiters: None,
+                comparison_pairs: None,
+            });
+            file_metadata.column_delimiters = Some(column_delimiters);
+            file.file_metadata = Some(file_metadata);
+        }
+
+        return Ok(());
+    }
+
+    //a sniffed delimiter goes into the file's metadata so that it is also
+    //sent to the recon task when the file is attached
+    fn apply_inferred_file_settings(file: &mut File, read_options: &mut FileReadOptions, inferred_settings: &InferredFileSettings) {
+        if let Some(column_delimiter) = inferred_settings.column_delimiter.as_ref().and_then(|delimiter| delimiter.chars().next()) {
+            let mut file_metadata = file.file_metadata.clone().unwrap_or(FileMetadata {
+                column_delimiters: None,
+                comparison_pairs: None,
+            });
+            file_metadata.column_delimiters = Some(vec![column_delimiter]);
+            file.file_metadata = Some(file_metadata);
+        }
+
+        let mut csv_read_options = read_options.csv.clone().unwrap_or_default();
+        if inferred_settings.quote_character.is_some() {
+            csv_read_options.quote_character = inferred_settings.quote_character;
+        }
+        if inferred_settings.has_header_row.is_some() {
+            csv_read_options.has_header_row = inferred_settings.has_header_row;
+        }
+        if inferred_settings.encoding.is_some() {
+            csv_read_options.encoding = inferred_settings.encoding.clone();
+        }
+        read_options.csv = Some(csv_read_options);
+    }
+
+    async fn create_recon_task_if_required(
+        &self,
+        file: &File,
+        file_hash: &str,
+        read_options: &FileReadOptions,
+        user_id: &str,
+        recon_configurations: &ReconciliationConfigs,
+        named_comparison_pairs: &[NamedComparisonPair],
+    ) -> Result<FileThatHasBeenRead, AppError> {
+        if self.should_reject_duplicate_files {
+            self.reject_file_if_attached_to_another_task(file, file_hash).await?;
+        }
+
+        //only the column headers are needed to create the recon task
+        let column_headers = self.file_reader.read_column_headers(file, read_options).await?;
+        let mut file_that_has_been_read = FileThatHasBeenRead::new(file, column_headers, file_hash.to_string());
+
+        //the recon task only understands comparison pairs made up of column indexes
+        self.add_named_comparison_pairs(&mut file_that_has_been_read, named_comparison_pairs)?;
+
+        //the second file of a recon task is reconciled using the pairs the task was created with
+        let mut comparison_pairs = file_that_has_been_read.get_comparison_pairs();
+        if let Some(upload_request_id) = &file_that_has_been_read.upload_request_id {
+            comparison_pairs.extend(self.recon_tasks_handler.get_recon_task_comparison_pairs(upload_request_id).await?);
+        }
+
+        //pairs that point at columns the file does not have would only fail once the file is reconciled
+        let invalid_comparison_pairs = file_that_has_been_read.get_invalid_comparison_pairs(&comparison_pairs);
+        if !invalid_comparison_pairs.is_empty() {
+            return app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!(
+                    "the {:?} has {} column headers {:?}, these comparison pairs reference columns it does not have: {:?}",
+                    file_that_has_been_read.file_type,
+                    file_that_has_been_read.column_headers.len(),
+                    file_that_has_been_read.column_headers,
+                    invalid_comparison_pairs
+                ),
+            );
+        }
+
+        if file_that_has_been_read.upload_request_id.is_none() {
+            //since this is a new recon task, we create the recon task
+            let upload_request_id = self.recon_tasks_handler
+                .create_recon_task(&file_that_has_been_read, user_id, recon_configurations)
+                .await?;
+
+            //we set the recon task id
+            file_that_has_been_read.upload_request_id = Some(upload_request_id);
+        }
+
+        return Ok(file_that_has_been_read);
+    }
+
+    fn add_named_comparison_pairs(
+        &self,
+        file_that_has_been_read: &mut FileThatHasBeenRead,
+        named_comparison_pairs: &[NamedComparisonPair],
+    ) -> Result<(), AppError> {
+        if named_comparison_pairs.is_empty() {
+            return Ok(());
+        }
+
+        let mut comparison_pairs = vec![];
+        let mut problems = vec![];
+
+        for named_comparison_pair in named_comparison_pairs {
+            match named_comparison_pair.resolve(&file_that_has_been_read.file_type, &file_that_has_been_read.column_headers) {
+                Ok(comparison_pair) => comparison_pairs.push(comparison_pair),
+                Err(problem) => problems.push(problem),
             }
         }
 
-        //group the records into file chunks
-        let file_chunks = self
+        if !problems.is_empty() {
+            return app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!("some named comparison pairs could not be resolved: {}", problems.join("; ")),
+            );
+        }
+
+        let mut file_metadata = file_that_has_been_read.file_metadata.clone().unwrap_or(FileMetadata {
+            column_delimiters: None,
+            comparison_pairs: None,
+        });
+        let mut all_comparison_pairs = file_metadata.comparison_pairs.unwrap_or_default();
+        all_comparison_pairs.extend(comparison_pairs);
+        file_metadata.comparison_pairs = Some(all_comparison_pairs);
+        file_that_has_been_read.file_metadata = Some(file_metadata);
+
+        return Ok(());
+    }
+
+    //a file is a duplicate if a file with the same contents is attached to a
+    //different recon task that the recon tasks service has not finished with yet
+    async fn reject_file_if_attached_to_another_task(&self, file: &File, file_hash: &str) -> Result<(), AppError> {
+        let upload_request_id = file.upload_request_id.clone().unwrap_or_default();
+
+        return match self.recon_tasks_handler.find_active_recon_task_with_file(file_hash).await? {
+            Some(recon_task_id) if recon_task_id != upload_request_id => app_error_with_msg(
+                AppErrorKind::BadClientRequest,
+                &format!("this file has already been attached to recon task {}, which is still in progress", recon_task_id),
+            ),
+            _ => Ok(()),
+        };
+    }
+
+    /**
+    counts the rows in the file, attaches it to its recon task and uploads its rows in chunks
+
+    rows are streamed from the reader into the chunker and each chunk is uploaded as soon as it is full,
+    so at most max_concurrent_chunk_uploads chunks of rows (MAX_ROWS_PER_FILE_CHUNK rows each) are held in memory at a time.
+    the file is read twice: once to count its rows for the recon task and once to upload its rows
+     */
+    async fn split_file_into_chunks(&self, job: &SplitFileJob, job_status: &mut SplitFileJobStatus) -> Result<(), AppError> {
+        let mut file_that_has_been_read = job.file_that_has_been_read.clone();
+
+        let mut checkpoint = match job.resume_from_checkpoint.clone() {
+            //the file was counted and attached to its recon task before the split was interrupted
+            Some(checkpoint) => checkpoint,
+
+            None => {
+                //count the records in the file
+                self.save_job_status(job_status, SplitFileJobPhase::Reading);
+                file_that_has_been_read.row_count = self.file_reader.count_file_rows(&job.file, &job.read_options).await?;
+                job_status.rows_read = file_that_has_been_read.row_count;
+
+                //then we attach the file to the recon task
+                //depending on the file type
+                self.save_job_status(job_status, SplitFileJobPhase::Attaching);
+                self.attach_file_to_task(&mut file_that_has_been_read).await?;
+
+                let checkpoint = SplitFileCheckpoint::new(
+                    job.get_upload_request_id(),
+                    job.user_id.clone(),
+                    file_that_has_been_read.file_type.clone(),
+                    file_that_has_been_read.file_hash.clone(),
+                );
+                self.save_checkpoint(&checkpoint).await;
+                checkpoint
+            }
+        };
+
+        //stream the records in the file
+        let file_rows = self.file_reader.stream_file_rows(&job.file, &job.read_options).await?;
+
+        //group the records into file chunks as they are read
+        let mut file_chunks = self
             .transformer
-            .group_rows_into_file_chunks(&file_that_has_been_read, 200);
+            .group_rows_into_file_chunks(&file_that_has_been_read, file_rows, MAX_ROWS_PER_FILE_CHUNK);
+
+        //upload each chunk as soon as it has been put together,
+        //keeping up to max_concurrent_chunk_uploads uploads in flight
+        let max_concurrent_chunk_uploads = self.max_concurrent_chunk_uploads.max(1);
+        let mut uploads_in_flight = FuturesUnordered::new();
+
+        self.save_job_status(job_status, SplitFileJobPhase::Chunking);
+        loop {
+            let (remaining_file_chunks, next_chunk) = self
+                .put_next_chunk_together(file_chunks, &mut uploads_in_flight, job_status, &mut checkpoint)
+                .await?;
+            file_chunks = remaining_file_chunks;
+
+            let chunk = match next_chunk {
+                None => break,
+                Some(chunk) => chunk?,
+            };
+
+            //chunks acknowledged before a split was interrupted are not sent again
+            if checkpoint.is_chunk_acknowledged(chunk.chunk_sequence_number) {
+                job_status.chunks_uploaded = job_status.chunks_uploaded + 1;
+                continue;
+            }
+
+            //the last chunk tells the receiving service that the file is complete,
+            //so it is only sent once every chunk before it has been acknowledged
+            if chunk.is_last_chunk {
+                self.wait_for_uploads_in_flight(&mut uploads_in_flight, job_status, &mut checkpoint, 0).await?;
+            }
 
-        //upload each chunk
-        for chunk in file_chunks {
-            let _ = self.file_chunks_uploader.upload_file_chunk(&chunk).await?;
+            self.save_job_status(job_status, SplitFileJobPhase::Uploading);
+            let file_chunks_uploader = &self.file_chunks_uploader;
+            let chunk_sequence_number = chunk.chunk_sequence_number;
+            uploads_in_flight.push(async move {
+                file_chunks_uploader.upload_file_chunk(&chunk).await.map(|_| chunk_sequence_number)
+            });
+
+            //make room for the next chunk before it is put together
+            self.wait_for_uploads_in_flight(&mut uploads_in_flight, job_status, &mut checkpoint, max_concurrent_chunk_uploads - 1).await?;
+            self.save_job_status(job_status, SplitFileJobPhase::Chunking);
         }
 
-        //return success
-        return Ok(SplitFileResponse {
-            upload_request_id: file_that_has_been_read.upload_request_id.unwrap_or("".to_string()),
+        self.wait_for_uploads_in_flight(&mut uploads_in_flight, job_status, &mut checkpoint, 0).await?;
+
+        checkpoint.is_complete = true;
+        self.save_checkpoint(&checkpoint).await;
+
+        return Ok(());
+    }
+
+    //reading rows blocks, so the next chunk is put together on a blocking thread
+    //while the uploads in flight carry on and are checkpointed as they are acknowledged.
+    //the stream is handed back with the chunk taken from it, which is none once the stream has ended
+    async fn put_next_chunk_together<F>(
+        &self,
+        mut file_chunks: FileChunkStream,
+        uploads_in_flight: &mut FuturesUnordered<F>,
+        job_status: &mut SplitFileJobStatus,
+        checkpoint: &mut SplitFileCheckpoint,
+    ) -> Result<(FileChunkStream, Option<Result<UploadFileChunkRequest, AppError>>), AppError>
+        where F: Future<Output=Result<i64, AppError>>
+    {
+        let mut chunk_being_put_together = tokio::task::spawn_blocking(move || {
+            let next_chunk = file_chunks.next();
+            return (file_chunks, next_chunk);
         });
+
+        loop {
+            tokio::select! {
+                put_together = &mut chunk_being_put_together => {
+                    return match put_together {
+                        Ok(put_together) => Ok(put_together),
+                        Err(e) => app_error(AppErrorKind::InternalError, Box::new(e)),
+                    };
+                }
+                Some(upload_result) = uploads_in_flight.next(), if !uploads_in_flight.is_empty() => {
+                    self.acknowledge_chunk(upload_result?, job_status, checkpoint).await;
+                }
+            }
+        }
+    }
+
+    //waits until no more than max_uploads_left_in_flight uploads are still running,
+    //checkpointing every chunk that is acknowledged.
+    //the first failed upload is returned and the uploads still in flight are dropped
+    async fn wait_for_uploads_in_flight<F>(
+        &self,
+        uploads_in_flight: &mut FuturesUnordered<F>,
+        job_status: &mut SplitFileJobStatus,
+        checkpoint: &mut SplitFileCheckpoint,
+        max_uploads_left_in_flight: usize,
+    ) -> Result<(), AppError>
+        where F: Future<Output=Result<i64, AppError>>
+    {
+        while uploads_in_flight.len() > max_uploads_left_in_flight {
+            let acknowledged_chunk_sequence_number = match uploads_in_flight.next().await {
+                None => break,
+                Some(upload_result) => upload_result?,
+            };
+
+            self.acknowledge_chunk(acknowledged_chunk_sequence_number, job_status, checkpoint).await;
+        }
+
+        return Ok(());
+    }
+
+    async fn acknowledge_chunk(&self, chunk_sequence_number: i64, job_status: &mut SplitFileJobStatus, checkpoint: &mut SplitFileCheckpoint) {
+        checkpoint.acknowledge_chunk(chunk_sequence_number);
+        self.save_checkpoint(checkpoint).await;
+
+        job_status.chunks_uploaded = job_status.chunks_uploaded + 1;
+        self.job_status_store.save_job_status(job_status);
+    }
+
+    fn save_job_status(&self, job_status: &mut SplitFileJobStatus, phase: SplitFileJobPhase) {
+        job_status.phase = phase;
+        self.job_status_store.save_job_status(job_status);
+    }
+
+    //a checkpoint that fails to save only means a resumed split sends a few chunks again,
+    //so the split carries on
+    async fn save_checkpoint(&self, checkpoint: &SplitFileCheckpoint) {
+        if let Err(e) = self.checkpoint_store.save_checkpoint(checkpoint).await {
+            log::warn!("failed to save split file checkpoint for upload_request_id {}: {}", checkpoint.upload_request_id, e);
+        }
+    }
+
+    async fn release_file(&self, file: &File) {
+        if let Err(e) = self.file_retriever.release_file(file).await {
+            log::warn!("failed to release retrieved file {:?}: {}", file.file_path, e);
+        }
     }
-}
 
-impl SplitFileService {
     async fn attach_file_to_task(&self, file_that_has_been_read: &mut FileThatHasBeenRead) -> Result<(), AppError> {
         //then we attach the file to the recon task
         //depending on the file type
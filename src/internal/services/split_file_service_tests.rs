@@ -1,16 +1,41 @@
-use crate::internal::interfaces::file_chunks_upload_service_connector::MockFileChunksUploadHandlerServiceConnectorInterface;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::internal::interfaces::file_chunks_upload_service_connector::{
+    FileChunksUploadHandlerServiceConnectorInterface,
+    MockFileChunksUploadHandlerServiceConnectorInterface,
+};
 use crate::internal::interfaces::file_reader::MockFileReader;
+use crate::internal::interfaces::file_retriever::MockFileRetriever;
 use crate::internal::interfaces::recon_tasks_service_connector::MockReconTasksServiceConnectorInterface;
+use crate::internal::interfaces::split_file_checkpoint_store::MockSplitFileCheckpointStoreInterface;
+use crate::internal::interfaces::split_file_job_queue::{MockSplitFileJobQueueInterface, SplitFileJobQueueInterface};
+use crate::internal::interfaces::split_file_job_status_store::MockSplitFileJobStatusStoreInterface;
 use crate::internal::interfaces::split_file_service::SplitFileServiceInterface;
 use crate::internal::interfaces::transformer::MockTransformerInterface;
 use crate::internal::models::view_models::requests::split_file_request::SplitFileRequest;
 use crate::internal::models::view_models::responses::split_file_response::SplitFileResponse;
+use crate::internal::services::split_file_job_worker::SplitFileJobQueue;
 use crate::internal::services::split_file_service::SplitFileService;
 use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppError;
 use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
-use crate::internal::shared_reconciler_rust_libraries::models::entities::file::{File, FileMetadata, FileStorageLocation, FileThatHasBeenRead, SupportedFileExtension};
+use crate::internal::models::entities::app_error_reason::AppErrorReason;
+use crate::internal::models::entities::chunk_payload::ChunkPayloadFormat;
+use crate::internal::models::entities::file::FileThatHasBeenRead;
+use crate::internal::models::entities::file_read_options::{CsvReadOptions, FileReadOptions};
+use crate::internal::models::entities::split_file_checkpoint::SplitFileCheckpoint;
+use crate::internal::models::entities::split_file_job::{SplitFileJob, SplitFileJobPhase, SplitFileJobStatus};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::file::{File, FileMetadata, FileStorageLocation, SupportedFileExtension};
 use crate::internal::shared_reconciler_rust_libraries::models::entities::file_upload_chunk::FileUploadChunkSource;
-use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::{ComparisonPair, ReconFileType};
+use crate::internal::models::entities::inferred_file_settings::InferredFileSettings;
+use crate::internal::models::entities::named_comparison_pair::{ColumnReference, NamedComparisonPair};
+use crate::internal::models::entities::reconciliation_options::ReconciliationOptions;
+use crate::internal::models::entities::retrieved_file::RetrievedFile;
+use crate::internal::models::entities::statement_summary::{StatementBalances, StatementSummary};
+use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::{ComparisonPair, ReconciliationConfigs, ReconFileType};
 use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::view_models::requests::UploadFileChunkRequest;
 
 //specifies the request, expected mock responses from dependencies and
@@ -19,9 +44,12 @@ use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservi
 #[derive(Clone, Debug)]
 struct TestSpecifications {
     request: SplitFileRequest,
-    mock_read_file_result: Option<Result<FileThatHasBeenRead, AppError>>,
+    mock_retrieve_file_result: Option<Result<File, AppError>>,
+    mock_read_column_headers_result: Option<Result<Vec<String>, AppError>>,
     mock_create_recon_task_result: Option<Result<String, AppError>>,
     mock_attach_comparison_file_result: Option<Result<String, AppError>>,
+    mock_active_recon_task_with_file: Option<String>,
+    mock_recon_task_comparison_pairs: Vec<ComparisonPair>,
     mock_group_rows_into_file_chunks_result: Option<Vec<UploadFileChunkRequest>>,
     mock_upload_file_chunk_result: Option<Result<(), AppError>>,
     expected_final_result: Result<SplitFileResponse, AppError>,
@@ -32,6 +60,7 @@ struct TestSpecifications {
 #[derive(Clone, Debug)]
 struct ValidRequestsTestScenarios {
     ok_test: TestSpecifications,
+    is_retrieve_file_error_handled: TestSpecifications,
     is_read_file_error_handled: TestSpecifications,
     is_create_recon_task_error_handled: TestSpecifications,
     is_attach_file_to_task_error_handled: TestSpecifications,
@@ -45,8 +74,18 @@ fn test_read_and_split_file_handler_valid_request() {
         ok_test: TestSpecifications {
             ..ok_test_specification.clone()
         },
+        is_retrieve_file_error_handled: TestSpecifications {
+            mock_retrieve_file_result: Some(dummy_error(AppErrorKind::BadClientRequest)),
+            mock_read_column_headers_result: None,
+            mock_create_recon_task_result: None,
+            mock_attach_comparison_file_result: None,
+            mock_group_rows_into_file_chunks_result: None,
+            mock_upload_file_chunk_result: None,
+            expected_final_result: dummy_error(AppErrorKind::BadClientRequest),
+            ..ok_test_specification.clone()
+        },
         is_read_file_error_handled: TestSpecifications {
-            mock_read_file_result: Some(dummy_error(AppErrorKind::InternalError)),
+            mock_read_column_headers_result: Some(dummy_error(AppErrorKind::InternalError)),
             mock_create_recon_task_result: None,
             mock_attach_comparison_file_result: None,
             mock_group_rows_into_file_chunks_result: None,
@@ -84,6 +123,13 @@ fn test_read_and_split_file_handler_valid_request() {
             });
         });
 
+        ctx.when("retrieve file returns an error", |ctx| {
+            ctx.then("method returns the same error", |env| {
+                let resp = setup_service_and_send_request(&env.is_retrieve_file_error_handled.clone());
+                assert_eq!(resp, env.is_retrieve_file_error_handled.expected_final_result.clone())
+            });
+        });
+
         ctx.when("read file returns an error", |ctx| {
             ctx.then("method returns the same error", |env| {
                 let resp = setup_service_and_send_request(&env.is_read_file_error_handled.clone());
@@ -118,9 +164,16 @@ fn test_read_and_split_file_handler_valid_request() {
 fn generate_ok_test_specification() -> TestSpecifications {
     TestSpecifications {
         request: get_dummy_request(),
-        mock_read_file_result: Some(dummy_file_that_has_been_read()),
+        mock_retrieve_file_result: Some(Ok(get_dummy_request().file)),
+        mock_read_column_headers_result: Some(Ok(vec![String::from("id"), String::from("amount")])),
         mock_create_recon_task_result: Some(Ok(String::from("RECON-TASK-1234"))),
         mock_attach_comparison_file_result: Some(Ok(String::from("RECON-TASK-1234"))),
+        mock_active_recon_task_with_file: None,
+        mock_recon_task_comparison_pairs: vec![ComparisonPair {
+            primary_file_column_index: 0,
+            comparison_file_column_index: 0,
+            is_row_identifier: true,
+        }],
         mock_group_rows_into_file_chunks_result: Some(vec![UploadFileChunkRequest {
             upload_request_id: "1234".to_string(),
             chunk_sequence_number: 1,
@@ -131,6 +184,8 @@ fn generate_ok_test_specification() -> TestSpecifications {
         mock_upload_file_chunk_result: Some(Ok(())),
         expected_final_result: Ok(SplitFileResponse {
             upload_request_id: String::from("RECON-TASK-1234"),
+            inferred_settings: None,
+            statement_summary: None,
         }),
     }
 }
@@ -142,44 +197,73 @@ fn dummy_error<V>(app_error_kind: AppErrorKind) -> Result<V, AppError> {
     ))
 }
 
-fn dummy_file_that_has_been_read() -> Result<FileThatHasBeenRead, AppError> {
-    Ok(
-        FileThatHasBeenRead {
-            id: None,
-            upload_request_id: None,
-            file_type: ReconFileType::PrimaryFile,
-            column_headers: vec![],
-            file_rows: vec![],
-            file_metadata: None,
-        })
-}
+fn setup_service_and_send_request(test_specifications: &TestSpecifications) -> Result<SplitFileResponse, AppError> {
+    let sut = setup_service(test_specifications, Arc::new(Mutex::new(vec![])));
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.clone().request));
 
+    return result;
+}
 
-fn setup_service_and_send_request(test_specifications: &TestSpecifications) -> Result<SplitFileResponse, AppError> {
+//the job statuses saved by the service are collected into saved_job_statuses
+fn setup_service(test_specifications: &TestSpecifications, saved_job_statuses: Arc<Mutex<Vec<SplitFileJobStatus>>>) -> SplitFileService {
+    let mut mock_file_retriever = Box::new(MockFileRetriever::new());
     let mut mock_file_reader = Box::new(MockFileReader::new());
     let mut mock_transformer = Box::new(MockTransformerInterface::new());
     let mut mock_file_chunks_uploader = Box::new(MockFileChunksUploadHandlerServiceConnectorInterface::new());
     let mut mock_recon_tasks_repo_handler = Box::new(MockReconTasksServiceConnectorInterface::new());
+    let mut mock_job_queue = Box::new(MockSplitFileJobQueueInterface::new());
+    let mut mock_job_status_store = Box::new(MockSplitFileJobStatusStoreInterface::new());
+    let mut mock_checkpoint_store = Box::new(MockSplitFileCheckpointStoreInterface::new());
 
     //setup mock responses
-    match test_specifications.clone().mock_read_file_result {
+    match test_specifications.clone().mock_retrieve_file_result {
         None => {}
         Some(result) => {
-            mock_file_reader.expect_read_file().returning(move |_y| {
+            mock_file_retriever.expect_retrieve_file().returning(move |_y, _z| {
+                result.clone().map(|file| RetrievedFile { file, file_hash: String::from("FINGERPRINT-1234") })
+            });
+        }
+    }
+
+    mock_file_retriever.expect_release_file().returning(|_y| Ok(()));
+    mock_file_reader.expect_infer_file_settings().returning(|_y, _z| Ok(None));
+    mock_file_reader.expect_read_statement_summary().returning(|_y, _z| Ok(None));
+
+    match test_specifications.clone().mock_read_column_headers_result {
+        None => {}
+        Some(result) => {
+            mock_file_reader.expect_read_column_headers().returning(move |_y, _z| {
                 result.clone()
             });
+            mock_file_reader.expect_count_file_rows().returning(|_y, _z| {
+                Ok(0)
+            });
+            mock_file_reader.expect_stream_file_rows().returning(|_y, _z| {
+                Ok(Box::new(vec![].into_iter()))
+            });
         }
     }
 
     match test_specifications.clone().mock_create_recon_task_result {
         None => {}
         Some(result) => {
-            mock_recon_tasks_repo_handler.expect_create_recon_task().returning(move |_y| {
+            mock_recon_tasks_repo_handler.expect_create_recon_task().returning(move |_y, _z, _w| {
                 result.clone()
             });
         }
     }
 
+    let active_recon_task_with_file = test_specifications.clone().mock_active_recon_task_with_file;
+    mock_recon_tasks_repo_handler.expect_find_active_recon_task_with_file().returning(move |_y| {
+        Ok(active_recon_task_with_file.clone())
+    });
+
+    let recon_task_comparison_pairs = test_specifications.clone().mock_recon_task_comparison_pairs;
+    mock_recon_tasks_repo_handler.expect_get_recon_task_comparison_pairs().returning(move |_y| {
+        Ok(recon_task_comparison_pairs.clone())
+    });
+
     match test_specifications.clone().mock_attach_comparison_file_result {
         None => {}
         Some(result) => {
@@ -192,8 +276,8 @@ fn setup_service_and_send_request(test_specifications: &TestSpecifications) -> R
     match test_specifications.clone().mock_group_rows_into_file_chunks_result {
         None => {}
         Some(result) => {
-            mock_transformer.expect_group_rows_into_file_chunks().returning(move |_y, _x| {
-                result.clone()
+            mock_transformer.expect_group_rows_into_file_chunks().returning(move |_y, _x, _z| {
+                Box::new(result.clone().into_iter().map(Ok))
             });
         }
     }
@@ -207,16 +291,973 @@ fn setup_service_and_send_request(test_specifications: &TestSpecifications) -> R
         }
     }
 
-    let sut = SplitFileService {
+    mock_job_queue.expect_queue_job().returning(|_y| Ok(()));
+
+    mock_job_status_store.expect_save_job_status().returning(move |job_status| {
+        saved_job_statuses.lock().unwrap().push(job_status.clone());
+    });
+
+    mock_job_status_store.expect_get_job_status().returning(|upload_request_id| {
+        match upload_request_id {
+            "RECON-TASK-1234" => Some(SplitFileJobStatus::new(upload_request_id.to_string(), String::from("user@example.com"))),
+            _ => None,
+        }
+    });
+
+    mock_checkpoint_store.expect_save_checkpoint().returning(|_y| Ok(()));
+    mock_checkpoint_store.expect_get_checkpoint().returning(|_y, _z| Ok(None));
+
+    return SplitFileService {
+        file_retriever: mock_file_retriever,
         file_reader: mock_file_reader,
         transformer: mock_transformer,
         file_chunks_uploader: mock_file_chunks_uploader,
         recon_tasks_handler: mock_recon_tasks_repo_handler,
+        job_queue: mock_job_queue,
+        job_status_store: mock_job_status_store,
+        checkpoint_store: mock_checkpoint_store,
+        max_concurrent_chunk_uploads: 1,
+        should_reject_duplicate_files: false,
+        default_reconciliation_configs: get_default_reconciliation_configs(),
+        chunk_payload_format: ChunkPayloadFormat::RawDataV1,
     };
+}
 
-    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.clone().request));
+#[test]
+fn test_queue_file_for_splitting_returns_upload_request_id_before_rows_are_read() {
+    let test_specifications = generate_ok_test_specification();
+    let saved_job_statuses = Arc::new(Mutex::new(vec![]));
+    let sut = setup_service(&test_specifications, saved_job_statuses.clone());
 
-    return result;
+    let result = tokio_test::block_on(sut.queue_file_for_splitting(test_specifications.request.clone()));
+
+    assert_eq!(result.map_err(|e| e.error), test_specifications.expected_final_result);
+    assert_eq!(*saved_job_statuses.lock().unwrap(), vec![SplitFileJobStatus::new(String::from("RECON-TASK-1234"), String::from("user@example.com"))]);
+}
+
+#[test]
+fn test_files_are_turned_away_and_their_job_marked_failed_when_the_job_queue_is_full() {
+    let test_specifications = generate_ok_test_specification();
+    let saved_job_statuses = Arc::new(Mutex::new(vec![]));
+    let mut sut = setup_service(&test_specifications, saved_job_statuses.clone());
+
+    let (job_queue, _job_receiver) = SplitFileJobQueue::new(1);
+    job_queue.queue_job(get_dummy_split_file_job()).unwrap();
+    sut.job_queue = Box::new(job_queue);
+
+    let queue_full_error = tokio_test::block_on(sut.queue_file_for_splitting(test_specifications.request.clone())).unwrap_err();
+
+    assert_eq!(queue_full_error.reason, Some(AppErrorReason::JobQueueFull));
+
+    let saved_job_statuses = saved_job_statuses.lock().unwrap();
+    assert_eq!(saved_job_statuses.last().unwrap().phase, SplitFileJobPhase::Failed);
+    assert_eq!(saved_job_statuses.last().unwrap().failure, Some(queue_full_error.error));
+}
+
+#[test]
+fn test_run_split_file_job_records_progress_until_completed() {
+    let test_specifications = generate_ok_test_specification();
+    let saved_job_statuses = Arc::new(Mutex::new(vec![]));
+    let sut = setup_service(&test_specifications, saved_job_statuses.clone());
+
+    let result = tokio_test::block_on(sut.run_split_file_job(get_dummy_split_file_job()));
+
+    assert!(result.is_ok());
+
+    let saved_job_statuses = saved_job_statuses.lock().unwrap();
+    let phases: Vec<SplitFileJobPhase> = saved_job_statuses.iter().map(|x| x.phase.clone()).collect();
+    assert_eq!(phases, vec![
+        SplitFileJobPhase::Reading,
+        SplitFileJobPhase::Attaching,
+        SplitFileJobPhase::Chunking,
+        SplitFileJobPhase::Uploading,
+        SplitFileJobPhase::Uploading,
+        SplitFileJobPhase::Chunking,
+        SplitFileJobPhase::Completed,
+    ]);
+    assert_eq!(saved_job_statuses.last().unwrap().chunks_uploaded, 1);
+}
+
+#[test]
+fn test_chunks_are_uploaded_concurrently_and_the_last_chunk_is_sent_after_all_others_are_acknowledged() {
+    let test_specifications = TestSpecifications {
+        mock_group_rows_into_file_chunks_result: Some(get_dummy_file_chunks(5)),
+        ..generate_ok_test_specification()
+    };
+    let saved_job_statuses = Arc::new(Mutex::new(vec![]));
+    let upload_events = Arc::new(Mutex::new(vec![]));
+    let max_uploads_in_flight = Arc::new(AtomicUsize::new(0));
+
+    let sut = SplitFileService {
+        file_chunks_uploader: Box::new(SlowFileChunksUploader {
+            upload_events: upload_events.clone(),
+            uploads_in_flight: Arc::new(AtomicUsize::new(0)),
+            max_uploads_in_flight: max_uploads_in_flight.clone(),
+        }),
+        max_concurrent_chunk_uploads: 3,
+        ..setup_service(&test_specifications, saved_job_statuses.clone())
+    };
+
+    let result = tokio_test::block_on(sut.run_split_file_job(get_dummy_split_file_job()));
+
+    assert!(result.is_ok());
+    assert_eq!(max_uploads_in_flight.load(Ordering::SeqCst), 3);
+    assert_eq!(saved_job_statuses.lock().unwrap().last().unwrap().chunks_uploaded, 5);
+
+    let upload_events = upload_events.lock().unwrap();
+    let last_chunk_sent_at = upload_events.iter().position(|x| x == "sent 5").unwrap();
+    for chunk_sequence_number in 1..5 {
+        let acknowledged_at = upload_events.iter().position(|x| *x == format!("acknowledged {}", chunk_sequence_number)).unwrap();
+        assert!(acknowledged_at < last_chunk_sent_at);
+    }
+}
+
+#[test]
+fn test_uploads_in_flight_are_acknowledged_while_the_next_chunk_is_put_together() {
+    let test_specifications = TestSpecifications {
+        mock_group_rows_into_file_chunks_result: Some(get_dummy_file_chunks(3)),
+        ..generate_ok_test_specification()
+    };
+    let saved_job_statuses = Arc::new(Mutex::new(vec![]));
+    let upload_events = Arc::new(Mutex::new(vec![]));
+
+    let mut mock_transformer = Box::new(MockTransformerInterface::new());
+    let chunk_events = upload_events.clone();
+    mock_transformer.expect_group_rows_into_file_chunks().returning(move |_y, _x, _z| {
+        Box::new(SlowFileChunkStream {
+            file_chunks: get_dummy_file_chunks(3).into_iter(),
+            upload_events: chunk_events.clone(),
+        })
+    });
+
+    let sut = SplitFileService {
+        transformer: mock_transformer,
+        file_chunks_uploader: Box::new(SlowFileChunksUploader {
+            upload_events: upload_events.clone(),
+            uploads_in_flight: Arc::new(AtomicUsize::new(0)),
+            max_uploads_in_flight: Arc::new(AtomicUsize::new(0)),
+        }),
+        max_concurrent_chunk_uploads: 3,
+        ..setup_service(&test_specifications, saved_job_statuses.clone())
+    };
+
+    let result = tokio_test::block_on(sut.run_split_file_job(get_dummy_split_file_job()));
+
+    assert!(result.is_ok());
+
+    let upload_events = upload_events.lock().unwrap();
+    let acknowledged_at = upload_events.iter().position(|x| x == "acknowledged 1").unwrap();
+    let put_together_at = upload_events.iter().position(|x| x == "put together 2").unwrap();
+    assert!(acknowledged_at < put_together_at);
+}
+
+//only puts the second chunk together once the first one has been acknowledged,
+//or after a second if the upload of the first chunk is not making progress
+struct SlowFileChunkStream {
+    file_chunks: std::vec::IntoIter<UploadFileChunkRequest>,
+    upload_events: Arc<Mutex<Vec<String>>>,
+}
+
+impl Iterator for SlowFileChunkStream {
+    type Item = Result<UploadFileChunkRequest, AppError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let chunk = self.file_chunks.next()?;
+
+        if chunk.chunk_sequence_number == 2 {
+            for _ in 0..100 {
+                if self.upload_events.lock().unwrap().contains(&"acknowledged 1".to_string()) {
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+
+        self.upload_events.lock().unwrap().push(format!("put together {}", chunk.chunk_sequence_number));
+        return Some(Ok(chunk));
+    }
+}
+
+//takes longer to acknowledge chunks with lower sequence numbers, so
+//chunks are acknowledged out of order when uploaded concurrently
+struct SlowFileChunksUploader {
+    upload_events: Arc<Mutex<Vec<String>>>,
+    uploads_in_flight: Arc<AtomicUsize>,
+    max_uploads_in_flight: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl FileChunksUploadHandlerServiceConnectorInterface for SlowFileChunksUploader {
+    async fn upload_file_chunk(&self, request: &UploadFileChunkRequest) -> Result<(), AppError> {
+        self.upload_events.lock().unwrap().push(format!("sent {}", request.chunk_sequence_number));
+
+        let uploads_in_flight = self.uploads_in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_uploads_in_flight.fetch_max(uploads_in_flight, Ordering::SeqCst);
+
+        tokio::time::sleep(Duration::from_millis(10 * (6 - request.chunk_sequence_number as u64))).await;
+
+        self.uploads_in_flight.fetch_sub(1, Ordering::SeqCst);
+        self.upload_events.lock().unwrap().push(format!("acknowledged {}", request.chunk_sequence_number));
+        return Ok(());
+    }
+}
+
+#[test]
+fn test_run_split_file_job_records_the_failure() {
+    let test_specifications = TestSpecifications {
+        mock_upload_file_chunk_result: Some(dummy_error(AppErrorKind::InternalError)),
+        ..generate_ok_test_specification()
+    };
+    let saved_job_statuses = Arc::new(Mutex::new(vec![]));
+    let sut = setup_service(&test_specifications, saved_job_statuses.clone());
+
+    let result = tokio_test::block_on(sut.run_split_file_job(get_dummy_split_file_job()));
+
+    let last_job_status = saved_job_statuses.lock().unwrap().last().unwrap().clone();
+    assert_eq!(last_job_status.phase, SplitFileJobPhase::Failed);
+    assert_eq!(last_job_status.chunks_uploaded, 0);
+    assert_eq!(Err(last_job_status.failure.unwrap()), result);
+}
+
+#[test]
+fn test_get_split_file_job_status_returns_not_found_for_unknown_jobs() {
+    let sut = setup_service(&generate_ok_test_specification(), Arc::new(Mutex::new(vec![])));
+
+    let found = tokio_test::block_on(sut.get_split_file_job_status(String::from("RECON-TASK-1234"), String::from("user@example.com")));
+    let not_found = tokio_test::block_on(sut.get_split_file_job_status(String::from("RECON-TASK-0000"), String::from("user@example.com")));
+
+    assert!(found.is_ok());
+    assert_eq!(not_found.unwrap_err().kind, AppErrorKind::NotFound);
+}
+
+#[test]
+fn test_get_split_file_job_status_returns_not_found_for_jobs_of_other_callers() {
+    let sut = setup_service(&generate_ok_test_specification(), Arc::new(Mutex::new(vec![])));
+
+    let result = tokio_test::block_on(sut.get_split_file_job_status(String::from("RECON-TASK-1234"), String::from("someone-else@example.com")));
+
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::NotFound);
+}
+
+#[test]
+fn test_resume_splitting_file_only_uploads_chunks_that_were_not_acknowledged() {
+    let test_specifications = TestSpecifications {
+        mock_group_rows_into_file_chunks_result: Some(get_dummy_file_chunks(5)),
+        ..generate_ok_test_specification()
+    };
+    let saved_job_statuses = Arc::new(Mutex::new(vec![]));
+    let uploaded_chunk_sequence_numbers = Arc::new(Mutex::new(vec![]));
+    let saved_checkpoints = Arc::new(Mutex::new(vec![]));
+
+    let mut checkpoint = get_dummy_checkpoint();
+    checkpoint.acknowledge_chunk(1);
+    checkpoint.acknowledge_chunk(2);
+    checkpoint.acknowledge_chunk(4);
+
+    let sut = SplitFileService {
+        file_chunks_uploader: get_recording_file_chunks_uploader(uploaded_chunk_sequence_numbers.clone()),
+        checkpoint_store: get_mock_checkpoint_store(Some(checkpoint), saved_checkpoints.clone()),
+        ..setup_service(&test_specifications, saved_job_statuses.clone())
+    };
+
+    let result = tokio_test::block_on(sut.resume_splitting_file(String::from("RECON-TASK-1234"), get_dummy_request()));
+
+    assert_eq!(result.map_err(|e| e.error), test_specifications.expected_final_result);
+    assert_eq!(*uploaded_chunk_sequence_numbers.lock().unwrap(), vec![3, 5]);
+
+    //a resumed file has already been counted and attached to its recon task
+    let phases: Vec<SplitFileJobPhase> = saved_job_statuses.lock().unwrap().iter().map(|x| x.phase.clone()).collect();
+    assert!(!phases.contains(&SplitFileJobPhase::Reading));
+    assert!(!phases.contains(&SplitFileJobPhase::Attaching));
+
+    let last_checkpoint = saved_checkpoints.lock().unwrap().last().unwrap().clone();
+    assert_eq!(last_checkpoint.last_acknowledged_chunk_sequence_number, 5);
+    assert!(last_checkpoint.is_complete);
+}
+
+#[test]
+fn test_resume_splitting_file_rejects_a_different_file() {
+    let test_specifications = generate_ok_test_specification();
+    let checkpoint = SplitFileCheckpoint {
+        file_fingerprint: String::from("FINGERPRINT-5678"),
+        ..get_dummy_checkpoint()
+    };
+
+    let sut = SplitFileService {
+        checkpoint_store: get_mock_checkpoint_store(Some(checkpoint), Arc::new(Mutex::new(vec![]))),
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.resume_splitting_file(String::from("RECON-TASK-1234"), get_dummy_request()));
+
+    assert_eq!(result.unwrap_err().error.kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_resume_splitting_file_rejects_a_split_that_was_already_completed() {
+    let test_specifications = generate_ok_test_specification();
+    let uploaded_chunk_sequence_numbers = Arc::new(Mutex::new(vec![]));
+    let checkpoint = SplitFileCheckpoint {
+        is_complete: true,
+        ..get_dummy_checkpoint()
+    };
+
+    let sut = SplitFileService {
+        file_chunks_uploader: get_recording_file_chunks_uploader(uploaded_chunk_sequence_numbers.clone()),
+        checkpoint_store: get_mock_checkpoint_store(Some(checkpoint), Arc::new(Mutex::new(vec![]))),
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.resume_splitting_file(String::from("RECON-TASK-1234"), get_dummy_request()));
+
+    assert_eq!(result.unwrap_err().reason, Some(AppErrorReason::SplitAlreadyCompleted));
+    assert!(uploaded_chunk_sequence_numbers.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_resume_splitting_file_returns_not_found_for_a_split_started_by_another_caller() {
+    let test_specifications = generate_ok_test_specification();
+    let uploaded_chunk_sequence_numbers = Arc::new(Mutex::new(vec![]));
+    let checkpoint = SplitFileCheckpoint {
+        user_id: String::from("someone-else@example.com"),
+        ..get_dummy_checkpoint()
+    };
+
+    let sut = SplitFileService {
+        file_chunks_uploader: get_recording_file_chunks_uploader(uploaded_chunk_sequence_numbers.clone()),
+        checkpoint_store: get_mock_checkpoint_store(Some(checkpoint), Arc::new(Mutex::new(vec![]))),
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.resume_splitting_file(String::from("RECON-TASK-1234"), get_dummy_request()));
+
+    assert_eq!(result.unwrap_err().error.kind, AppErrorKind::NotFound);
+    assert!(uploaded_chunk_sequence_numbers.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_resume_splitting_file_returns_not_found_if_no_split_was_started() {
+    let sut = setup_service(&generate_ok_test_specification(), Arc::new(Mutex::new(vec![])));
+
+    let result = tokio_test::block_on(sut.resume_splitting_file(String::from("RECON-TASK-1234"), get_dummy_request()));
+
+    assert_eq!(result.unwrap_err().error.kind, AppErrorKind::NotFound);
+}
+
+#[test]
+fn test_new_splits_checkpoint_every_acknowledged_chunk() {
+    let test_specifications = TestSpecifications {
+        mock_group_rows_into_file_chunks_result: Some(get_dummy_file_chunks(2)),
+        ..generate_ok_test_specification()
+    };
+    let saved_checkpoints = Arc::new(Mutex::new(vec![]));
+
+    let sut = SplitFileService {
+        checkpoint_store: get_mock_checkpoint_store(None, saved_checkpoints.clone()),
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+    assert!(result.is_ok());
+
+    let saved_checkpoints: Vec<(i64, bool)> = saved_checkpoints
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|x| (x.last_acknowledged_chunk_sequence_number, x.is_complete))
+        .collect();
+    assert_eq!(saved_checkpoints, vec![(0, false), (1, false), (2, false), (2, true)]);
+}
+
+#[test]
+fn test_files_already_attached_to_another_active_task_are_rejected_when_duplicates_are_not_allowed() {
+    let test_specifications = TestSpecifications {
+        mock_active_recon_task_with_file: Some(String::from("RECON-TASK-5678")),
+        ..generate_ok_test_specification()
+    };
+
+    let sut = SplitFileService {
+        should_reject_duplicate_files: true,
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::BadClientRequest);
+
+    //the same file can still be attached when duplicates are allowed
+    let sut = setup_service(&test_specifications, Arc::new(Mutex::new(vec![])));
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+    assert_eq!(result, test_specifications.expected_final_result);
+}
+
+#[test]
+fn test_files_only_attached_to_finished_tasks_are_not_duplicates() {
+    let test_specifications = generate_ok_test_specification();
+
+    let sut = SplitFileService {
+        should_reject_duplicate_files: true,
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+    assert_eq!(result, test_specifications.expected_final_result);
+}
+
+#[test]
+fn test_comparison_pairs_referencing_missing_columns_are_rejected_before_the_recon_task_is_created() {
+    let mut test_specifications = generate_ok_test_specification();
+    test_specifications.request.file.file_metadata = Some(FileMetadata {
+        column_delimiters: None,
+        comparison_pairs: Some(vec![
+            ComparisonPair {
+                primary_file_column_index: 0,
+                comparison_file_column_index: 1,
+                is_row_identifier: true,
+            },
+            ComparisonPair {
+                primary_file_column_index: 1,
+                comparison_file_column_index: 2,
+                is_row_identifier: false,
+            },
+        ]),
+    });
+    test_specifications.mock_retrieve_file_result = Some(Ok(test_specifications.request.file.clone()));
+
+    let mut mock_recon_tasks_repo_handler = Box::new(MockReconTasksServiceConnectorInterface::new());
+    mock_recon_tasks_repo_handler.expect_create_recon_task().never();
+
+    let sut = SplitFileService {
+        recon_tasks_handler: mock_recon_tasks_repo_handler,
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+
+    let error = result.unwrap_err();
+    assert_eq!(error.kind, AppErrorKind::BadClientRequest);
+    assert!(error.message.contains("comparison_file_column_index: 2"));
+    assert!(!error.message.contains("comparison_file_column_index: 1"));
+}
+
+#[test]
+fn test_the_second_file_is_rejected_if_the_recon_task_comparison_pairs_reference_columns_it_does_not_have() {
+    let mut test_specifications = TestSpecifications {
+        mock_recon_task_comparison_pairs: vec![
+            ComparisonPair {
+                primary_file_column_index: 0,
+                comparison_file_column_index: 1,
+                is_row_identifier: true,
+            },
+            ComparisonPair {
+                primary_file_column_index: 1,
+                comparison_file_column_index: 2,
+                is_row_identifier: false,
+            },
+        ],
+        ..generate_ok_test_specification()
+    };
+    test_specifications.request.file.upload_request_id = Some(String::from("RECON-TASK-1234"));
+    test_specifications.request.file.file_metadata = None;
+    test_specifications.mock_retrieve_file_result = Some(Ok(test_specifications.request.file.clone()));
+
+    let uploaded_chunk_sequence_numbers = Arc::new(Mutex::new(vec![]));
+    let sut = SplitFileService {
+        file_chunks_uploader: get_recording_file_chunks_uploader(uploaded_chunk_sequence_numbers.clone()),
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+
+    let error = result.unwrap_err();
+    assert_eq!(error.kind, AppErrorKind::BadClientRequest);
+    assert!(error.message.contains("comparison_file_column_index: 2"));
+    assert!(!error.message.contains("comparison_file_column_index: 1"));
+    assert!(uploaded_chunk_sequence_numbers.lock().unwrap().is_empty());
+}
+
+#[test]
+fn test_named_comparison_pairs_are_rejected_for_an_existing_recon_task() {
+    let test_specifications = generate_ok_test_specification();
+    let sut = setup_service(&test_specifications, Arc::new(Mutex::new(vec![])));
+
+    let mut request = SplitFileRequest {
+        named_comparison_pairs: Some(vec![NamedComparisonPair {
+            primary_file_column: ColumnReference::Index(0),
+            comparison_file_column: ColumnReference::Index(0),
+            is_row_identifier: true,
+        }]),
+        ..test_specifications.request.clone()
+    };
+    request.file.upload_request_id = Some(String::from("RECON-TASK-1234"));
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(request));
+
+    let error = result.unwrap_err();
+    assert_eq!(error.kind, AppErrorKind::BadClientRequest);
+    assert!(error.message.contains("named comparison pairs"));
+}
+
+#[test]
+fn test_a_new_recon_job_without_any_comparison_pairs_is_rejected() {
+    let test_specifications = generate_ok_test_specification();
+    let sut = setup_service(&test_specifications, Arc::new(Mutex::new(vec![])));
+
+    let mut request = SplitFileRequest {
+        named_comparison_pairs: Some(vec![]),
+        ..test_specifications.request.clone()
+    };
+    request.file.file_metadata = Some(FileMetadata {
+        column_delimiters: None,
+        comparison_pairs: Some(vec![]),
+    });
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(request));
+
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_named_comparison_pairs_are_resolved_to_column_indexes_before_the_recon_task_is_created() {
+    let mut test_specifications = generate_ok_test_specification();
+    test_specifications.mock_read_column_headers_result = Some(Ok(vec![
+        String::from("Transaction Id"),
+        String::from("Amount"),
+    ]));
+    test_specifications.request.file.file_metadata = None;
+    test_specifications.request.named_comparison_pairs = Some(vec![
+        NamedComparisonPair {
+            primary_file_column: ColumnReference::Index(0),
+            comparison_file_column: ColumnReference::Name { name: String::from(" transactionid "), aliases: vec![] },
+            is_row_identifier: true,
+        },
+        NamedComparisonPair {
+            primary_file_column: ColumnReference::Index(3),
+            comparison_file_column: ColumnReference::Name {
+                name: String::from("value"),
+                aliases: vec![String::from("AMOUNT")],
+            },
+            is_row_identifier: false,
+        },
+    ]);
+    test_specifications.mock_retrieve_file_result = Some(Ok(test_specifications.request.file.clone()));
+    let task_comparison_pairs = Arc::new(Mutex::new(vec![]));
+
+    let mut mock_recon_tasks_repo_handler = Box::new(MockReconTasksServiceConnectorInterface::new());
+    let recorded_comparison_pairs = task_comparison_pairs.clone();
+    mock_recon_tasks_repo_handler.expect_create_recon_task().returning(move |file, _z, _w| {
+        recorded_comparison_pairs.lock().unwrap().push(file.file_metadata.clone().unwrap().comparison_pairs.unwrap());
+        Ok(String::from("RECON-TASK-1234"))
+    });
+    mock_recon_tasks_repo_handler.expect_attach_comparison_file_to_task().returning(|_y| Ok(String::from("RECON-TASK-1234")));
+
+    let sut = SplitFileService {
+        recon_tasks_handler: mock_recon_tasks_repo_handler,
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+
+    assert!(result.is_ok());
+    assert_eq!(*task_comparison_pairs.lock().unwrap(), vec![vec![
+        ComparisonPair {
+            primary_file_column_index: 0,
+            comparison_file_column_index: 0,
+            is_row_identifier: true,
+        },
+        ComparisonPair {
+            primary_file_column_index: 3,
+            comparison_file_column_index: 1,
+            is_row_identifier: false,
+        },
+    ]]);
+}
+
+#[test]
+fn test_named_comparison_pairs_that_are_missing_ambiguous_or_for_the_other_file_are_rejected() {
+    let mut test_specifications = generate_ok_test_specification();
+    test_specifications.mock_read_column_headers_result = Some(Ok(vec![
+        String::from("id"),
+        String::from("Amount"),
+        String::from("amount "),
+    ]));
+    test_specifications.request.file.file_metadata = None;
+    test_specifications.mock_retrieve_file_result = Some(Ok(test_specifications.request.file.clone()));
+
+    let named_comparison_pairs_and_problems = vec![
+        (ColumnReference::Index(0), ColumnReference::Name { name: String::from("date"), aliases: vec![] }, "no ComparisonFile column header matches"),
+        (ColumnReference::Index(0), ColumnReference::Name { name: String::from("amount"), aliases: vec![] }, "match more than one ComparisonFile column header"),
+        (ColumnReference::Name { name: String::from("id"), aliases: vec![] }, ColumnReference::Index(0), "please supply its column index instead"),
+    ];
+
+    for (primary_file_column, comparison_file_column, expected_problem) in named_comparison_pairs_and_problems {
+        let mut mock_recon_tasks_repo_handler = Box::new(MockReconTasksServiceConnectorInterface::new());
+        mock_recon_tasks_repo_handler.expect_create_recon_task().never();
+
+        let sut = SplitFileService {
+            recon_tasks_handler: mock_recon_tasks_repo_handler,
+            ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+        };
+
+        let request = SplitFileRequest {
+            named_comparison_pairs: Some(vec![NamedComparisonPair {
+                primary_file_column,
+                comparison_file_column,
+                is_row_identifier: true,
+            }]),
+            ..test_specifications.request.clone()
+        };
+
+        let error = tokio_test::block_on(sut.read_and_split_file_into_chunks(request)).unwrap_err();
+
+        assert_eq!(error.kind, AppErrorKind::BadClientRequest);
+        assert!(error.message.contains(expected_problem), "{}", error.message);
+    }
+}
+
+#[test]
+fn test_settings_inferred_from_the_file_are_used_to_read_it_and_returned() {
+    let test_specifications = generate_ok_test_specification();
+    let inferred_settings = InferredFileSettings {
+        column_delimiter: Some(String::from(";")),
+        quote_character: Some('\''),
+        has_header_row: Some(false),
+        encoding: Some(String::from("utf-8")),
+    };
+    let attached_column_delimiters = Arc::new(Mutex::new(vec![]));
+
+    let mut mock_file_reader = Box::new(MockFileReader::new());
+    let returned_inferred_settings = inferred_settings.clone();
+    mock_file_reader.expect_infer_file_settings().returning(move |_y, _z| Ok(Some(returned_inferred_settings.clone())));
+    mock_file_reader.expect_read_statement_summary().returning(|_y, _z| Ok(None));
+    mock_file_reader
+        .expect_read_column_headers()
+        .withf(|file, read_options| {
+            let csv_read_options = read_options.csv.clone().unwrap();
+            file.file_metadata.clone().unwrap().column_delimiters == Some(vec![';'])
+                && csv_read_options.quote_character == Some('\'')
+                && csv_read_options.has_header_row == Some(false)
+        })
+        .returning(|_y, _z| Ok(vec![String::from("column_1"), String::from("column_2")]));
+    mock_file_reader.expect_count_file_rows().returning(|_y, _z| Ok(0));
+    mock_file_reader.expect_stream_file_rows().returning(|_y, _z| Ok(Box::new(vec![].into_iter())));
+
+    let mut mock_recon_tasks_repo_handler = Box::new(MockReconTasksServiceConnectorInterface::new());
+    mock_recon_tasks_repo_handler.expect_create_recon_task().returning(|_y, _z, _w| Ok(String::from("RECON-TASK-1234")));
+    let recorded_column_delimiters = attached_column_delimiters.clone();
+    mock_recon_tasks_repo_handler.expect_attach_comparison_file_to_task().returning(move |file| {
+        recorded_column_delimiters.lock().unwrap().push(file.file_metadata.clone().unwrap().column_delimiters);
+        Ok(String::from("RECON-TASK-1234"))
+    });
+
+    let sut = SplitFileService {
+        file_reader: mock_file_reader,
+        recon_tasks_handler: mock_recon_tasks_repo_handler,
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+
+    assert_eq!(result.unwrap().inferred_settings, Some(inferred_settings));
+    assert_eq!(*attached_column_delimiters.lock().unwrap(), vec![Some(vec![';'])]);
+}
+
+#[test]
+fn test_csv_delimiters_the_file_chunks_service_cannot_split_on_need_chunk_payload_version_2() {
+    let test_specifications = generate_ok_test_specification();
+
+    for csv_read_options in [
+        CsvReadOptions { column_delimiters: Some(vec![String::from("||"), String::from(";")]), ..CsvReadOptions::default() },
+        CsvReadOptions { column_delimiter_pattern: Some(String::from("\\s*;\\s*")), ..CsvReadOptions::default() },
+    ] {
+        let request = SplitFileRequest {
+            read_options: Some(FileReadOptions { csv: Some(csv_read_options), ..FileReadOptions::default() }),
+            ..test_specifications.request.clone()
+        };
+
+        let mut mock_file_retriever = Box::new(MockFileRetriever::new());
+        mock_file_retriever.expect_retrieve_file().never();
+        let raw_data_sut = SplitFileService {
+            file_retriever: mock_file_retriever,
+            ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+        };
+        let structured_cells_sut = SplitFileService {
+            chunk_payload_format: ChunkPayloadFormat::StructuredCellsV2,
+            ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+        };
+
+        let raw_data_result = tokio_test::block_on(raw_data_sut.read_and_split_file_into_chunks(request.clone()));
+        let structured_cells_result = tokio_test::block_on(structured_cells_sut.read_and_split_file_into_chunks(request));
+
+        assert_eq!(raw_data_result.unwrap_err().kind, AppErrorKind::BadClientRequest);
+        assert_eq!(structured_cells_result, test_specifications.expected_final_result);
+    }
+}
+
+#[test]
+fn test_single_character_csv_delimiters_are_sent_when_the_file_is_attached() {
+    let test_specifications = generate_ok_test_specification();
+    let request = SplitFileRequest {
+        read_options: Some(FileReadOptions {
+            csv: Some(CsvReadOptions { column_delimiters: Some(vec![String::from("|"), String::from(";")]), ..CsvReadOptions::default() }),
+            ..FileReadOptions::default()
+        }),
+        ..test_specifications.request.clone()
+    };
+    let attached_column_delimiters = Arc::new(Mutex::new(vec![]));
+
+    let mut mock_file_retriever = Box::new(MockFileRetriever::new());
+    mock_file_retriever.expect_retrieve_file().returning(|file, _z| Ok(RetrievedFile { file, file_hash: String::from("FINGERPRINT-1234") }));
+    mock_file_retriever.expect_release_file().returning(|_y| Ok(()));
+
+    let mut mock_recon_tasks_repo_handler = Box::new(MockReconTasksServiceConnectorInterface::new());
+    mock_recon_tasks_repo_handler.expect_create_recon_task().returning(|_y, _z, _w| Ok(String::from("RECON-TASK-1234")));
+    let recorded_column_delimiters = attached_column_delimiters.clone();
+    mock_recon_tasks_repo_handler.expect_attach_comparison_file_to_task().returning(move |file| {
+        recorded_column_delimiters.lock().unwrap().push(file.file_metadata.clone().unwrap().column_delimiters);
+        Ok(String::from("RECON-TASK-1234"))
+    });
+
+    let sut = SplitFileService {
+        file_retriever: mock_file_retriever,
+        recon_tasks_handler: mock_recon_tasks_repo_handler,
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(request));
+
+    assert!(result.is_ok());
+    assert_eq!(*attached_column_delimiters.lock().unwrap(), vec![Some(vec!['|', ';'])]);
+}
+
+#[test]
+fn test_statement_balances_read_from_the_file_are_returned() {
+    let test_specifications = generate_ok_test_specification();
+    let statement_summary = StatementSummary {
+        statements: vec![StatementBalances {
+            account_identification: Some(String::from("BE68539007547034")),
+            ..StatementBalances::default()
+        }],
+    };
+
+    let mut mock_file_reader = Box::new(MockFileReader::new());
+    let returned_statement_summary = statement_summary.clone();
+    mock_file_reader.expect_infer_file_settings().returning(|_y, _z| Ok(None));
+    mock_file_reader.expect_read_statement_summary().returning(move |_y, _z| Ok(Some(returned_statement_summary.clone())));
+    mock_file_reader.expect_read_column_headers().returning(|_y, _z| Ok(vec![String::from("id"), String::from("amount")]));
+    mock_file_reader.expect_count_file_rows().returning(|_y, _z| Ok(0));
+    mock_file_reader.expect_stream_file_rows().returning(|_y, _z| Ok(Box::new(vec![].into_iter())));
+
+    let sut = SplitFileService {
+        file_reader: mock_file_reader,
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+
+    assert_eq!(result.unwrap().statement_summary, Some(statement_summary));
+}
+
+#[test]
+fn test_file_hash_is_sent_when_attaching_the_file() {
+    let test_specifications = generate_ok_test_specification();
+    let attached_file_hashes = Arc::new(Mutex::new(vec![]));
+
+    let mut mock_recon_tasks_repo_handler = Box::new(MockReconTasksServiceConnectorInterface::new());
+    mock_recon_tasks_repo_handler.expect_create_recon_task().returning(|_y, _z, _w| Ok(String::from("RECON-TASK-1234")));
+
+    let recorded_file_hashes = attached_file_hashes.clone();
+    mock_recon_tasks_repo_handler.expect_attach_comparison_file_to_task().returning(move |file| {
+        recorded_file_hashes.lock().unwrap().push(file.file_hash.clone());
+        Ok(String::from("RECON-TASK-1234"))
+    });
+
+    let sut = SplitFileService {
+        recon_tasks_handler: mock_recon_tasks_repo_handler,
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+
+    assert!(result.is_ok());
+    assert_eq!(*attached_file_hashes.lock().unwrap(), vec![String::from("FINGERPRINT-1234")]);
+}
+
+#[test]
+fn test_recon_task_is_created_for_the_authenticated_caller() {
+    let test_specifications = generate_ok_test_specification();
+    let task_user_ids = Arc::new(Mutex::new(vec![]));
+
+    let mut mock_recon_tasks_repo_handler = Box::new(MockReconTasksServiceConnectorInterface::new());
+    let recorded_user_ids = task_user_ids.clone();
+    mock_recon_tasks_repo_handler.expect_create_recon_task().returning(move |_y, user_id, _w| {
+        recorded_user_ids.lock().unwrap().push(user_id.to_string());
+        Ok(String::from("RECON-TASK-1234"))
+    });
+    mock_recon_tasks_repo_handler.expect_attach_comparison_file_to_task().returning(|_y| Ok(String::from("RECON-TASK-1234")));
+
+    let sut = SplitFileService {
+        recon_tasks_handler: mock_recon_tasks_repo_handler,
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(test_specifications.request.clone()));
+
+    assert!(result.is_ok());
+    assert_eq!(*task_user_ids.lock().unwrap(), vec![String::from("user@example.com")]);
+}
+
+#[test]
+fn test_recon_task_is_not_created_without_a_caller() {
+    let test_specifications = generate_ok_test_specification();
+    let sut = setup_service(&test_specifications, Arc::new(Mutex::new(vec![])));
+
+    let request = SplitFileRequest {
+        user_id: None,
+        ..test_specifications.request.clone()
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(request));
+
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_recon_task_is_created_with_the_supplied_reconciliation_options_over_the_defaults() {
+    let test_specifications = generate_ok_test_specification();
+    let task_recon_configurations = Arc::new(Mutex::new(vec![]));
+
+    let mut mock_recon_tasks_repo_handler = Box::new(MockReconTasksServiceConnectorInterface::new());
+    let recorded_recon_configurations = task_recon_configurations.clone();
+    mock_recon_tasks_repo_handler.expect_create_recon_task().returning(move |_y, _z, recon_configurations| {
+        recorded_recon_configurations.lock().unwrap().push(recon_configurations.clone());
+        Ok(String::from("RECON-TASK-1234"))
+    });
+    mock_recon_tasks_repo_handler.expect_attach_comparison_file_to_task().returning(|_y| Ok(String::from("RECON-TASK-1234")));
+
+    let sut = SplitFileService {
+        recon_tasks_handler: mock_recon_tasks_repo_handler,
+        ..setup_service(&test_specifications, Arc::new(Mutex::new(vec![])))
+    };
+
+    let request = SplitFileRequest {
+        reconciliation_options: Some(ReconciliationOptions {
+            should_reconciliation_be_case_sensitive: Some(true),
+            should_ignore_white_space: Some(false),
+            ..ReconciliationOptions::default()
+        }),
+        ..test_specifications.request.clone()
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(request));
+
+    assert!(result.is_ok());
+    assert_eq!(*task_recon_configurations.lock().unwrap(), vec![ReconciliationConfigs {
+        should_check_for_duplicate_records_in_comparison_file: true,
+        should_reconciliation_be_case_sensitive: true,
+        should_ignore_white_space: false,
+        should_do_reverse_reconciliation: false,
+    }]);
+}
+
+#[test]
+fn test_reconciliation_options_are_rejected_for_an_existing_recon_task() {
+    let test_specifications = generate_ok_test_specification();
+    let sut = setup_service(&test_specifications, Arc::new(Mutex::new(vec![])));
+
+    let mut request = SplitFileRequest {
+        reconciliation_options: Some(ReconciliationOptions {
+            should_do_reverse_reconciliation: Some(true),
+            ..ReconciliationOptions::default()
+        }),
+        ..test_specifications.request.clone()
+    };
+    request.file.upload_request_id = Some(String::from("RECON-TASK-1234"));
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(request));
+
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+#[test]
+fn test_reconciliation_options_that_set_nothing_are_rejected() {
+    let test_specifications = generate_ok_test_specification();
+    let sut = setup_service(&test_specifications, Arc::new(Mutex::new(vec![])));
+
+    let request = SplitFileRequest {
+        reconciliation_options: Some(ReconciliationOptions::default()),
+        ..test_specifications.request.clone()
+    };
+
+    let result = tokio_test::block_on(sut.read_and_split_file_into_chunks(request));
+
+    assert_eq!(result.unwrap_err().kind, AppErrorKind::BadClientRequest);
+}
+
+fn get_default_reconciliation_configs() -> ReconciliationConfigs {
+    ReconciliationConfigs {
+        should_check_for_duplicate_records_in_comparison_file: true,
+        should_reconciliation_be_case_sensitive: false,
+        should_ignore_white_space: true,
+        should_do_reverse_reconciliation: false,
+    }
+}
+
+fn get_dummy_file_chunks(number_of_chunks: i64) -> Vec<UploadFileChunkRequest> {
+    (1..=number_of_chunks).map(|chunk_sequence_number| UploadFileChunkRequest {
+        upload_request_id: "RECON-TASK-1234".to_string(),
+        chunk_sequence_number,
+        chunk_source: FileUploadChunkSource::ComparisonFileChunk,
+        chunk_rows: vec![],
+        is_last_chunk: chunk_sequence_number == number_of_chunks,
+    }).collect()
+}
+
+fn get_dummy_checkpoint() -> SplitFileCheckpoint {
+    SplitFileCheckpoint::new(
+        String::from("RECON-TASK-1234"),
+        String::from("user@example.com"),
+        ReconFileType::ComparisonFile,
+        String::from("FINGERPRINT-1234"),
+    )
+}
+
+fn get_mock_checkpoint_store(
+    checkpoint: Option<SplitFileCheckpoint>,
+    saved_checkpoints: Arc<Mutex<Vec<SplitFileCheckpoint>>>,
+) -> Box<MockSplitFileCheckpointStoreInterface> {
+    let mut mock_checkpoint_store = Box::new(MockSplitFileCheckpointStoreInterface::new());
+    mock_checkpoint_store.expect_get_checkpoint().returning(move |_y, _z| Ok(checkpoint.clone()));
+    mock_checkpoint_store.expect_save_checkpoint().returning(move |checkpoint| {
+        saved_checkpoints.lock().unwrap().push(checkpoint.clone());
+        Ok(())
+    });
+    return mock_checkpoint_store;
+}
+
+fn get_recording_file_chunks_uploader(uploaded_chunk_sequence_numbers: Arc<Mutex<Vec<i64>>>) -> Box<MockFileChunksUploadHandlerServiceConnectorInterface> {
+    let mut mock_file_chunks_uploader = Box::new(MockFileChunksUploadHandlerServiceConnectorInterface::new());
+    mock_file_chunks_uploader.expect_upload_file_chunk().returning(move |chunk| {
+        uploaded_chunk_sequence_numbers.lock().unwrap().push(chunk.chunk_sequence_number);
+        Ok(())
+    });
+    return mock_file_chunks_uploader;
+}
+
+fn get_dummy_split_file_job() -> SplitFileJob {
+    let file = get_dummy_request().file;
+    let mut file_that_has_been_read = FileThatHasBeenRead::new(&file, vec![String::from("id"), String::from("amount")], String::from("FINGERPRINT-1234"));
+    file_that_has_been_read.upload_request_id = Some(String::from("RECON-TASK-1234"));
+
+    SplitFileJob {
+        file,
+        user_id: String::from("user@example.com"),
+        read_options: FileReadOptions::default(),
+        file_that_has_been_read,
+        resume_from_checkpoint: None,
+        inferred_settings: None,
+        statement_summary: None,
+    }
 }
 
 fn get_dummy_request() -> SplitFileRequest {
@@ -237,5 +1278,10 @@ fn get_dummy_request() -> SplitFileRequest {
             file_path: Some("E:/Work/test.csv".to_string()),
             file_type: ReconFileType::ComparisonFile,
         },
+        file_storage: None,
+        read_options: None,
+        reconciliation_options: None,
+        named_comparison_pairs: None,
+        user_id: Some("user@example.com".to_string()),
     }
 }
\ No newline at end of file
@@ -1,23 +1,127 @@
+use std::collections::HashMap;
+
 use actix_web::{
+    get,
+    HttpRequest,
     HttpResponse,
     post,
     web::{self, Data},
 };
+use serde::Serialize;
 
 use crate::internal::{
-    interfaces::split_file_service::SplitFileServiceInterface,
-    models::view_models::requests::split_file_request::SplitFileRequest,
+    interfaces::{
+        caller_authenticator::CallerAuthenticatorInterface,
+        split_file_service::SplitFileServiceInterface,
+    },
+    models::{
+        entities::app_error_reason::{AppErrorReason, AppErrorWithReason},
+        view_models::requests::{
+            read_file_query_params::ReadFileQueryParams,
+            split_file_request::SplitFileRequest,
+        },
+    },
     shared_reconciler_rust_libraries::web_api::utils::ok_or_error,
 };
 
 #[post("/read-file")]
 pub async fn read_file(
-    task_details: web::Json<SplitFileRequest>,
+    request: HttpRequest,
+    mut task_details: web::Json<SplitFileRequest>,
+    query_params: web::Query<ReadFileQueryParams>,
     service: Data<Box<dyn SplitFileServiceInterface>>,
+    authenticator: Data<Box<dyn CallerAuthenticatorInterface>>,
 ) -> HttpResponse {
+    task_details.user_id = match authenticate_caller(&request, &authenticator) {
+        Ok(user_id) => Some(user_id),
+        Err(unauthorized_response) => return unauthorized_response,
+    };
+
+    if query_params.process_in_background {
+        let response = service
+            .queue_file_for_splitting(task_details.0)
+            .await;
+
+        //the file is still being split, progress can be followed on the status endpoint
+        if let Ok(accepted_response) = &response {
+            return HttpResponse::Accepted().json(accepted_response);
+        }
+
+        return to_http_response(response);
+    }
+
     let response = service
         .read_and_split_file_into_chunks(task_details.0)
         .await;
 
     return ok_or_error(response);
 }
+
+//resubmits a file whose split was interrupted, only the chunks that were not acknowledged are uploaded
+#[post("/read-file/{upload_request_id}/resume")]
+pub async fn resume_read_file(
+    request: HttpRequest,
+    path: web::Path<String>,
+    mut task_details: web::Json<SplitFileRequest>,
+    service: Data<Box<dyn SplitFileServiceInterface>>,
+    authenticator: Data<Box<dyn CallerAuthenticatorInterface>>,
+) -> HttpResponse {
+    task_details.user_id = match authenticate_caller(&request, &authenticator) {
+        Ok(user_id) => Some(user_id),
+        Err(unauthorized_response) => return unauthorized_response,
+    };
+
+    let response = service
+        .resume_splitting_file(path.into_inner(), task_details.0)
+        .await;
+
+    return to_http_response(response);
+}
+
+#[get("/read-file/{upload_request_id}/status")]
+pub async fn get_read_file_status(
+    request: HttpRequest,
+    path: web::Path<String>,
+    service: Data<Box<dyn SplitFileServiceInterface>>,
+    authenticator: Data<Box<dyn CallerAuthenticatorInterface>>,
+) -> HttpResponse {
+    let user_id = match authenticate_caller(&request, &authenticator) {
+        Ok(user_id) => user_id,
+        Err(unauthorized_response) => return unauthorized_response,
+    };
+
+    let response = service
+        .get_split_file_job_status(path.into_inner(), user_id)
+        .await;
+
+    return ok_or_error(response);
+}
+
+//the user_id of the caller, or a 401 response if the caller could not be identified
+fn authenticate_caller(
+    request: &HttpRequest,
+    authenticator: &Data<Box<dyn CallerAuthenticatorInterface>>,
+) -> Result<String, HttpResponse> {
+    let request_headers: HashMap<String, String> = request
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| value.to_str().ok().map(|value| (name.as_str().to_lowercase(), value.to_string())))
+        .collect();
+
+    return authenticator
+        .authenticate_caller(&request_headers)
+        .map_err(|e| HttpResponse::Unauthorized().json(e));
+}
+
+//errors with a reason of their own get the status code that fits them, the rest are mapped by their kind
+fn to_http_response<T: Serialize>(response: Result<T, AppErrorWithReason>) -> HttpResponse {
+    if let Err(e) = &response {
+        match e.reason {
+            Some(AppErrorReason::JobQueueFull) => return HttpResponse::ServiceUnavailable().json(&e.error),
+            Some(AppErrorReason::SplitAlreadyCompleted) => return HttpResponse::Conflict().json(&e.error),
+            None => {}
+        }
+    }
+
+    return ok_or_error(response.map_err(|e| e.error));
+}
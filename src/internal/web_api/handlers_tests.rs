@@ -9,6 +9,7 @@ use actix_web::body::BoxBody;
 use actix_web::dev::ServiceResponse;
 use actix_web::http::StatusCode;
 
+use crate::internal::interfaces::caller_authenticator::{CallerAuthenticatorInterface, MockCallerAuthenticatorInterface};
 use crate::internal::interfaces::split_file_service::MockSplitFileServiceInterface;
 use crate::internal::interfaces::split_file_service::SplitFileServiceInterface;
 use crate::internal::models::view_models::requests::split_file_request::SplitFileRequest;
@@ -17,12 +18,15 @@ use crate::internal::shared_reconciler_rust_libraries::models::entities::app_err
 use crate::internal::shared_reconciler_rust_libraries::models::entities::app_errors::AppErrorKind;
 use crate::internal::shared_reconciler_rust_libraries::models::entities::file::{File, FileStorageLocation, SupportedFileExtension};
 use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::ReconFileType;
-use crate::internal::web_api::handlers::read_file;
+use crate::internal::models::entities::app_error_reason::AppErrorReason;
+use crate::internal::models::entities::split_file_job::{SplitFileJobPhase, SplitFileJobStatus};
+use crate::internal::web_api::handlers::{get_read_file_status, read_file, resume_read_file};
 
 //good request, bad client request, internal server error
 #[derive(Clone, Debug)]
 struct TestSpecifications {
     request: SplitFileRequest,
+    process_in_background: bool,
     mock_service_response: Result<SplitFileResponse, AppError>,
     expected_status_code: StatusCode,
 }
@@ -43,13 +47,17 @@ fn test_read_and_split_file_handler_valid_request() {
     let valid_request_test_suite = ValidRequestTestScenarios {
         ok_test: TestSpecifications {
             request: get_dummy_request(),
+            process_in_background: false,
             mock_service_response: Ok(SplitFileResponse {
                 upload_request_id: "FILE-1234".to_string(),
+                inferred_settings: None,
+                statement_summary: None,
             }),
             expected_status_code: StatusCode::OK,
         },
         internal_server_error_test: TestSpecifications {
             request: get_dummy_request(),
+            process_in_background: false,
             mock_service_response: get_dummy_error(AppErrorKind::InternalError),
             expected_status_code: StatusCode::INTERNAL_SERVER_ERROR,
         },
@@ -78,6 +86,7 @@ fn test_read_and_split_file_handler_invalid_request() {
     let invalid_request_test_suite = InvalidRequestTestScenarios {
         invalid_client_request: TestSpecifications {
             request: get_dummy_request(),
+            process_in_background: false,
             mock_service_response: get_dummy_error(AppErrorKind::BadClientRequest),
             expected_status_code: StatusCode::BAD_REQUEST,
         },
@@ -93,6 +102,210 @@ fn test_read_and_split_file_handler_invalid_request() {
     }));
 }
 
+#[test]
+fn test_read_file_handler_returns_400_for_unknown_or_non_boolean_reconciliation_options() {
+    let mut app = tokio_test::block_on(test::init_service((move || {
+        let mut mock_service = Box::new(MockSplitFileServiceInterface::new());
+        mock_service.expect_read_and_split_file_into_chunks().never();
+        let mock_service: Box<dyn SplitFileServiceInterface> = mock_service;
+
+        App::new()
+            .app_data(Data::new(mock_service))
+            .app_data(Data::new(get_mock_authenticator()))
+            .service(read_file)
+    })()));
+
+    for reconciliation_options in [
+        serde_json::json!({ "should_ignore_whitespace": true }),
+        serde_json::json!({ "should_ignore_white_space": "yes" }),
+        serde_json::json!({ "should_do_reverse_reconciliation": 1 }),
+    ] {
+        let mut request = serde_json::to_value(get_dummy_request()).unwrap();
+        request["reconciliation_options"] = reconciliation_options;
+
+        let resp = tokio_test::block_on(TestRequest::post()
+            .uri("/read-file")
+            .insert_header(("Authorization", "Bearer VALID-TOKEN"))
+            .set_json(request)
+            .send_request(&mut app));
+        assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+    }
+}
+
+#[test]
+fn test_read_file_handler_in_background_returns_202() {
+    let test_specifications = TestSpecifications {
+        request: get_dummy_request(),
+        process_in_background: true,
+        mock_service_response: get_dummy_error(AppErrorKind::InternalError),
+        expected_status_code: StatusCode::ACCEPTED,
+    };
+
+    let resp = setup_server_and_send_request(&test_specifications);
+
+    assert_eq!(resp.status(), test_specifications.expected_status_code);
+}
+
+#[test]
+fn test_read_file_handler_returns_503_when_the_job_queue_is_full() {
+    let mut app = tokio_test::block_on(test::init_service((move || {
+        let mut mock_service = Box::new(MockSplitFileServiceInterface::new());
+        mock_service
+            .expect_queue_file_for_splitting()
+            .returning(|_y| AppErrorReason::JobQueueFull.app_error(AppErrorKind::InternalError, None));
+        let mock_service: Box<dyn SplitFileServiceInterface> = mock_service;
+
+        App::new()
+            .app_data(Data::new(mock_service))
+            .app_data(Data::new(get_mock_authenticator()))
+            .service(read_file)
+    })()));
+
+    let resp = tokio_test::block_on(TestRequest::post()
+        .uri("/read-file?process_in_background=true")
+        .insert_header(("Authorization", "Bearer VALID-TOKEN"))
+        .set_json(get_dummy_request())
+        .send_request(&mut app));
+    assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[test]
+fn test_get_read_file_status_handler() {
+    let mut app = tokio_test::block_on(test::init_service((move || {
+        let mock_service = get_mock_service_response(Arc::new(Mutex::new(get_dummy_error(AppErrorKind::InternalError))));
+
+        App::new()
+            .app_data(Data::new(mock_service))
+            .app_data(Data::new(get_mock_authenticator()))
+            .service(get_read_file_status)
+    })()));
+
+    let found_resp = tokio_test::block_on(TestRequest::get()
+        .uri("/read-file/FILE-1234/status")
+        .insert_header(("Authorization", "Bearer VALID-TOKEN"))
+        .send_request(&mut app));
+    assert_eq!(found_resp.status(), StatusCode::OK);
+
+    let job_status: serde_json::Value = tokio_test::block_on(test::read_body_json(found_resp));
+    assert_eq!(job_status["phase"], "Uploading");
+    assert_eq!(job_status["chunks_uploaded"], 3);
+
+    let not_found_resp = tokio_test::block_on(TestRequest::get()
+        .uri("/read-file/FILE-0000/status")
+        .insert_header(("Authorization", "Bearer VALID-TOKEN"))
+        .send_request(&mut app));
+    assert_eq!(not_found_resp.status(), StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn test_resume_read_file_handler() {
+    let mut app = tokio_test::block_on(test::init_service((move || {
+        let mock_service = get_mock_service_response(Arc::new(Mutex::new(get_dummy_error(AppErrorKind::InternalError))));
+
+        App::new()
+            .app_data(Data::new(mock_service))
+            .app_data(Data::new(get_mock_authenticator()))
+            .service(resume_read_file)
+    })()));
+
+    let resp = tokio_test::block_on(TestRequest::post()
+        .uri("/read-file/FILE-1234/resume")
+        .insert_header(("Authorization", "Bearer VALID-TOKEN"))
+        .set_json(get_dummy_request())
+        .send_request(&mut app));
+    assert_eq!(resp.status(), StatusCode::OK);
+
+    let response: SplitFileResponse = tokio_test::block_on(test::read_body_json(resp));
+    assert_eq!(response.upload_request_id, "FILE-1234");
+}
+
+#[test]
+fn test_resume_read_file_handler_returns_409_when_the_split_was_already_completed() {
+    let mut app = tokio_test::block_on(test::init_service((move || {
+        let mut mock_service = Box::new(MockSplitFileServiceInterface::new());
+        mock_service
+            .expect_resume_splitting_file()
+            .returning(|_y, _z| AppErrorReason::SplitAlreadyCompleted.app_error(AppErrorKind::BadClientRequest, None));
+        let mock_service: Box<dyn SplitFileServiceInterface> = mock_service;
+
+        App::new()
+            .app_data(Data::new(mock_service))
+            .app_data(Data::new(get_mock_authenticator()))
+            .service(resume_read_file)
+    })()));
+
+    let resp = tokio_test::block_on(TestRequest::post()
+        .uri("/read-file/FILE-1234/resume")
+        .insert_header(("Authorization", "Bearer VALID-TOKEN"))
+        .set_json(get_dummy_request())
+        .send_request(&mut app));
+    assert_eq!(resp.status(), StatusCode::CONFLICT);
+}
+
+#[test]
+fn test_handlers_return_401_for_unauthenticated_callers() {
+    let mut app = tokio_test::block_on(test::init_service((move || {
+        let mut mock_service = Box::new(MockSplitFileServiceInterface::new());
+        mock_service.expect_read_and_split_file_into_chunks().never();
+        mock_service.expect_get_split_file_job_status().never();
+        let mock_service: Box<dyn SplitFileServiceInterface> = mock_service;
+
+        App::new()
+            .app_data(Data::new(mock_service))
+            .app_data(Data::new(get_mock_authenticator()))
+            .service(read_file)
+            .service(get_read_file_status)
+    })()));
+
+    let read_file_resp = tokio_test::block_on(TestRequest::post()
+        .uri("/read-file")
+        .set_json(get_dummy_request())
+        .send_request(&mut app));
+    assert_eq!(read_file_resp.status(), StatusCode::UNAUTHORIZED);
+
+    let status_resp = tokio_test::block_on(TestRequest::get()
+        .uri("/read-file/FILE-1234/status")
+        .insert_header(("Authorization", "Bearer EXPIRED-TOKEN"))
+        .send_request(&mut app));
+    assert_eq!(status_resp.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[test]
+fn test_read_file_handler_passes_the_authenticated_user_id_to_the_service() {
+    let mut app = tokio_test::block_on(test::init_service((move || {
+        let mut mock_service = Box::new(MockSplitFileServiceInterface::new());
+        mock_service
+            .expect_read_and_split_file_into_chunks()
+            .withf(|request| request.user_id == Some("user@example.com".to_string()))
+            .returning(|_y| Ok(SplitFileResponse { upload_request_id: "FILE-1234".to_string(), inferred_settings: None, statement_summary: None }));
+        let mock_service: Box<dyn SplitFileServiceInterface> = mock_service;
+
+        App::new()
+            .app_data(Data::new(mock_service))
+            .app_data(Data::new(get_mock_authenticator()))
+            .service(read_file)
+    })()));
+
+    let resp = tokio_test::block_on(TestRequest::post()
+        .uri("/read-file")
+        .insert_header(("Authorization", "Bearer VALID-TOKEN"))
+        .set_json(get_dummy_request())
+        .send_request(&mut app));
+    assert_eq!(resp.status(), StatusCode::OK);
+}
+
+//only the bearer token VALID-TOKEN is accepted, and it belongs to user@example.com
+fn get_mock_authenticator() -> Box<dyn CallerAuthenticatorInterface> {
+    let mut mock_authenticator = Box::new(MockCallerAuthenticatorInterface::new());
+    mock_authenticator.expect_authenticate_caller().returning(|request_headers| {
+        match request_headers.get("authorization").map(|value| value.as_str()) {
+            Some("Bearer VALID-TOKEN") => Ok("user@example.com".to_string()),
+            _ => Err(AppError::new(AppErrorKind::BadClientRequest, "unauthenticated".to_string())),
+        }
+    });
+    return mock_authenticator;
+}
+
 fn get_dummy_error(app_error_kind: AppErrorKind) -> Result<SplitFileResponse, AppError> {
     Err(AppError::new(
         app_error_kind, "error occurred".to_string(),
@@ -108,11 +321,13 @@ fn setup_server_and_send_request(test_specifications: &TestSpecifications) -> Se
 
         App::new()
             .app_data(Data::new(mock_service)) // add shared state
+            .app_data(Data::new(get_mock_authenticator()))
             .service(read_file)
     })()));
 
     let resp = tokio_test::block_on(TestRequest::post()
-        .uri(&format!("/read-file"))
+        .uri(&format!("/read-file?process_in_background={}", test_specifications.process_in_background))
+        .insert_header(("Authorization", "Bearer VALID-TOKEN"))
         .set_json(test_specifications.request.clone())
         .send_request(&mut app));
 
@@ -128,6 +343,26 @@ fn get_mock_service_response(service_response: Arc<Mutex<Result<SplitFileRespons
         }
         return Err(resp.err().clone().unwrap());
     });
+    mock_service.expect_queue_file_for_splitting().returning(|_y| {
+        Ok(SplitFileResponse {
+            upload_request_id: "FILE-1234".to_string(),
+            inferred_settings: None,
+            statement_summary: None,
+        })
+    });
+    mock_service.expect_resume_splitting_file().returning(|upload_request_id, _y| {
+        Ok(SplitFileResponse { upload_request_id, inferred_settings: None, statement_summary: None })
+    });
+    mock_service.expect_get_split_file_job_status().returning(|upload_request_id, user_id| {
+        match (upload_request_id.as_str(), user_id.as_str()) {
+            ("FILE-1234", "user@example.com") => Ok(SplitFileJobStatus {
+                phase: SplitFileJobPhase::Uploading,
+                chunks_uploaded: 3,
+                ..SplitFileJobStatus::new(upload_request_id, user_id)
+            }),
+            _ => Err(AppError::new(AppErrorKind::NotFound, "error occurred".to_string())),
+        }
+    });
     return mock_service;
 }
 
@@ -142,6 +377,11 @@ fn get_dummy_request() -> SplitFileRequest {
             file_path: Some("E:/Work/test.csv".to_string()),
             file_type: ReconFileType::ComparisonFile,
         },
+        file_storage: None,
+        read_options: None,
+        reconciliation_options: None,
+        named_comparison_pairs: None,
+        user_id: None,
     }
 }
 
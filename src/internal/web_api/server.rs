@@ -1,22 +1,43 @@
+use std::sync::Arc;
+use std::time::Duration;
+
 use actix_web::{App, HttpServer, web::Data};
 
 use crate::{
-    external::connectors::{
-        file_chunks_upload_service_connector::FileChunksUploadHandlerServiceConnector,
-        recon_tasks_service_connector::ReconTasksServiceConnector,
+    external::{
+        authenticators::caller_authenticator::{AuthSettings, CallerAuthenticator},
+        connectors::{
+            file_chunks_upload_service_connector::FileChunksUploadHandlerServiceConnector,
+            recon_tasks_service_connector::ReconTasksServiceConnector,
+        },
+        connectors::retry_policy::RetryPolicy,
+        stores::{
+            dapr_split_file_checkpoint_store::DaprSplitFileCheckpointStore,
+            file_split_file_checkpoint_store::FileSplitFileCheckpointStore,
+            in_memory_split_file_job_status_store::InMemorySplitFileJobStatusStore,
+        },
     },
     internal::{
-        interfaces::split_file_service::SplitFileServiceInterface,
+        interfaces::{
+            caller_authenticator::CallerAuthenticatorInterface,
+            split_file_checkpoint_store::SplitFileCheckpointStoreInterface,
+            split_file_service::SplitFileServiceInterface,
+        },
         services::{
             core_logic::transformer::Transformer,
+            split_file_job_worker::{run_split_file_job_worker, SplitFileJobQueue},
             split_file_service::SplitFileService,
         },
         web_api::handlers,
     },
 };
 use crate::external::readers::factory::FileReaderFactory;
-use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::file_upload_handler_microservice::FileChunksUploadHandlerMicroserviceClient;
-use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::interfaces::file_upload_handler_microservice::FileChunksUploadHandlerMicroserviceClientInterface;
+use crate::external::readers::parsed_files::ParsedFileCache;
+use crate::internal::models::entities::chunk_payload::ChunkPayloadFormat;
+use crate::external::retrievers::download_client::{DownloadClient, DownloadSettings};
+use crate::external::retrievers::factory::FileRetrieverFactory;
+use crate::external::retrievers::s3::S3Settings;
+use crate::internal::shared_reconciler_rust_libraries::models::entities::recon_tasks_models::ReconciliationConfigs;
 use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::interfaces::recon_tasks_microservice::ReconTasksMicroserviceClientInterface;
 use crate::internal::shared_reconciler_rust_libraries::sdks::internal_microservices::recon_tasks_microservice::ReconTasksMicroserviceClient;
 
@@ -27,6 +48,17 @@ const DEFAULT_APP_LISTEN_IP: &'static str = "0.0.0.0";
 const DEFAULT_APP_LISTEN_PORT: u16 = 8082;
 const DEFAULT_FILE_CHUNKS_UPLOAD_SERVICE_NAME: &'static str = "svc-file-chunks-upload-manager";
 const DEFAULT_RECON_TASKS_SERVICE_NAME: &'static str = "svc-task-details-repository-manager";
+const DEFAULT_FILE_DOWNLOADS_DIRECTORY_NAME: &'static str = "svc-file-reader-processor-downloads";
+const DEFAULT_S3_REGION: &'static str = "us-east-1";
+const DEFAULT_MAX_CONCURRENT_CHUNK_UPLOADS: usize = 4;
+const DEFAULT_SPLIT_FILE_CHECKPOINTS_DIRECTORY_NAME: &'static str = "svc-file-reader-processor-checkpoints";
+const DEFAULT_DAPR_CONNECTION_URL: &'static str = "http://localhost:3500";
+const DAPR_CHECKPOINT_STORE_TYPE: &'static str = "dapr";
+const FILE_CHECKPOINT_STORE_TYPE: &'static str = "file";
+const DEFAULT_AUTH_USER_ID_CLAIM: &'static str = "sub";
+const DEFAULT_SPLIT_FILE_JOB_QUEUE_CAPACITY: usize = 100;
+const DEFAULT_FINISHED_JOB_STATUS_TIME_TO_LIVE_SECONDS: u64 = 60 * 60;
+const DEFAULT_MAX_FINISHED_JOB_STATUSES: usize = 10_000;
 
 #[derive(Clone, Debug)]
 struct AppSettings {
@@ -41,38 +73,126 @@ struct AppSettings {
     pub file_chunks_uploader_service_name: String,
 
     pub recon_tasks_service_name: String,
+
+    pub file_downloads_directory: String,
+
+    pub local_files_directory: Option<String>,
+
+    pub s3_settings: Option<S3Settings>,
+
+    pub download_settings: DownloadSettings,
+
+    pub max_concurrent_chunk_uploads: usize,
+
+    pub chunk_upload_retry_policy: RetryPolicy,
+
+    pub checkpoint_store_type: String,
+
+    pub checkpoints_directory: String,
+
+    pub dapr_connection_url: String,
+
+    pub dapr_state_store_name: Option<String>,
+
+    pub should_reject_duplicate_files: bool,
+
+    pub auth_settings: AuthSettings,
+
+    pub default_reconciliation_configs: ReconciliationConfigs,
+
+    pub chunk_payload_format: ChunkPayloadFormat,
+
+    pub split_file_job_queue_capacity: usize,
+
+    pub finished_job_status_time_to_live: Duration,
+
+    pub max_finished_job_statuses: usize,
 }
 
 pub async fn run_async() -> Result<(), std::io::Error> {
-    //retrieve app settings from the env variables
-    let app_settings = read_app_settings();
+    //retrieve app settings from the env variables, a setting that is set to something that makes no sense stops the app from starting
+    let app_settings = read_app_settings()?;
+
+    //a checkpoint store that is asked for but cannot be set up is a misconfiguration, so the app refuses to start
+    check_checkpoint_store_settings(&app_settings)?;
+
+    //a JWKS that cannot be loaded is a misconfiguration, so the app refuses to start
+    let caller_authenticator = CallerAuthenticator::new(app_settings.auth_settings.clone()).map_err(|e| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("failed to load the JWKS used to authenticate callers: {}", e.message),
+        )
+    })?;
+
+    if app_settings.local_files_directory.is_none() {
+        log::warn!("LOCAL_FILES_DIRECTORY is not set, so local files are read from any path on this service's disk");
+    }
+
+    //every file is downloaded with the same client, so its connections are shared by every handler thread and the background worker
+    let download_client = DownloadClient::new(app_settings.download_settings.clone()).map_err(|e| {
+        std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("failed to set up the client files are downloaded with: {}", e.message),
+        )
+    })?;
 
     let app_listen_url = format!("{}:{}", app_settings.app_ip, app_settings.app_port);
 
     //just for logging purposes
-    println!("App is listening on: {:?}", app_listen_url);
+    log::info!("App is listening on: {:?}", app_listen_url);
+
+    //job statuses are shared by every handler thread and the background worker
+    let job_status_store = InMemorySplitFileJobStatusStore::new(
+        app_settings.finished_job_status_time_to_live,
+        app_settings.max_finished_job_statuses,
+    );
+    let (job_queue, job_receiver) = SplitFileJobQueue::new(app_settings.split_file_job_queue_capacity);
+
+    //a file is prepared by a handler thread and split by the background worker, so they share the files parsed in full
+    let parsed_files = ParsedFileCache::default();
+
+    //files accepted for background processing are split by a single worker
+    let worker_service: Arc<dyn SplitFileServiceInterface> = Arc::from(setup_service(
+        app_settings.clone(),
+        job_queue.clone(),
+        job_status_store.clone(),
+        parsed_files.clone(),
+        download_client.clone(),
+    ));
+    actix_web::rt::spawn(run_split_file_job_worker(worker_service, job_receiver));
+
 
     HttpServer::new(move || {
         // Create some global state prior to running the handler threads
-        let service = setup_service(app_settings.clone());
+        let service = setup_service(
+            app_settings.clone(),
+            job_queue.clone(),
+            job_status_store.clone(),
+            parsed_files.clone(),
+            download_client.clone(),
+        );
+        let authenticator: Box<dyn CallerAuthenticatorInterface> = Box::new(caller_authenticator.clone());
 
         // add shared state and routing
         App::new()
             .app_data(Data::new(service))
+            .app_data(Data::new(authenticator))
             .service(handlers::read_file)
+            .service(handlers::resume_read_file)
+            .service(handlers::get_read_file_status)
     })
         .bind(app_listen_url)?
         .run()
         .await
 }
 
-fn setup_service(app_settings: AppSettings) -> Box<dyn SplitFileServiceInterface> {
-    let file_chunks_ms: Box<dyn FileChunksUploadHandlerMicroserviceClientInterface> = Box::new(FileChunksUploadHandlerMicroserviceClient {
-        host: app_settings.file_chunks_uploader_service_connection_url.clone(),
-        file_chunks_service_app_id: app_settings
-            .file_chunks_uploader_service_name
-            .clone(),
-    });
+fn setup_service(
+    app_settings: AppSettings,
+    job_queue: SplitFileJobQueue,
+    job_status_store: InMemorySplitFileJobStatusStore,
+    parsed_files: ParsedFileCache,
+    download_client: DownloadClient,
+) -> Box<dyn SplitFileServiceInterface> {
     let recon_tasks_ms: Box<dyn ReconTasksMicroserviceClientInterface> = Box::new(ReconTasksMicroserviceClient {
         host: app_settings.recon_tasks_service_connection_url.clone(),
         recon_tasks_service_app_id: app_settings
@@ -82,16 +202,66 @@ fn setup_service(app_settings: AppSettings) -> Box<dyn SplitFileServiceInterface
 
 
     let service: Box<dyn SplitFileServiceInterface> = Box::new(SplitFileService {
-        transformer: Box::new(Transformer {}),
-        file_reader: Box::new(FileReaderFactory {}),
-        file_chunks_uploader: Box::new(FileChunksUploadHandlerServiceConnector::new(file_chunks_ms)),
-        recon_tasks_handler: Box::new(ReconTasksServiceConnector::new(recon_tasks_ms)),
+        file_retriever: Box::new(FileRetrieverFactory::new(
+            app_settings.file_downloads_directory.clone(),
+            app_settings.local_files_directory.clone(),
+            app_settings.s3_settings.clone(),
+            download_client,
+        )),
+        transformer: Box::new(Transformer {
+            chunk_payload_format: app_settings.chunk_payload_format,
+        }),
+        file_reader: Box::new(FileReaderFactory { parsed_files }),
+        file_chunks_uploader: Box::new(FileChunksUploadHandlerServiceConnector::new(
+            &app_settings.file_chunks_uploader_service_connection_url,
+            &app_settings.file_chunks_uploader_service_name,
+            app_settings.chunk_upload_retry_policy.clone(),
+            app_settings.chunk_payload_format,
+        )),
+        recon_tasks_handler: Box::new(ReconTasksServiceConnector::new(
+            recon_tasks_ms,
+            &app_settings.recon_tasks_service_connection_url,
+            &app_settings.recon_tasks_service_name,
+        )),
+        job_queue: Box::new(job_queue),
+        job_status_store: Box::new(job_status_store),
+        checkpoint_store: setup_checkpoint_store(&app_settings),
+        max_concurrent_chunk_uploads: app_settings.max_concurrent_chunk_uploads,
+        should_reject_duplicate_files: app_settings.should_reject_duplicate_files,
+        default_reconciliation_configs: app_settings.default_reconciliation_configs.clone(),
+        chunk_payload_format: app_settings.chunk_payload_format,
     });
     service
 }
 
-fn read_app_settings() -> AppSettings {
-    AppSettings {
+//checkpoints are kept on the local disk unless the Dapr state store is asked for, in which case it must be named
+fn check_checkpoint_store_settings(app_settings: &AppSettings) -> Result<(), std::io::Error> {
+    return match (app_settings.checkpoint_store_type.as_str(), &app_settings.dapr_state_store_name) {
+        ("", _) | (FILE_CHECKPOINT_STORE_TYPE, _) | (DAPR_CHECKPOINT_STORE_TYPE, Some(_)) => Ok(()),
+        (DAPR_CHECKPOINT_STORE_TYPE, None) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            "SPLIT_FILE_CHECKPOINT_STORE is dapr but DAPR_STATE_STORE_NAME is not set",
+        )),
+        (checkpoint_store_type, _) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("unknown SPLIT_FILE_CHECKPOINT_STORE {}, expected {} or {}", checkpoint_store_type, FILE_CHECKPOINT_STORE_TYPE, DAPR_CHECKPOINT_STORE_TYPE),
+        )),
+    };
+}
+
+//checkpoints are kept on the local disk unless a Dapr state store has been configured
+fn setup_checkpoint_store(app_settings: &AppSettings) -> Box<dyn SplitFileCheckpointStoreInterface> {
+    match (app_settings.checkpoint_store_type.as_str(), app_settings.dapr_state_store_name.clone()) {
+        (DAPR_CHECKPOINT_STORE_TYPE, Some(state_store_name)) => Box::new(DaprSplitFileCheckpointStore::new(
+            app_settings.dapr_connection_url.clone(),
+            state_store_name,
+        )),
+        _ => Box::new(FileSplitFileCheckpointStore::new(app_settings.checkpoints_directory.clone())),
+    }
+}
+
+fn read_app_settings() -> Result<AppSettings, std::io::Error> {
+    Ok(AppSettings {
         app_port: DEFAULT_APP_LISTEN_PORT.to_string(),
 
         app_ip: std::env::var("APP_IP").unwrap_or(DEFAULT_APP_LISTEN_IP.to_string()),
@@ -107,5 +277,182 @@ fn read_app_settings() -> AppSettings {
 
         recon_tasks_service_name: std::env::var("RECON_TASKS_SERVICE_NAME")
             .unwrap_or(DEFAULT_RECON_TASKS_SERVICE_NAME.to_string()),
+
+        file_downloads_directory: std::env::var("FILE_DOWNLOADS_DIRECTORY")
+            .unwrap_or(std::env::temp_dir().join(DEFAULT_FILE_DOWNLOADS_DIRECTORY_NAME).to_string_lossy().to_string()),
+
+        //files on the service's own disk can only be read from inside this directory, when it is set
+        local_files_directory: std::env::var("LOCAL_FILES_DIRECTORY").ok(),
+
+        s3_settings: read_s3_settings(),
+
+        download_settings: read_download_settings(),
+
+        max_concurrent_chunk_uploads: std::env::var("MAX_CONCURRENT_CHUNK_UPLOADS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_MAX_CONCURRENT_CHUNK_UPLOADS),
+
+        chunk_upload_retry_policy: read_chunk_upload_retry_policy(),
+
+        checkpoint_store_type: std::env::var("SPLIT_FILE_CHECKPOINT_STORE").unwrap_or_default(),
+
+        checkpoints_directory: std::env::var("SPLIT_FILE_CHECKPOINTS_DIRECTORY")
+            .unwrap_or(std::env::temp_dir().join(DEFAULT_SPLIT_FILE_CHECKPOINTS_DIRECTORY_NAME).to_string_lossy().to_string()),
+
+        dapr_connection_url: std::env::var("DAPR_CONNECTION_URL")
+            .unwrap_or(DEFAULT_DAPR_CONNECTION_URL.to_string()),
+
+        dapr_state_store_name: std::env::var("DAPR_STATE_STORE_NAME").ok(),
+
+        should_reject_duplicate_files: read_bool_setting("REJECT_DUPLICATE_FILES"),
+
+        auth_settings: read_auth_settings(),
+
+        default_reconciliation_configs: read_default_reconciliation_configs(),
+
+        chunk_payload_format: read_chunk_payload_format()?,
+
+        //files sent for background processing are turned away once this many are waiting
+        split_file_job_queue_capacity: std::env::var("SPLIT_FILE_JOB_QUEUE_CAPACITY")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .filter(|capacity| *capacity > 0)
+            .unwrap_or(DEFAULT_SPLIT_FILE_JOB_QUEUE_CAPACITY),
+
+        finished_job_status_time_to_live: std::env::var("FINISHED_JOB_STATUS_TIME_TO_LIVE_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(Duration::from_secs(DEFAULT_FINISHED_JOB_STATUS_TIME_TO_LIVE_SECONDS)),
+
+        max_finished_job_statuses: std::env::var("MAX_FINISHED_JOB_STATUSES")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_MAX_FINISHED_JOB_STATUSES),
+    })
+}
+
+//rows are sent as raw text unless a newer payload version is asked for. a version this app
+//does not know would otherwise send rows in a format the file chunks service does not expect
+fn read_chunk_payload_format() -> Result<ChunkPayloadFormat, std::io::Error> {
+    let payload_version = match std::env::var("CHUNK_PAYLOAD_VERSION") {
+        Ok(payload_version) => payload_version,
+        Err(_) => return Ok(ChunkPayloadFormat::default()),
+    };
+
+    return match payload_version.trim().parse().ok().and_then(ChunkPayloadFormat::from_version) {
+        Some(chunk_payload_format) => Ok(chunk_payload_format),
+        None => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("unknown CHUNK_PAYLOAD_VERSION {}, expected 1 or 2", payload_version),
+        )),
+    };
+}
+
+//new recon tasks use these settings for anything the caller does not supply
+fn read_default_reconciliation_configs() -> ReconciliationConfigs {
+    ReconciliationConfigs {
+        should_check_for_duplicate_records_in_comparison_file: read_bool_setting("DEFAULT_SHOULD_CHECK_FOR_DUPLICATE_RECORDS_IN_COMPARISON_FILE"),
+        should_reconciliation_be_case_sensitive: read_bool_setting("DEFAULT_SHOULD_RECONCILIATION_BE_CASE_SENSITIVE"),
+        should_ignore_white_space: read_bool_setting("DEFAULT_SHOULD_IGNORE_WHITE_SPACE"),
+        should_do_reverse_reconciliation: read_bool_setting("DEFAULT_SHOULD_DO_REVERSE_RECONCILIATION"),
+    }
+}
+
+//a setting is only switched on if it is set to true
+fn read_bool_setting(env_variable_name: &str) -> bool {
+    std::env::var(env_variable_name)
+        .map(|value| value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+//callers are identified by a JWT bearer token, or by a header set by a trusted sidecar
+fn read_auth_settings() -> AuthSettings {
+    AuthSettings {
+        jwt_secret: std::env::var("AUTH_JWT_SECRET").ok(),
+        jwks_file_path: std::env::var("AUTH_JWKS_PATH").ok(),
+        jwt_issuer: std::env::var("AUTH_JWT_ISSUER").ok(),
+        jwt_audience: std::env::var("AUTH_JWT_AUDIENCE").ok(),
+        user_id_claim: std::env::var("AUTH_USER_ID_CLAIM").unwrap_or(DEFAULT_AUTH_USER_ID_CLAIM.to_string()),
+        trusted_user_id_header: std::env::var("AUTH_TRUSTED_USER_ID_HEADER").ok(),
     }
 }
+
+//chunk uploads are retried with the default policy unless it is overridden
+fn read_chunk_upload_retry_policy() -> RetryPolicy {
+    let default_retry_policy = RetryPolicy::default();
+
+    RetryPolicy {
+        max_attempts: std::env::var("CHUNK_UPLOAD_MAX_ATTEMPTS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default_retry_policy.max_attempts),
+
+        initial_backoff: std::env::var("CHUNK_UPLOAD_INITIAL_BACKOFF_MILLIS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(default_retry_policy.initial_backoff),
+
+        max_backoff: std::env::var("CHUNK_UPLOAD_MAX_BACKOFF_MILLIS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_millis)
+            .unwrap_or(default_retry_policy.max_backoff),
+
+        ..default_retry_policy
+    }
+}
+
+//files are downloaded with the default limits unless they are overridden. the configured S3 endpoint
+//is trusted, so objects can be fetched from a store on the internal network such as MinIO
+fn read_download_settings() -> DownloadSettings {
+    let default_download_settings = DownloadSettings::default();
+
+    let mut allowed_private_hosts: Vec<String> = std::env::var("FILE_DOWNLOAD_ALLOWED_PRIVATE_HOSTS")
+        .unwrap_or_default()
+        .split(',')
+        .map(|host| host.trim().to_string())
+        .filter(|host| !host.is_empty())
+        .collect();
+    if let Some(s3_endpoint_host) = read_s3_settings()
+        .and_then(|s3_settings| reqwest::Url::parse(&s3_settings.endpoint_url).ok())
+        .and_then(|endpoint_url| endpoint_url.host_str().map(|host| host.to_string()))
+    {
+        allowed_private_hosts.push(s3_endpoint_host);
+    }
+
+    DownloadSettings {
+        connect_timeout: std::env::var("FILE_DOWNLOAD_CONNECT_TIMEOUT_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(default_download_settings.connect_timeout),
+
+        read_timeout: std::env::var("FILE_DOWNLOAD_READ_TIMEOUT_SECONDS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or(default_download_settings.read_timeout),
+
+        max_file_size: std::env::var("MAX_FILE_DOWNLOAD_BYTES")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default_download_settings.max_file_size),
+
+        allowed_private_hosts,
+    }
+}
+
+//S3 retrieval is only switched on when an endpoint has been configured
+fn read_s3_settings() -> Option<S3Settings> {
+    let endpoint_url = std::env::var("S3_ENDPOINT_URL").ok()?;
+
+    Some(S3Settings {
+        endpoint_url,
+        region: std::env::var("S3_REGION").unwrap_or(DEFAULT_S3_REGION.to_string()),
+        access_key_id: std::env::var("S3_ACCESS_KEY_ID").unwrap_or_default(),
+        secret_access_key: std::env::var("S3_SECRET_ACCESS_KEY").unwrap_or_default(),
+    })
+}
@@ -5,5 +5,8 @@ use crate::internal::web_api::server;
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    //logs at info and above unless RUST_LOG asks for something else
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
     server::run_async().await
 }